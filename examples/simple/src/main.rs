@@ -1,13 +1,18 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-use egui::epaint::{Color32, CubicBezierShape, Shape, Stroke};
+use egui::epaint::{Color32, Shape};
 use egui::panel::TopBottomSide;
-use egui::{Context, DragValue, Response, Rounding, Style, Ui, Visuals, Widget, WidgetText};
+use egui::{
+    Context, DragValue, PointerButton, Pos2, Response, Rounding, Style, Ui, Visuals, Widget,
+    WidgetText,
+};
 use egui_shape_editor::shape_editor::constraints::Constraint;
 use egui_shape_editor::shape_editor::style::Light;
 use egui_shape_editor::shape_editor::{
-    ParamType, ParamValue, ShapeEditorBuilder, ShapeEditorOptions, ShapePointIndex,
+    GridMode, ParamType, ParamValue, ScrollAction, ShapeEditorBuilder, ShapeEditorOptions,
+    SnapTargetKind, Unit, UnitOptions,
 };
+#[cfg(target_arch = "wasm32")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::ops::{BitOrAssign, RangeInclusive};
 
@@ -150,10 +155,110 @@ impl eframe::App for App {
                     ui.separator();
                     ui.checkbox(&mut options.snap_enabled_by_default, "Snap enabled");
                     ui.add_enabled_ui(options.snap_enabled_by_default, |ui| {
-                        ui.add(DragValue::new(&mut options.snap_distance).clamp_range(0..=100));
+                        ui.add(
+                            options
+                                .units
+                                .drag_value(&mut options.snap_distance)
+                                .clamp_range(0.0..=options.units.to_unit(100.0)),
+                        );
+                        ui.collapsing("Snap to", |ui| {
+                            for kind in [
+                                SnapTargetKind::GridPrimary,
+                                SnapTargetKind::GridSecondary,
+                                SnapTargetKind::GridSub,
+                                SnapTargetKind::ControlPoints,
+                                SnapTargetKind::Geometry,
+                                SnapTargetKind::Angle,
+                            ] {
+                                ui.checkbox(
+                                    &mut options.snap.target_mut(kind).enabled,
+                                    kind.to_string(),
+                                );
+                            }
+                            ui.checkbox(&mut options.snap.path_points_only, "PathPointsOnly");
+                        });
+                    });
+                    ui.checkbox(&mut options.drag_to_create_shapes, "Drag to create");
+                    ui.checkbox(&mut options.show_minimap, "Minimap");
+                    ui.collapsing("Pointer", |ui| {
+                        let bindings = &mut options.pointer_bindings;
+                        let mut middle_pan = bindings.pan_buttons.contains(&PointerButton::Middle);
+                        if ui.checkbox(&mut middle_pan, "Middle drag pan").changed() {
+                            bindings
+                                .pan_buttons
+                                .retain(|button| *button != PointerButton::Middle);
+                            if middle_pan {
+                                bindings.pan_buttons.push(PointerButton::Middle);
+                            }
+                        }
+                        ui.checkbox(&mut bindings.pan_with_space_drag, "Space drag pan");
+                        ui.radio_value(&mut bindings.scroll, ScrollAction::Pan, "Scroll pans");
+                        ui.radio_value(&mut bindings.scroll, ScrollAction::Zoom, "Scroll zooms");
+                        ui.add(
+                            egui::Slider::new(&mut options.auto_scroll_speed, 0.0..=50.0)
+                                .text("Auto-scroll speed"),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button("Fit").clicked() {
+                            editor.zoom_to_fit(ctx, options.zoom_to_fit_margin);
+                        }
+                        if ui.button("100%").clicked() {
+                            editor.zoom_to(ctx, 1.0, Pos2::ZERO);
+                        }
+                        if ui.button("Sel").clicked() {
+                            editor.zoom_to_selection(ctx, options.zoom_to_fit_margin);
+                        }
+                    });
+                    ui.label(format!("Zoom: {:.0}%", editor.current_zoom(ctx) * 100.0));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut options.grid.mode, GridMode::Rectangular, "Rect");
+                        ui.radio_value(&mut options.grid.mode, GridMode::Isometric, "Iso");
+                        ui.radio_value(
+                            &mut options.grid.mode,
+                            GridMode::Polar { spokes: 12 },
+                            "Polar",
+                        );
+                    });
+                    ui.collapsing("Units", |ui| {
+                        egui::ComboBox::from_id_source("unit")
+                            .selected_text(options.units.unit.to_string())
+                            .show_ui(ui, |ui| {
+                                for unit in [Unit::Px, Unit::Mm, Unit::Cm, Unit::In, Unit::Pt] {
+                                    ui.selectable_value(
+                                        &mut options.units.unit,
+                                        unit,
+                                        unit.to_string(),
+                                    );
+                                }
+                            });
+                        ui.add(
+                            DragValue::new(&mut options.units.dpi)
+                                .clamp_range(1.0..=1200.0)
+                                .suffix(" dpi"),
+                        );
+                        ui.add(
+                            DragValue::new(&mut options.units.precision)
+                                .clamp_range(0..=6)
+                                .prefix("Precision: "),
+                        );
+                        let units = options.units.clone();
+                        ui.add(
+                            units
+                                .drag_value(&mut options.grid.spacing.x)
+                                .clamp_range(units.to_unit(1.0)..=units.to_unit(1000.0))
+                                .prefix("Grid X: "),
+                        );
+                        ui.add(
+                            units
+                                .drag_value(&mut options.grid.spacing.y)
+                                .clamp_range(units.to_unit(1.0)..=units.to_unit(1000.0))
+                                .prefix("Grid Y: "),
+                        );
                     });
                     ui.separator();
                     ui.label("Parameters:");
+                    let units = options.units.clone();
                     let params = editor.selection_shapes_params(ctx);
                     let mut common_params = params.common();
                     let mut changed = false;
@@ -168,6 +273,7 @@ impl eframe::App for App {
                                     val,
                                     options.stroke.width,
                                     0.0..=50.0,
+                                    &units,
                                     "Width: ",
                                 ))
                                 .changed(),
@@ -194,8 +300,14 @@ impl eframe::App for App {
                                     .changed(),
                             ),
                             ParamType::Radius => changed.bitor_assign(
-                                ui.add(float_param_widget(val, 50.0, 0.0..=10000.0, "Radius: "))
-                                    .changed(),
+                                ui.add(float_param_widget(
+                                    val,
+                                    50.0,
+                                    0.0..=10000.0,
+                                    &units,
+                                    "Radius: ",
+                                ))
+                                .changed(),
                             ),
                             ParamType::Texture => {}
                         });
@@ -234,7 +346,7 @@ fn color_param_widget<'a, L: Into<WidgetText> + 'a>(
         ui.add_enabled_ui(enabled, |ui| {
             response.bitor_assign(ui.color_edit_button_srgba(&mut color));
         });
-        *value = enabled.then(|| ParamValue::Color(color));
+        *value = enabled.then_some(ParamValue::Color(color));
         response
     }
 }
@@ -243,6 +355,7 @@ fn float_param_widget<'a, L: Into<WidgetText> + 'a>(
     value: &'a mut Option<ParamValue>,
     default: f32,
     range: RangeInclusive<f32>,
+    units: &'a UnitOptions,
     label: L,
 ) -> impl Widget + 'a {
     move |ui: &mut Ui| -> Response {
@@ -255,7 +368,13 @@ fn float_param_widget<'a, L: Into<WidgetText> + 'a>(
         let mut response = ui.checkbox(&mut enabled, "");
         ui.label(label);
         ui.add_enabled_ui(enabled, |ui| {
-            response.bitor_assign(ui.add(DragValue::new(&mut float).clamp_range(range)));
+            response.bitor_assign(
+                ui.add(
+                    units
+                        .drag_value(&mut float)
+                        .clamp_range(units.to_unit(*range.start())..=units.to_unit(*range.end())),
+                ),
+            );
         });
         *value = enabled.then(|| ParamValue::Float(float.try_into().unwrap_or_default()));
         response
@@ -279,7 +398,7 @@ fn boolean_param_widget<'a, L: Into<WidgetText> + 'a>(
         ui.add_enabled_ui(enabled, |ui| {
             response.bitor_assign(ui.checkbox(&mut boolean, ""));
         });
-        *value = enabled.then(|| ParamValue::Boolean(boolean));
+        *value = enabled.then_some(ParamValue::Boolean(boolean));
         response
     }
 }
@@ -331,11 +450,12 @@ fn rounding_param_widget<'a, L: Into<WidgetText> + 'a>(
                 );
             });
         });
-        *value = enabled.then(|| ParamValue::Rounding(rounding));
+        *value = enabled.then_some(ParamValue::Rounding(rounding));
         response
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(remote = "egui::epaint::Shape")]
 enum ShapeAdapter {
@@ -351,21 +471,23 @@ enum ShapeAdapter {
     Ellipse(egui::epaint::EllipseShape),
     LineSegment {
         points: [egui::Pos2; 2],
-        stroke: Stroke,
+        stroke: egui::Stroke,
     },
     Path(egui::epaint::PathShape),
     Rect(egui::epaint::RectShape),
     Text(egui::epaint::TextShape),
     Mesh(egui::epaint::Mesh),
     QuadraticBezier(egui::epaint::QuadraticBezierShape),
-    CubicBezier(CubicBezierShape),
+    CubicBezier(egui::epaint::CubicBezierShape),
     #[serde(skip)]
     Callback(egui::epaint::PaintCallback),
 }
 
+#[cfg(target_arch = "wasm32")]
 #[derive(Serialize, Deserialize)]
 struct ShapeWrapper(#[serde(with = "ShapeAdapter")] Shape);
 
+#[cfg(target_arch = "wasm32")]
 fn serialize_vec_shape<S>(val: &Vec<Shape>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -374,6 +496,7 @@ where
     vec.serialize(serializer)
 }
 
+#[cfg(target_arch = "wasm32")]
 fn deserialize_vec_shape<'de, D>(deserializer: D) -> Result<Vec<Shape>, D::Error>
 where
     D: Deserializer<'de>,
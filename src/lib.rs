@@ -1,3 +1 @@
 pub mod shape_editor;
-
-
@@ -1,31 +1,57 @@
 use crate::shape_editor::canvas::{CanvasContext, KeyboardAction};
-use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::constraints::{Constraint, Constraints};
+use crate::shape_editor::control_point::ShapeControlPoints;
+pub use crate::shape_editor::grid::{GridMode, GridOptions, GridStepProgression};
+pub use crate::shape_editor::guides::{Guide, GuideOrientation};
+use crate::shape_editor::interaction::{MoveGuide, MoveOrigin};
+pub use crate::shape_editor::minimap::Minimap;
+pub use crate::shape_editor::pointer_bindings::{ModifierAction, PointerBindings, ScrollAction};
+use crate::shape_editor::shape_action::edit_constraints::{AddConstraint, RemoveConstraint};
 use crate::shape_editor::shape_action::ShapeAction;
 use crate::shape_editor::shape_params::ApplyShapeParams;
 pub use crate::shape_editor::shape_params::{ParamType, ParamValue, ShapesParams};
+use crate::shape_editor::shape_visitor::get_points_positions::GetPointsPositions;
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
+use crate::shape_editor::shape_visitor::indexed_shape_control_points_visitor::IndexedShapeControlPointsVisitorAdapter;
+use crate::shape_editor::shape_visitor::ShapeVisitor;
 pub use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeType};
+use crate::shape_editor::snap::SnapTargetOverride;
+pub use crate::shape_editor::snap::{
+    ExternalSnapGeometry, ExternalSnapTarget, SnapOptions, SnapProvider, SnapTargetKind,
+    SnapTargetOptions,
+};
+use crate::shape_editor::stable_ids::{PointId, ShapeId};
+pub use crate::shape_editor::units::{Unit, UnitOptions};
 use egui::ahash::{HashMap, HashSet};
-use egui::{Color32, Context, Id, KeyboardShortcut, Response, Sense, Shape, Stroke, Ui, Vec2};
+use egui::{
+    Color32, Context, Id, KeyboardShortcut, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2,
+};
 use memory::ShapeEditorMemory;
 use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Range;
-use transform::Transform;
+pub use transform::{Transform, TransformShape};
 
 mod canvas;
 mod canvas_context_menu;
+mod constraint_overlay;
 pub mod constraints;
 mod control_point;
 mod grid;
+mod guides;
 mod index;
 mod interaction;
 mod memory;
+mod minimap;
+mod pointer_bindings;
 mod rulers;
 mod shape_action;
 mod shape_params;
 mod shape_visitor;
 mod snap;
+pub mod stable_ids;
 pub mod style;
 mod transform;
+mod units;
 mod utils;
 
 pub struct ShapeEditor<'a> {
@@ -33,6 +59,7 @@ pub struct ShapeEditor<'a> {
     pub shape: &'a mut Shape,
     pub style: &'a dyn style::Style,
     pub options: ShapeEditorOptions,
+    pub snap_providers: Vec<&'a dyn SnapProvider>,
 }
 
 #[derive(Clone)]
@@ -43,9 +70,20 @@ pub struct ShapeEditorOptions {
     pub stroke: Stroke,
     pub snap_distance: f32,
     pub snap_enabled_by_default: bool,
+    pub snap: SnapOptions,
+    pub grid: GridOptions,
+    pub units: UnitOptions,
     pub keyboard_shortcuts: HashMap<KeyboardAction, KeyboardShortcut>,
+    pub pointer_bindings: PointerBindings,
     pub context_menu_add_shapes: Vec<ShapeType>,
     pub connect_chained_shapes: bool,
+    pub drag_to_create_shapes: bool,
+    pub show_constraints: bool,
+    pub zoom_to_fit_margin: f32,
+    pub zoom_to_fit_on_open: bool,
+    pub show_minimap: bool,
+    pub auto_scroll_margin: f32,
+    pub auto_scroll_speed: f32,
 }
 
 impl Default for ShapeEditorOptions {
@@ -67,9 +105,20 @@ impl Default for ShapeEditorOptions {
             stroke: Stroke::new(1.0, Color32::BLACK),
             snap_distance: 5.0,
             snap_enabled_by_default: true,
+            snap: Default::default(),
+            grid: Default::default(),
+            units: Default::default(),
             keyboard_shortcuts: Default::default(),
+            pointer_bindings: Default::default(),
             context_menu_add_shapes,
             connect_chained_shapes: true,
+            drag_to_create_shapes: false,
+            show_constraints: true,
+            zoom_to_fit_margin: 20.0,
+            zoom_to_fit_on_open: true,
+            show_minimap: false,
+            auto_scroll_margin: 24.0,
+            auto_scroll_speed: 10.0,
         }
     }
 }
@@ -77,9 +126,22 @@ impl Default for ShapeEditorOptions {
 #[derive(Clone, Default)]
 pub struct Selection {
     control_points: BTreeSet<ShapePointIndex>,
+    constraint: Option<Constraint>,
 }
 
 impl Selection {
+    pub(crate) fn control_points_bounds(
+        &self,
+        control_points: &ShapeControlPoints,
+    ) -> Option<Rect> {
+        let points: Vec<Pos2> = self
+            .control_points
+            .iter()
+            .filter_map(|index| control_points.pos_by_index(index))
+            .collect();
+        Some(Rect::from_points(&points)).filter(|bounds| bounds.is_finite())
+    }
+
     pub fn has_control_points(&self) -> bool {
         !self.control_points.is_empty()
     }
@@ -113,10 +175,6 @@ impl Selection {
         &self.control_points
     }
 
-    pub(crate) fn control_points_mut(&mut self) -> &mut BTreeSet<ShapePointIndex> {
-        &mut self.control_points
-    }
-
     pub fn shapes(&self) -> HashSet<usize> {
         self.control_points
             .iter()
@@ -124,6 +182,34 @@ impl Selection {
             .collect()
     }
 
+    pub(crate) fn remap_control_points(
+        &mut self,
+        remap: impl FnMut(&ShapePointIndex) -> Option<ShapePointIndex>,
+    ) {
+        self.control_points = self.control_points.iter().filter_map(remap).collect();
+    }
+
+    pub(crate) fn remap_constraint(
+        &mut self,
+        remap: impl Fn(&ShapePointIndex) -> Option<ShapePointIndex>,
+    ) {
+        self.constraint = self
+            .constraint
+            .and_then(|constraint| constraint.remap_points(remap));
+    }
+
+    pub fn select_constraint(&mut self, constraint: Constraint) {
+        self.constraint = Some(constraint);
+    }
+
+    pub fn selected_constraint(&self) -> Option<&Constraint> {
+        self.constraint.as_ref()
+    }
+
+    pub fn clear_selected_constraint(&mut self) {
+        self.constraint = None;
+    }
+
     pub fn deselect_control_points(&mut self, control_points: &[ShapePointIndex]) {
         control_points.iter().for_each(|index| {
             self.control_points.remove(index);
@@ -133,6 +219,7 @@ impl Selection {
 
 pub struct ShapeEditorResponse {
     pub response: Response,
+    pub approximated_shapes: Vec<ShapeType>,
 }
 
 pub struct ShapeEditorCanvasResponse {
@@ -144,12 +231,24 @@ impl<'a> ShapeEditor<'a> {
         let rect = ui.available_rect_before_wrap();
         let outer_rect = rect;
         let mut memory = ShapeEditorMemory::load(egui_ctx, self.id);
+        self.options
+            .snap
+            .apply_overrides(&mut memory.snap_target_overrides);
         let margins = self.style.rulers_margins();
         let canvas_rect = margins.shrink_rect(outer_rect);
         let response = ui.allocate_rect(canvas_rect, Sense::click_and_drag());
+        let first_frame = memory.canvas_size == Vec2::ZERO;
+        memory.canvas_size = canvas_rect.size();
+        if first_frame && self.options.zoom_to_fit_on_open && !memory.transform_preset {
+            memory.zoom_to_rect(
+                self.shape.visual_bounding_rect(),
+                self.options.zoom_to_fit_margin,
+                &self.options.scaling_range,
+            );
+        }
         let ctx = CanvasContext::new(
             self.shape,
-            canvas_rect,
+            &self.snap_providers,
             &self.options,
             &mut memory,
             &response,
@@ -157,14 +256,41 @@ impl<'a> ShapeEditor<'a> {
             self.style,
         );
 
+        if let Some(orientation) = rulers::ruler_drag_started(self.style, ui, self.id, outer_rect) {
+            memory.begin_interaction(MoveGuide::new(orientation));
+        }
+        let corner_response = rulers::ruler_corner_response(self.style, ui, self.id, outer_rect);
+        if corner_response.double_clicked() {
+            memory.grid_origin = None;
+        } else if corner_response.drag_started() {
+            memory.begin_interaction(MoveOrigin::new(memory.grid_origin));
+        }
+
         self.show_canvas(response.clone(), egui_ctx, &ctx, &mut memory);
 
+        if self.options.show_minimap {
+            minimap::show_minimap(
+                ui,
+                self.id,
+                minimap::overlay_rect(self.style, canvas_rect),
+                self.shape,
+                &mut memory,
+                self.style,
+            );
+        }
+
         let ui_painter = ui.painter();
-        rulers::paint_rulers(self.style, ui_painter, outer_rect, &ctx);
+        let selection_bounds = memory
+            .selection()
+            .control_points_bounds(&ctx.shape_control_points);
+        rulers::paint_rulers(self.style, ui_painter, outer_rect, &ctx, selection_bounds);
 
         memory.store(egui_ctx, self.id);
 
-        ShapeEditorResponse { response }
+        ShapeEditorResponse {
+            response,
+            approximated_shapes: ctx.approximated_shapes,
+        }
     }
 
     fn apply_action(&mut self, action: impl ShapeAction + 'static, memory: &mut ShapeEditorMemory) {
@@ -196,13 +322,74 @@ impl<'a> ShapeEditor<'a> {
     }
 
     pub fn set_scale(&self, ctx: &Context, transform: Transform) {
-        memory_mut(self.id, ctx, |mem| mem.set_transform(transform));
+        memory_mut(self.id, ctx, |mem| {
+            mem.set_transform(transform);
+            mem.transform_preset = true;
+        });
+    }
+
+    pub fn current_zoom(&self, ctx: &Context) -> f32 {
+        memory_mut(self.id, ctx, |mem| mem.transform().scale().x)
+    }
+
+    pub fn zoom_to(&self, ctx: &Context, zoom: f32, anchor: Pos2) {
+        memory_mut(self.id, ctx, |mem| {
+            mem.zoom_to(zoom, anchor, &self.options.scaling_range);
+            mem.transform_preset = true;
+        });
+    }
+
+    pub fn center_on(&self, ctx: &Context, point: Pos2) {
+        memory_mut(self.id, ctx, |mem| mem.center_on(point));
+    }
+
+    pub fn zoom_to_rect(&self, ctx: &Context, rect: Rect, margin: f32) {
+        memory_mut(self.id, ctx, |mem| {
+            mem.zoom_to_rect(rect, margin, &self.options.scaling_range)
+        });
+    }
+
+    pub fn zoom_to_fit(&self, ctx: &Context, margin: f32) {
+        self.zoom_to_rect(ctx, self.shape.visual_bounding_rect(), margin);
+    }
+
+    pub fn zoom_to_selection(&mut self, ctx: &Context, margin: f32) {
+        let control_points = ShapeControlPoints::collect(self.shape);
+        let bounds = memory_mut(self.id, ctx, |mem| {
+            mem.selection().control_points_bounds(&control_points)
+        });
+        if let Some(bounds) = bounds {
+            self.zoom_to_rect(ctx, bounds, margin);
+        }
     }
 
     pub fn options_mut(&mut self) -> &mut ShapeEditorOptions {
         &mut self.options
     }
 
+    pub fn snap_options_mut(&mut self) -> &mut SnapOptions {
+        &mut self.options.snap
+    }
+
+    pub fn set_snap_target_enabled(&mut self, ctx: &Context, kind: SnapTargetKind, enabled: bool) {
+        let options_enabled = self.options.snap.is_enabled(kind);
+        memory_mut(self.id, ctx, |mem| {
+            mem.snap_target_overrides.insert(
+                kind,
+                SnapTargetOverride {
+                    options_enabled,
+                    enabled,
+                },
+            );
+        });
+    }
+
+    pub fn clear_snap_target_override(&mut self, ctx: &Context, kind: SnapTargetKind) {
+        memory_mut(self.id, ctx, |mem| {
+            mem.snap_target_overrides.remove(&kind);
+        });
+    }
+
     pub fn selection(&self, ctx: &Context) -> Selection {
         memory_mut(self.id, ctx, |mem| mem.selection().clone())
     }
@@ -237,6 +424,78 @@ impl<'a> ShapeEditor<'a> {
     ) -> R {
         memory_mut(self.id, ctx, |mem| func(&mut mem.constraints))
     }
+
+    pub fn add_constraint(&mut self, ctx: &Context, constraint: Constraint) {
+        memory_mut(self.id, ctx, |mem| {
+            self.apply_action(AddConstraint(constraint), mem)
+        })
+    }
+
+    pub fn remove_constraint(&mut self, ctx: &Context, constraint: Constraint) {
+        memory_mut(self.id, ctx, |mem| {
+            self.apply_action(RemoveConstraint(constraint), mem)
+        })
+    }
+
+    pub fn guides(&self, ctx: &Context) -> Vec<Guide> {
+        memory_mut(self.id, ctx, |mem| mem.guides.clone())
+    }
+
+    pub fn set_guides(&self, ctx: &Context, guides: Vec<Guide>) {
+        memory_mut(self.id, ctx, |mem| mem.guides = guides)
+    }
+
+    pub fn origin(&self, ctx: &Context) -> Pos2 {
+        memory_mut(self.id, ctx, |mem| {
+            mem.grid_origin.unwrap_or(self.options.grid.origin)
+        })
+    }
+
+    pub fn set_origin(&self, ctx: &Context, origin: Option<Pos2>) {
+        memory_mut(self.id, ctx, |mem| mem.grid_origin = origin)
+    }
+
+    pub fn to_origin_relative(&self, ctx: &Context, pos: Pos2) -> Pos2 {
+        (pos - self.origin(ctx)).to_pos2()
+    }
+
+    pub fn with_guides_mut<R>(&self, ctx: &Context, func: impl FnOnce(&mut Vec<Guide>) -> R) -> R {
+        memory_mut(self.id, ctx, |mem| func(&mut mem.guides))
+    }
+
+    pub fn shape_id(&mut self, ctx: &Context, shape_index: usize) -> Option<ShapeId> {
+        if !GetShapes::single(self.shape, shape_index)
+            .is_some_and(|shape| !matches!(shape, Shape::Noop))
+        {
+            return None;
+        }
+        Some(memory_mut(self.id, ctx, |mem| {
+            mem.stable_ids.shape_id(shape_index)
+        }))
+    }
+
+    pub fn point_id(&mut self, ctx: &Context, index: ShapePointIndex) -> Option<PointId> {
+        let mut positions_visitor = GetPointsPositions::new(HashSet::from_iter([index]));
+        IndexedShapeControlPointsVisitorAdapter(&mut positions_visitor).visit(self.shape);
+        if positions_visitor
+            .into_not_found_and_positions()
+            .1
+            .is_empty()
+        {
+            return None;
+        }
+        Some(memory_mut(self.id, ctx, |mem| {
+            mem.stable_ids.point_id(index)
+        }))
+    }
+
+    pub fn shape_index_by_id(&self, ctx: &Context, id: ShapeId) -> Option<usize> {
+        memory_mut(self.id, ctx, |mem| mem.stable_ids.shape_index(id))
+    }
+
+    pub fn point_index_by_id(&self, ctx: &Context, id: PointId) -> Option<ShapePointIndex> {
+        memory_mut(self.id, ctx, |mem| mem.stable_ids.point_index(id))
+    }
 }
 
 pub struct ShapeEditorBuilder<'a> {
@@ -244,6 +503,7 @@ pub struct ShapeEditorBuilder<'a> {
     shape: &'a mut Shape,
     style: &'a dyn style::Style,
     options: Option<ShapeEditorOptions>,
+    snap_providers: Vec<&'a dyn SnapProvider>,
 }
 
 impl<'a> ShapeEditorBuilder<'a> {
@@ -253,6 +513,7 @@ impl<'a> ShapeEditorBuilder<'a> {
             shape,
             style,
             options: None,
+            snap_providers: Vec::new(),
         }
     }
 
@@ -261,12 +522,18 @@ impl<'a> ShapeEditorBuilder<'a> {
         self
     }
 
+    pub fn snap_provider(mut self, snap_provider: &'a dyn SnapProvider) -> Self {
+        self.snap_providers.push(snap_provider);
+        self
+    }
+
     pub fn build(self) -> ShapeEditor<'a> {
         ShapeEditor {
             id: self.id,
             shape: self.shape,
             style: self.style,
             options: self.options.unwrap_or_default(),
+            snap_providers: self.snap_providers,
         }
     }
 }
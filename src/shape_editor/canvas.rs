@@ -1,37 +1,70 @@
 use crate::shape_editor::{
-    grid, index, style, Selection, ShapeEditor, ShapeEditorCanvasResponse, ShapeEditorOptions,
+    constraint_overlay, grid, guides, index, style, Selection, ShapeEditor,
+    ShapeEditorCanvasResponse, ShapeEditorOptions,
 };
 
 use super::transform::Transform;
+use crate::shape_editor::constraints::Constraint;
 use crate::shape_editor::control_point::{ShapeControlPoint, ShapeControlPoints};
+use crate::shape_editor::grid::GridOptions;
 use crate::shape_editor::index::GridIndex;
 use crate::shape_editor::memory::ShapeEditorMemory;
-use crate::shape_editor::shape_visitor::ShapePointIndex;
-use crate::shape_editor::snap::{paint_snap_point_highlight, SnapInfo};
-use egui::ahash::HashMap;
+use crate::shape_editor::pointer_bindings::{ModifierAction, PointerBindings, ScrollAction};
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
+use crate::shape_editor::shape_visitor::shape_geometry::ShapeGeometry;
+use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeType};
+use crate::shape_editor::snap::{paint_snap_point_highlight, SnapInfo, SnapProvider, SnapSources};
+use crate::shape_editor::units::UnitOptions;
+use egui::ahash::{HashMap, HashSet};
 use egui::emath::One;
 use egui::{
-    Color32, Context, Key, KeyboardShortcut, Modifiers, Painter, PointerButton, Pos2, Rect,
-    Response, Shape, Stroke, Ui, Vec2,
+    Color32, Context, CursorIcon, Key, KeyboardShortcut, Modifiers, Painter, PointerButton, Pos2,
+    Rect, Response, Shape, Stroke, Ui, Vec2,
 };
 use itertools::Itertools;
 use strum::EnumIter;
 use strum::IntoEnumIterator;
 
 #[derive(Default, Debug)]
-pub(crate) struct ActionModifier(Modifiers);
+pub(crate) struct ActionModifier(HashSet<ModifierAction>);
 
 impl ActionModifier {
+    fn new(bindings: &PointerBindings, modifiers: Modifiers, drag_create: bool) -> Self {
+        let active: Vec<ModifierAction> = ModifierAction::iter()
+            .filter(|action| drag_create || !action.is_drag_create())
+            .filter(|action| bindings.is_active(*action, modifiers))
+            .collect();
+        Self(
+            active
+                .iter()
+                .filter(|action| {
+                    !active
+                        .iter()
+                        .any(|other| bindings.is_overridden(**action, *other))
+                })
+                .copied()
+                .collect(),
+        )
+    }
+
     pub fn do_not_deselect_selected_points(&self) -> bool {
-        self.0.shift
+        self.0.contains(&ModifierAction::KeepSelection)
     }
 
     pub fn add_point_on_click(&self) -> bool {
-        self.0.ctrl || self.0.command
+        self.0.contains(&ModifierAction::AddPointOnClick)
     }
 
     pub fn snap_mouse_cursor(&self) -> bool {
-        self.0.alt
+        self.0.contains(&ModifierAction::ToggleSnap)
+    }
+
+    pub fn constrain_proportions(&self) -> bool {
+        self.0.contains(&ModifierAction::ConstrainProportions)
+    }
+
+    pub fn draw_from_center(&self) -> bool {
+        self.0.contains(&ModifierAction::DrawFromCenter)
     }
 }
 
@@ -41,10 +74,11 @@ pub(crate) struct CanvasTransform {
     pub canvas_content_to_canvas: Transform,
     pub canvas_content_to_ui: Transform,
     pub ui_to_canvas_content: Transform,
+    ui_canvas_rect: Rect,
 }
 
 impl CanvasTransform {
-    fn new(canvas_rect: Rect, content_transform: &Transform) -> Self {
+    pub(crate) fn new(canvas_rect: Rect, content_transform: &Transform) -> Self {
         let canvas_to_ui = Transform::from_to(
             Rect::from_min_size(Pos2::ZERO, canvas_rect.size()),
             canvas_rect,
@@ -58,16 +92,17 @@ impl CanvasTransform {
             canvas_content_to_canvas,
             canvas_content_to_ui,
             ui_to_canvas_content,
+            ui_canvas_rect: canvas_rect,
         }
     }
 
     pub fn canvas_content_viewport(&self) -> Rect {
         self.ui_to_canvas_content
-            .transform_rect(self.ui_to_canvas.0.from())
+            .transform_rect(&self.ui_canvas_rect)
     }
 
     pub fn ui_canvas_rect(&self) -> &Rect {
-        self.ui_to_canvas.0.from()
+        &self.ui_canvas_rect
     }
 }
 
@@ -76,6 +111,9 @@ pub enum KeyboardAction {
     AddPoint,
     DeletePoint,
     Undo,
+    ZoomToFit,
+    ZoomToActualSize,
+    ZoomToSelection,
 }
 
 impl KeyboardAction {
@@ -83,12 +121,21 @@ impl KeyboardAction {
     const SHORTCUT_DELETE_POINT: KeyboardShortcut =
         KeyboardShortcut::new(Modifiers::NONE, Key::Delete);
     const SHORTCUT_UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::CTRL, Key::Z);
+    const SHORTCUT_ZOOM_TO_FIT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::CTRL, Key::Num0);
+    const SHORTCUT_ZOOM_TO_ACTUAL_SIZE: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::CTRL, Key::Num1);
+    const SHORTCUT_ZOOM_TO_SELECTION: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::CTRL, Key::Num2);
 
     fn default_keyboard_shortcut(&self) -> &KeyboardShortcut {
         match self {
             KeyboardAction::AddPoint => &Self::SHORTCUT_ADD_POINT,
             KeyboardAction::DeletePoint => &Self::SHORTCUT_DELETE_POINT,
             KeyboardAction::Undo => &Self::SHORTCUT_UNDO,
+            KeyboardAction::ZoomToFit => &Self::SHORTCUT_ZOOM_TO_FIT,
+            KeyboardAction::ZoomToActualSize => &Self::SHORTCUT_ZOOM_TO_ACTUAL_SIZE,
+            KeyboardAction::ZoomToSelection => &Self::SHORTCUT_ZOOM_TO_SELECTION,
         }
     }
 }
@@ -96,6 +143,7 @@ impl KeyboardAction {
 #[derive(Debug)]
 pub(crate) struct CanvasInput {
     pub mouse_hover_pos: Option<Pos2>,
+    pub pointer_pos: Option<Pos2>,
     pub mouse_pos: Pos2,
     pub canvas_content_mouse_pos: Pos2,
     pub canvas_mouse_hover_pos: Option<Pos2>,
@@ -103,14 +151,20 @@ pub(crate) struct CanvasInput {
     pub mouse_primary_clicked: bool,
     pub mouse_primary_down: bool,
     pub mouse_secondary_pressed: bool,
-    pub mouse_secondary_down: bool,
     pub drag_started: bool,
     pub drag_stopped: bool,
+    pub pan_button_down: bool,
     pub action_modifier: ActionModifier,
     pub keyboard_action: Option<KeyboardAction>,
     pub mouse_scroll_delta: Vec2,
     pub mouse_zoom_delta: f32,
+    pub touch_zoom_delta: f32,
+    pub touch_pan_delta: Vec2,
+    pub touch: bool,
+    pub multi_touch: bool,
+    pub long_touched: bool,
     pub drag_delta: Vec2,
+    pub dt: f32,
 }
 
 impl CanvasInput {
@@ -119,9 +173,14 @@ impl CanvasInput {
         response: &Response,
         transform: &CanvasTransform,
         last_mouse_hover_pos: Pos2,
+        drag_create: bool,
     ) -> Self {
+        let bindings = &options.pointer_bindings;
         let mouse_hover_pos = response.hover_pos();
-        let mouse_pos = mouse_hover_pos.unwrap_or(last_mouse_hover_pos);
+        let pointer_pos = response.ctx.input(|input| input.pointer.latest_pos());
+        let mouse_pos = mouse_hover_pos
+            .or(pointer_pos.filter(|_| response.dragged()))
+            .unwrap_or(last_mouse_hover_pos);
         let canvas_content_mouse_pos = transform.ui_to_canvas_content.transform_pos(mouse_pos);
         let canvas_mouse_hover_pos =
             mouse_hover_pos.map(|pos| transform.ui_to_canvas.transform_pos(pos));
@@ -129,23 +188,23 @@ impl CanvasInput {
             mouse_primary_pressed,
             mouse_secondary_pressed,
             mouse_primary_down,
-            mouse_secondary_down,
             action_modifier,
             mouse_primary_clicked,
             canvas_action,
             mouse_scroll_delta,
             mouse_zoom_delta,
+            pan_button_down,
         ) = if response.context_menu_opened() {
             (
                 false,
                 false,
                 false,
-                false,
                 ActionModifier::default(),
                 false,
                 None,
                 Vec2::ZERO,
                 f32::ONE,
+                false,
             )
         } else {
             response.ctx.input_mut(|input| {
@@ -153,8 +212,7 @@ impl CanvasInput {
                     input.pointer.button_pressed(PointerButton::Primary),
                     input.pointer.button_pressed(PointerButton::Secondary),
                     input.pointer.button_down(PointerButton::Primary),
-                    input.pointer.button_down(PointerButton::Secondary),
-                    ActionModifier(input.modifiers),
+                    ActionModifier::new(bindings, input.modifiers, drag_create),
                     input.pointer.primary_clicked(),
                     KeyboardAction::iter().find(|canvas_action| {
                         input.consume_shortcut(
@@ -164,17 +222,49 @@ impl CanvasInput {
                                 .unwrap_or(canvas_action.default_keyboard_shortcut()),
                         )
                     }),
-                    input.smooth_scroll_delta,
-                    input.zoom_delta(),
+                    match bindings.scroll {
+                        ScrollAction::Pan => input.smooth_scroll_delta,
+                        ScrollAction::Zoom => Vec2::ZERO,
+                    },
+                    match bindings.scroll {
+                        ScrollAction::Pan => input.zoom_delta(),
+                        ScrollAction::Zoom => {
+                            input.zoom_delta()
+                                * (input.smooth_scroll_delta.y * bindings.scroll_zoom_speed).exp()
+                        }
+                    },
+                    bindings
+                        .pan_buttons
+                        .iter()
+                        .any(|button| input.pointer.button_down(*button))
+                        || (bindings.pan_with_space_drag && input.key_down(Key::Space)),
                 )
             })
         };
+        let (touch, multi_touch) = response
+            .ctx
+            .input(|input| (input.any_touches(), input.multi_touch()));
+        let (mouse_zoom_delta, touch_zoom_delta, touch_pan_delta) = match multi_touch {
+            Some(multi_touch) if !response.context_menu_opened() && mouse_hover_pos.is_some() => (
+                f32::ONE,
+                multi_touch.zoom_delta,
+                multi_touch.translation_delta,
+            ),
+            _ => (mouse_zoom_delta, f32::ONE, Vec2::ZERO),
+        };
+        let multi_touch = multi_touch.is_some();
+        let long_touched = response.long_touched();
+        let mouse_primary_pressed = mouse_primary_pressed && !pan_button_down && !multi_touch;
+        let mouse_primary_clicked =
+            mouse_primary_clicked && !pan_button_down && !multi_touch && !long_touched;
+        let dt = response.ctx.input(|input| input.stable_dt);
         let drag_started = response.drag_started();
         let drag_stopped = response.drag_stopped();
         let drag_delta = response.drag_delta();
 
         Self {
             mouse_hover_pos,
+            pointer_pos,
             mouse_pos,
             canvas_content_mouse_pos,
             canvas_mouse_hover_pos,
@@ -182,23 +272,33 @@ impl CanvasInput {
             mouse_primary_clicked,
             mouse_primary_down,
             mouse_secondary_pressed,
-            mouse_secondary_down,
             action_modifier,
             drag_started,
             drag_stopped,
+            pan_button_down,
             keyboard_action: canvas_action,
             mouse_scroll_delta,
             mouse_zoom_delta,
+            touch_zoom_delta,
+            touch_pan_delta,
+            touch,
+            multi_touch,
+            long_touched,
             drag_delta,
+            dt,
         }
     }
 
     pub fn primary_drag_started(&self) -> bool {
-        self.drag_started && self.mouse_primary_down
+        self.drag_started && self.mouse_primary_down && !self.pan_button_down && !self.multi_touch
+    }
+
+    pub fn primary_interrupted(&self) -> bool {
+        self.multi_touch || self.long_touched
     }
 
-    pub fn secondary_drag_started(&self) -> bool {
-        self.drag_started && self.mouse_secondary_down
+    pub fn pan_drag_started(&self) -> bool {
+        self.drag_started && self.pan_button_down
     }
 }
 
@@ -207,8 +307,13 @@ pub(crate) struct CanvasContext {
     pub(crate) input: CanvasInput,
     pub(crate) painter: Painter,
     pub(crate) grid_index: GridIndex,
+    pub(crate) units: UnitOptions,
     pub(crate) hovered_ui_shape_points: HashMap<ShapePointIndex, ShapeControlPoint>,
+    pub(crate) hovered_constraint: Option<Constraint>,
+    pub(crate) hovered_guide: Option<usize>,
+    pub(crate) hit_radius: f32,
     pub(crate) ui_shape: Shape,
+    pub(crate) approximated_shapes: Vec<ShapeType>,
     pub(crate) ui_shape_control_points: ShapeControlPoints,
     pub(crate) shape_control_points: ShapeControlPoints,
 }
@@ -216,33 +321,134 @@ pub(crate) struct CanvasContext {
 impl CanvasContext {
     pub(crate) fn new(
         shape: &mut Shape,
-        canvas_rect: Rect,
+        snap_providers: &[&dyn SnapProvider],
         options: &ShapeEditorOptions,
         memory: &mut ShapeEditorMemory,
         response: &Response,
         ui: &Ui,
         style: &dyn style::Style,
     ) -> Self {
-        let transform = CanvasTransform::new(canvas_rect, &memory.transform());
-        let input = CanvasInput::new(options, response, &transform, memory.last_mouse_hover_pos());
+        let canvas_rect = response.rect;
+        let transform = CanvasTransform::new(canvas_rect, memory.transform());
+        let drag_create = memory
+            .interaction()
+            .iter()
+            .any(|interaction| interaction.is_drag_creating());
+        let input = CanvasInput::new(
+            options,
+            response,
+            &transform,
+            memory.last_mouse_hover_pos(),
+            drag_create,
+        );
         let painter = ui.painter_at(canvas_rect);
-        let grid_index = GridIndex::from_transform(&transform);
-        let mut ui_shape = transform.canvas_content_to_ui.transform_shape(shape);
-        let ui_shape_control_points = ShapeControlPoints::collect(&mut ui_shape);
+        let grid_index = GridIndex::from_transform(
+            &transform,
+            &GridOptions {
+                origin: memory.grid_origin.unwrap_or(options.grid.origin),
+                ..options.grid.clone()
+            },
+            &options.units,
+        );
+        let (ui_shape, approximated_shapes) = ui.fonts(|fonts| {
+            transform
+                .canvas_content_to_ui
+                .transform_shape_with_fonts(shape, fonts)
+        });
         let shape_control_points = ShapeControlPoints::collect(shape);
+        let ui_shape_control_points =
+            shape_control_points.transform(&transform.canvas_content_to_ui);
+        let touch_hit_scale = if input.touch {
+            style.touch_hit_scale()
+        } else {
+            1.0
+        };
+        let hit_radius = style.control_point_radius() * touch_hit_scale;
         let hovered_ui_shape_points = input
             .mouse_hover_pos
-            .map(|pos| ui_shape_control_points.points_in_radius(pos, style.control_point_radius()))
+            .map(|pos| ui_shape_control_points.points_in_radius(pos, hit_radius))
             .unwrap_or_default();
+        let hovered_constraint = options
+            .show_constraints
+            .then(|| {
+                constraint_overlay::hovered_constraint(
+                    input.mouse_hover_pos,
+                    &memory.constraints,
+                    &ui_shape_control_points,
+                    style,
+                )
+            })
+            .flatten();
+        let hovered_guide = guides::hovered_guide(
+            input.mouse_hover_pos,
+            &memory.guides,
+            &transform,
+            style.guide_hover_distance() * touch_hit_scale,
+        );
         let selection = memory.selection().clone();
         if options.snap_enabled_by_default != input.action_modifier.snap_mouse_cursor() {
+            let shape_geometry = ShapeGeometry::collect(shape, selection.shapes());
+            let snap_anchor = memory
+                .interaction()
+                .iter()
+                .find_map(|interaction| interaction.snap_anchor())
+                .or_else(|| {
+                    input
+                        .action_modifier
+                        .add_point_on_click()
+                        .then(|| selection.single_control_point())
+                        .flatten()
+                        .and_then(|index| shape_control_points.pos_by_index(index))
+                });
+            let moving_bounds = memory
+                .interaction()
+                .iter()
+                .find_map(|interaction| interaction.moved_point())
+                .map(|moved_point| {
+                    let selected_shapes = selection.shapes();
+                    let partially_selected_shapes: HashSet<usize> = shape_control_points
+                        .iter()
+                        .filter(|(index, _)| {
+                            selected_shapes.contains(&index.shape_index)
+                                && !selection.is_control_point_selected(index)
+                        })
+                        .map(|(index, _)| index.shape_index)
+                        .collect();
+                    let shapes_bounds = GetShapes::by_indexes(
+                        shape,
+                        selected_shapes
+                            .difference(&partially_selected_shapes)
+                            .copied(),
+                    )
+                    .values()
+                    .map(Shape::visual_bounding_rect)
+                    .fold(Rect::NOTHING, Rect::union);
+                    let selected_points: Vec<Pos2> = selection
+                        .control_points()
+                        .iter()
+                        .filter(|index| partially_selected_shapes.contains(&index.shape_index))
+                        .filter_map(|index| shape_control_points.pos_by_index(index))
+                        .collect();
+                    shapes_bounds
+                        .union(Rect::from_points(&selected_points))
+                        .translate(input.canvas_content_mouse_pos - moved_point)
+                })
+                .filter(|bounds| bounds.is_finite());
             SnapInfo::update_snap_info(
                 &mut memory.snap,
                 input.canvas_content_mouse_pos,
-                transform.ui_to_canvas_content.scale().x * options.snap_distance,
-                &grid_index,
-                &shape_control_points,
-                &selection,
+                transform.ui_to_canvas_content.scale().x,
+                &SnapSources {
+                    grid_index: &grid_index,
+                    shape_control_points: &shape_control_points,
+                    shape_geometry: &shape_geometry,
+                    selection: &selection,
+                    anchor: snap_anchor,
+                    snap_providers,
+                    guides: &memory.guides,
+                    moving_bounds,
+                },
+                options,
             );
         } else {
             memory.snap.clear();
@@ -253,8 +459,13 @@ impl CanvasContext {
             input,
             painter,
             grid_index,
+            units: options.units.clone(),
             ui_shape,
+            approximated_shapes,
             hovered_ui_shape_points,
+            hovered_constraint,
+            hovered_guide,
+            hit_radius,
             ui_shape_control_points,
             shape_control_points,
         }
@@ -288,20 +499,36 @@ impl<'a> ShapeEditor<'a> {
         memory: &mut ShapeEditorMemory,
     ) -> ShapeEditorCanvasResponse {
         puffin_egui::puffin::profile_function!();
-        self.canvas_context_menu(response.clone(), memory, &ctx);
-        paint_canvas_background(&ctx, self.style);
+        self.canvas_context_menu(response.clone(), memory, ctx);
+        paint_canvas_background(ctx, self.style);
 
-        grid::paint_grid(&ctx, self.style);
+        grid::paint_grid(ctx, self.style);
         ctx.painter.add(ctx.ui_shape.clone());
+        if self.options.show_constraints {
+            constraint_overlay::paint_constraints(
+                ctx,
+                &memory.constraints,
+                memory.selection(),
+                self.style,
+            );
+        }
+        guides::paint_guides(ctx, &memory.guides, ctx.hovered_guide, self.style);
+        if ctx
+            .hovered_guide
+            .and_then(|index| memory.guides.get(index))
+            .is_some_and(|guide| !guide.locked)
+        {
+            egui_ctx.set_cursor_icon(CursorIcon::Grab);
+        }
 
-        memory.current_frame_interactions(&ctx);
-        memory.update_interaction(self.shape, self.style, &self.options, &ctx);
+        memory.current_frame_interactions(ctx);
+        memory.update_interaction(self.shape, self.style, &self.options, ctx);
 
-        paint_shape_control_points(&ctx, memory, self.style);
-        paint_snap_point_highlight(&ctx, &memory.snap(), self.style);
-        paint_canvas_border(&ctx, self.style);
+        paint_shape_control_points(ctx, memory, self.style);
+        paint_snap_point_highlight(ctx, memory.snap(), self.style);
+        paint_canvas_border(ctx, self.style);
 
-        memory.next_frame_interactions(&ctx);
+        memory.next_frame_interactions(ctx);
 
         if !egui_ctx.is_context_menu_open() {
             if let Some(mouse_hover_pos) = ctx.input.mouse_hover_pos {
@@ -352,3 +579,289 @@ fn paint_shape_control_points(
             .add(ui_shape_point.to_shape(hovered, selected, style));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::shape_editor::interaction::DragShape;
+    use crate::shape_editor::memory::ShapeEditorMemory;
+    use crate::shape_editor::shape_visitor::ShapeType;
+    use crate::shape_editor::style::Light;
+    use crate::shape_editor::transform::Transform;
+    use crate::shape_editor::ShapeEditorBuilder;
+    use egui::{
+        CentralPanel, Color32, Context, Event, Id, Modifiers, PointerButton, Pos2, RawInput, Rect,
+        Shape, TouchDeviceId, TouchId, TouchPhase, Vec2,
+    };
+
+    const EDITOR_ID: &str = "touch_test_editor";
+
+    struct TouchScreen {
+        ctx: Context,
+        shape: Shape,
+        time: f64,
+    }
+
+    impl TouchScreen {
+        fn new() -> Self {
+            let mut screen = Self::with_shape(Shape::Noop);
+            screen.frame(0.0, Vec::new());
+            screen
+        }
+
+        fn with_shape(shape: Shape) -> Self {
+            Self {
+                ctx: Context::default(),
+                shape,
+                time: 0.0,
+            }
+        }
+
+        fn frame(&mut self, dt: f64, events: Vec<Event>) {
+            self.frame_with_modifiers(dt, events, Modifiers::NONE);
+        }
+
+        fn frame_with_modifiers(&mut self, dt: f64, events: Vec<Event>, modifiers: Modifiers) {
+            self.time += dt;
+            let input = RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::splat(400.0))),
+                time: Some(self.time),
+                modifiers,
+                events,
+                ..Default::default()
+            };
+            let shape = &mut self.shape;
+            let _ = self.ctx.run(input, |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ShapeEditorBuilder::new(Id::new(EDITOR_ID), shape, &Light::default())
+                        .build()
+                        .show(ui, ctx);
+                });
+            });
+        }
+
+        fn memory(&self) -> ShapeEditorMemory {
+            ShapeEditorMemory::load(&self.ctx, Id::new(EDITOR_ID))
+        }
+
+        fn has_interaction(&self, name: &str) -> bool {
+            self.memory()
+                .interaction()
+                .iter()
+                .any(|interaction| format!("{:?}", interaction).starts_with(name))
+        }
+    }
+
+    fn document() -> Shape {
+        Shape::rect_filled(
+            Rect::from_min_size(Pos2::new(1000.0, 1000.0), Vec2::splat(100.0)),
+            0.0,
+            Color32::RED,
+        )
+    }
+
+    #[test]
+    fn zooms_to_fit_on_open() {
+        let mut screen = TouchScreen::with_shape(document());
+        screen.frame(0.0, Vec::new());
+        let bounds = screen
+            .memory()
+            .transform()
+            .transform_rect(&document().visual_bounding_rect());
+        assert!(Rect::from_min_size(Pos2::ZERO, Vec2::splat(400.0)).contains_rect(bounds));
+    }
+
+    #[test]
+    fn preset_transform_wins_over_zoom_to_fit_on_open() {
+        let mut screen = TouchScreen::with_shape(document());
+        let preset = Transform::from_scale(Vec2::splat(2.0));
+        ShapeEditorBuilder::new(Id::new(EDITOR_ID), &mut screen.shape, &Light::default())
+            .build()
+            .set_scale(&screen.ctx, preset.clone());
+        screen.frame(0.0, Vec::new());
+        assert_eq!(*screen.memory().transform(), preset);
+    }
+
+    fn drag_create_rect(modifiers: Modifiers) -> Rect {
+        let mut screen = TouchScreen::new();
+        let mut memory = screen.memory();
+        memory.interaction_mut().push(Box::new(
+            DragShape::with_shape_type(ShapeType::Rect).unwrap(),
+        ));
+        memory.store(&screen.ctx, Id::new(EDITOR_ID));
+        let (from, to) = (Pos2::new(100.0, 100.0), Pos2::new(220.0, 160.0));
+        let button = |pos, pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers,
+        };
+        screen.frame(0.1, vec![Event::PointerMoved(from)]);
+        screen.frame(0.1, vec![button(from, true)]);
+        screen.frame_with_modifiers(0.1, vec![Event::PointerMoved(to)], modifiers);
+        screen.frame_with_modifiers(0.1, vec![button(to, false)], modifiers);
+        match &screen.shape {
+            Shape::Vec(shapes) => match shapes.as_slice() {
+                [.., Shape::Rect(rect)] => rect.rect,
+                shapes => panic!("expected a rect, got {:?}", shapes),
+            },
+            shape => panic!("expected a rect, got {:?}", shape),
+        }
+    }
+
+    #[test]
+    fn shift_constrains_drag_create_to_square() {
+        let rect = drag_create_rect(Modifiers::SHIFT);
+        assert_eq!(rect.width(), rect.height());
+        assert_ne!(drag_create_rect(Modifiers::NONE).size(), rect.size());
+    }
+
+    #[test]
+    fn alt_drag_creates_from_center() {
+        let plain = drag_create_rect(Modifiers::NONE);
+        let from_center = drag_create_rect(Modifiers::ALT);
+        assert_eq!(from_center.center(), plain.min);
+        assert_eq!(from_center.size(), plain.size() * 2.0);
+    }
+
+    fn button(pos: Pos2, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn ruler_corner_drags_origin_and_double_click_resets_it() {
+        let mut screen = TouchScreen::new();
+        let corner = Pos2::new(16.0, 16.0);
+        let target = Pos2::new(203.0, 157.0);
+        screen.frame(0.1, vec![Event::PointerMoved(corner)]);
+        screen.frame(0.1, vec![button(corner, true)]);
+        screen.frame(0.1, vec![Event::PointerMoved(Pos2::new(100.0, 100.0))]);
+        screen.frame(0.1, vec![Event::PointerMoved(target)]);
+        screen.frame(0.1, vec![button(target, false)]);
+        let memory = screen.memory();
+        let origin = memory.grid_origin.expect("origin should be set");
+        let canvas_min = corner + Vec2::splat(Light::default().rulers_width / 2.0);
+        let ui_origin = memory.transform().transform_pos(origin) + canvas_min.to_vec2();
+        assert!(
+            ui_origin.distance(target) < 10.0,
+            "{ui_origin:?} is far from {target:?}"
+        );
+
+        screen.frame(0.5, vec![Event::PointerMoved(corner)]);
+        for _ in 0..2 {
+            screen.frame(0.05, vec![button(corner, true)]);
+            screen.frame(0.05, vec![button(corner, false)]);
+        }
+        assert_eq!(screen.memory().grid_origin, None);
+    }
+
+    fn drag_selection_translation(to: Pos2) -> Vec2 {
+        let mut screen = TouchScreen::new();
+        let from = Pos2::new(200.0, 200.0);
+        let before = screen.memory().transform().transform_pos(Pos2::ZERO);
+        screen.frame(0.1, vec![Event::PointerMoved(from)]);
+        screen.frame(0.1, vec![button(from, true)]);
+        screen.frame(0.1, vec![Event::PointerMoved(to)]);
+        assert!(screen.has_interaction("Selection"));
+        for _ in 0..3 {
+            screen.frame(0.1, Vec::new());
+        }
+        screen.memory().transform().transform_pos(Pos2::ZERO) - before
+    }
+
+    #[test]
+    fn selection_near_edge_auto_scrolls_away_from_it() {
+        let right = drag_selection_translation(Pos2::new(385.0, 200.0));
+        assert!(right.x < 0.0, "{right:?}");
+        assert_eq!(right.y, 0.0);
+
+        let top = drag_selection_translation(Pos2::new(200.0, 30.0));
+        assert!(top.y > 0.0, "{top:?}");
+        assert_eq!(top.x, 0.0);
+    }
+
+    #[test]
+    fn selection_inside_margin_does_not_scroll() {
+        assert_eq!(
+            drag_selection_translation(Pos2::new(300.0, 300.0)),
+            Vec2::ZERO
+        );
+    }
+
+    fn touch(id: u64, phase: TouchPhase, pos: Pos2) -> Event {
+        Event::Touch {
+            device_id: TouchDeviceId(0),
+            id: TouchId(id),
+            phase,
+            pos,
+            force: None,
+        }
+    }
+
+    fn primary_touch(phase: TouchPhase, pos: Pos2) -> Vec<Event> {
+        let mut events = vec![touch(0, phase, pos), Event::PointerMoved(pos)];
+        if phase != TouchPhase::Move {
+            events.push(Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed: phase == TouchPhase::Start,
+                modifiers: Modifiers::NONE,
+            });
+        }
+        events
+    }
+
+    #[test]
+    fn pinch_zooms_canvas() {
+        let mut screen = TouchScreen::new();
+        let scale = screen.memory().transform().scale();
+        let mut events = primary_touch(TouchPhase::Start, Pos2::new(150.0, 200.0));
+        events.push(touch(1, TouchPhase::Start, Pos2::new(250.0, 200.0)));
+        screen.frame(0.1, events);
+        screen.frame(0.1, Vec::new());
+        let mut events = primary_touch(TouchPhase::Move, Pos2::new(100.0, 200.0));
+        events.push(touch(1, TouchPhase::Move, Pos2::new(300.0, 200.0)));
+        screen.frame(0.1, events);
+        assert!(screen.memory().transform().scale().x > scale.x);
+    }
+
+    #[test]
+    fn second_finger_ends_selection() {
+        let mut screen = TouchScreen::new();
+        screen.frame(
+            0.1,
+            primary_touch(TouchPhase::Start, Pos2::new(100.0, 100.0)),
+        );
+        screen.frame(
+            0.1,
+            primary_touch(TouchPhase::Move, Pos2::new(150.0, 150.0)),
+        );
+        screen.frame(
+            0.1,
+            primary_touch(TouchPhase::Move, Pos2::new(160.0, 160.0)),
+        );
+        assert!(screen.has_interaction("Selection"));
+        screen.frame(
+            0.1,
+            vec![touch(1, TouchPhase::Start, Pos2::new(300.0, 300.0))],
+        );
+        assert!(!screen.has_interaction("Selection"));
+    }
+
+    #[test]
+    fn long_press_opens_context_menu() {
+        let mut screen = TouchScreen::new();
+        screen.frame(
+            0.1,
+            primary_touch(TouchPhase::Start, Pos2::new(100.0, 100.0)),
+        );
+        screen.frame(1.0, Vec::new());
+        screen.frame(0.1, Vec::new());
+        assert!(screen.ctx.is_context_menu_open());
+        assert!(!screen.has_interaction("Selection"));
+    }
+}
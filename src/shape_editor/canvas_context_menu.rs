@@ -1,7 +1,17 @@
 use crate::shape_editor::canvas::CanvasContext;
+use crate::shape_editor::constraints::Constraint;
 use crate::shape_editor::memory::ShapeEditorMemory;
+use crate::shape_editor::shape_action::cut_path::CutPath;
+use crate::shape_editor::shape_action::edit_constraints::{AddConstraint, RemoveConstraint};
+use crate::shape_editor::shape_action::join_paths::JoinPaths;
+use crate::shape_editor::shape_action::reverse_path::ReversePath;
+use crate::shape_editor::shape_action::toggle_path_closed::{ClosePath, OpenPath};
+use crate::shape_editor::shape_action::{Combined, ShapeAction};
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
 use crate::shape_editor::{interaction, ShapeEditor};
-use egui::Response;
+use egui::{Response, Shape, Ui};
+use itertools::Itertools;
+use std::collections::BTreeSet;
 
 impl<'a> ShapeEditor<'a> {
     pub(crate) fn canvas_context_menu(
@@ -11,24 +21,40 @@ impl<'a> ShapeEditor<'a> {
         ctx: &CanvasContext,
     ) {
         puffin_egui::puffin::profile_function!();
+        if response.secondary_clicked() {
+            memory.context_menu_guide = ctx.hovered_guide;
+        }
         response.context_menu(|ui| {
             if !self.options.context_menu_add_shapes.is_empty() {
                 ui.menu_button("Add shape", |ui| {
                     let point = ctx.input.canvas_content_mouse_pos;
                     for shape_type in &self.options.context_menu_add_shapes {
                         if ui.button(shape_type.to_string()).clicked() {
-                            memory.begin_interaction(
-                                interaction::AddPointsThanShape::with_shape_type_and_start_point(
-                                    *shape_type,
-                                    point,
-                                ),
-                            );
+                            if let Some(drag_shape) = self
+                                .options
+                                .drag_to_create_shapes
+                                .then(|| interaction::DragShape::with_shape_type(*shape_type))
+                                .flatten()
+                            {
+                                memory.begin_interaction(drag_shape);
+                            } else {
+                                memory.begin_interaction(
+                                    interaction::AddPointsThanShape::with_shape_type_and_start_point(
+                                        *shape_type,
+                                        point,
+                                    ),
+                                );
+                            }
                             ui.close_menu();
                         }
                     }
                 });
             }
 
+            self.path_operations_menu(ui, memory);
+            self.constraints_menu(ui, memory);
+            guides_menu(ui, memory);
+
             if let Some(last_action_name) = memory
                 .action_history()
                 .last()
@@ -42,3 +68,131 @@ impl<'a> ShapeEditor<'a> {
         });
     }
 }
+
+impl<'a> ShapeEditor<'a> {
+    fn path_operations_menu(&mut self, ui: &mut Ui, memory: &mut ShapeEditorMemory) {
+        if ui.button("Scissors").clicked() {
+            memory.begin_interaction(interaction::Scissors);
+            ui.close_menu();
+        }
+        let selected_points = memory.selection().control_points().clone();
+        let selected_shapes = GetShapes::by_indexes(self.shape, memory.selection().shapes());
+        let selected_paths: BTreeSet<usize> = selected_shapes
+            .iter()
+            .filter_map(|(index, shape)| matches!(shape, Shape::Path(_)).then_some(*index))
+            .collect();
+        if selected_paths.is_empty() {
+            return;
+        }
+        let mut action: Option<Box<dyn ShapeAction>> = None;
+        if let Some(index) = memory.selection().single_control_point() {
+            if let Some(Shape::Path(path)) = selected_shapes.get(&index.shape_index) {
+                if ui.button("Cut path at point").clicked() {
+                    action = Some(Box::new(CutPath::at_point(*index)));
+                }
+                if path.closed {
+                    if ui.button("Open path at point").clicked() {
+                        action = Some(Box::new(OpenPath(*index)));
+                    }
+                } else if JoinPaths::is_path_end_point(path, index.point_index)
+                    && ui.button("Close path").clicked()
+                {
+                    action = Some(Box::new(ClosePath(index.shape_index)));
+                }
+            }
+        }
+        if let Some((first, second)) = selected_points.iter().collect_tuple() {
+            if let (Some(Shape::Path(first_path)), Some(Shape::Path(second_path))) = (
+                selected_shapes.get(&first.shape_index),
+                selected_shapes.get(&second.shape_index),
+            ) {
+                if first.shape_index != second.shape_index
+                    && JoinPaths::is_path_end_point(first_path, first.point_index)
+                    && JoinPaths::is_path_end_point(second_path, second.point_index)
+                    && ui.button("Join paths").clicked()
+                {
+                    action = Some(Box::new(JoinPaths(*first, *second)));
+                }
+            }
+        }
+        if ui.button("Reverse path").clicked() {
+            action = Some(Box::new(ReversePath(selected_paths)));
+        }
+        if let Some(action) = action {
+            memory.apply_boxed_action(action, self.shape);
+            ui.close_menu();
+        }
+    }
+}
+
+impl<'a> ShapeEditor<'a> {
+    fn constraints_menu(&mut self, ui: &mut Ui, memory: &mut ShapeEditorMemory) {
+        let mut actions: Vec<Box<dyn ShapeAction>> = Vec::new();
+        if let Some(constraint) = memory.selection().selected_constraint() {
+            if ui.button("Remove constraint").clicked() {
+                actions.push(Box::new(RemoveConstraint(*constraint)));
+            }
+        }
+        let selected_points = memory.selection().control_points();
+        if selected_points.len() > 1 && ui.button("Link points").clicked() {
+            actions.extend(selected_points.iter().tuple_windows().map(|(from, to)| {
+                Box::new(AddConstraint(Constraint::LinkTranslationBidirectional(
+                    *from, *to,
+                ))) as Box<dyn ShapeAction>
+            }));
+        }
+        let links: Vec<Constraint> = memory
+            .constraints
+            .constraints()
+            .filter(|constraint| {
+                matches!(
+                    constraint,
+                    Constraint::LinkTranslationBidirectional(_, _)
+                        | Constraint::LinkTranslationFromTo(_, _)
+                ) && constraint
+                    .points()
+                    .iter()
+                    .all(|index| selected_points.contains(index))
+            })
+            .copied()
+            .collect();
+        if !links.is_empty() && ui.button("Unlink points").clicked() {
+            actions.extend(
+                links
+                    .into_iter()
+                    .map(|link| Box::new(RemoveConstraint(link)) as Box<dyn ShapeAction>),
+            );
+        }
+        if !actions.is_empty() {
+            memory.selection_mut().clear_selected_constraint();
+            memory.apply_boxed_action(
+                Box::new(Combined::new("Edit Constraints".into(), actions)),
+                self.shape,
+            );
+            ui.close_menu();
+        }
+    }
+}
+
+fn guides_menu(ui: &mut Ui, memory: &mut ShapeEditorMemory) {
+    let Some(index) = memory.context_menu_guide else {
+        return;
+    };
+    let Some(guide) = memory.guides.get(index).copied() else {
+        return;
+    };
+    let lock_label = if guide.locked {
+        "Unlock guide"
+    } else {
+        "Lock guide"
+    };
+    if ui.button(lock_label).clicked() {
+        memory.guides[index].locked = !guide.locked;
+        ui.close_menu();
+    }
+    if ui.button("Remove guide").clicked() {
+        memory.guides.remove(index);
+        memory.context_menu_guide = None;
+        ui.close_menu();
+    }
+}
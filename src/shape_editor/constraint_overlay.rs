@@ -0,0 +1,204 @@
+use crate::shape_editor::canvas::CanvasContext;
+use crate::shape_editor::constraints::{Constraint, Constraints};
+use crate::shape_editor::control_point::ShapeControlPoints;
+use crate::shape_editor::{index, style, Selection};
+use egui::{Pos2, Shape, Vec2};
+
+pub(crate) fn constraint_glyph_position(
+    constraint: &Constraint,
+    ui_shape_control_points: &ShapeControlPoints,
+    style: &dyn style::Style,
+) -> Option<Pos2> {
+    let positions: Vec<Pos2> = constraint
+        .points()
+        .iter()
+        .map(|index| ui_shape_control_points.pos_by_index(index))
+        .collect::<Option<_>>()?;
+    match constraint {
+        Constraint::PointPositionRange(_, _) => {
+            Some(positions[0] + Vec2::new(1.0, -1.0) * style.constraint_glyph_radius() * 2.0)
+        }
+        _ => {
+            let sum = positions
+                .iter()
+                .fold(Vec2::ZERO, |sum, pos| sum + pos.to_vec2());
+            Some((sum / positions.len() as f32).to_pos2())
+        }
+    }
+}
+
+pub(crate) fn hovered_constraint(
+    mouse_hover_pos: Option<Pos2>,
+    constraints: &Constraints,
+    ui_shape_control_points: &ShapeControlPoints,
+    style: &dyn style::Style,
+) -> Option<Constraint> {
+    let mouse_hover_pos = mouse_hover_pos?;
+    constraints
+        .constraints()
+        .filter_map(|constraint| {
+            let distance = constraint_glyph_position(constraint, ui_shape_control_points, style)?
+                .distance(mouse_hover_pos);
+            (distance <= style.constraint_glyph_radius()).then_some((*constraint, distance))
+        })
+        .min_by_key(|(_, distance)| index::not_nan_f32(*distance))
+        .map(|(constraint, _)| constraint)
+}
+
+pub(crate) fn paint_constraints(
+    ctx: &CanvasContext,
+    constraints: &Constraints,
+    selection: &Selection,
+    style: &dyn style::Style,
+) {
+    puffin_egui::puffin::profile_function!();
+    let viewport = ctx.transform.canvas_content_viewport();
+    if selection.has_control_points() {
+        for index in constraints.affected_points(selection) {
+            if selection.is_control_point_selected(&index) {
+                continue;
+            }
+            if let Some(pos) = ctx.ui_shape_control_points.pos_by_index(&index) {
+                ctx.painter.circle_stroke(
+                    pos,
+                    style.control_point_radius() * 1.5,
+                    style.constraint_link_stroke(),
+                );
+            }
+        }
+    }
+    for constraint in constraints.constraints() {
+        let Some(glyph_position) =
+            constraint_glyph_position(constraint, &ctx.ui_shape_control_points, style)
+        else {
+            continue;
+        };
+        let selected = selection.selected_constraint() == Some(constraint)
+            || ctx.hovered_constraint.as_ref() == Some(constraint);
+        let positions: Vec<Pos2> = constraint
+            .points()
+            .iter()
+            .filter_map(|index| ctx.ui_shape_control_points.pos_by_index(index))
+            .collect();
+        match constraint {
+            Constraint::PointPositionRange(_, position_range) => {
+                let range_rect = position_range.to_rect().intersect(viewport);
+                if range_rect.is_positive() {
+                    ctx.painter.rect_stroke(
+                        ctx.transform
+                            .canvas_content_to_ui
+                            .transform_rect(&range_rect),
+                        0.0,
+                        style.constraint_range_stroke(),
+                    );
+                }
+                ctx.painter
+                    .add(style.constraint_lock_shape(glyph_position, selected));
+            }
+            Constraint::LinkTranslationBidirectional(_, _)
+            | Constraint::LinkTranslationFromTo(_, _) => {
+                ctx.painter
+                    .line_segment([positions[0], positions[1]], style.constraint_link_stroke());
+                ctx.painter
+                    .add(style.constraint_glyph_shape(glyph_position, selected));
+            }
+            _ => {
+                ctx.painter.add(Shape::Vec(
+                    positions
+                        .iter()
+                        .flat_map(|pos| {
+                            Shape::dashed_line(
+                                &[glyph_position, *pos],
+                                style.constraint_link_stroke(),
+                                style.constraint_glyph_radius(),
+                                style.constraint_glyph_radius(),
+                            )
+                        })
+                        .collect(),
+                ));
+                ctx.painter
+                    .add(style.constraint_glyph_shape(glyph_position, selected));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::style::{Light, Style};
+    use egui::epaint::PathShape;
+    use egui::{Color32, Stroke};
+
+    fn control_points() -> ShapeControlPoints {
+        ShapeControlPoints::collect(&mut Shape::Vec(vec![Shape::Path(PathShape::line(
+            vec![
+                Pos2::new(0.0, 0.0),
+                Pos2::new(20.0, 0.0),
+                Pos2::new(20.0, 40.0),
+            ],
+            Stroke::new(1.0, Color32::BLACK),
+        ))]))
+    }
+
+    #[test]
+    fn glyph_sits_at_the_centroid_of_constraint_points() {
+        let style = Light::default();
+        let control_points = control_points();
+        assert_eq!(
+            constraint_glyph_position(
+                &Constraint::Horizontal((0, 0).into(), (0, 1).into()),
+                &control_points,
+                &style
+            ),
+            Some(Pos2::new(10.0, 0.0))
+        );
+        assert_eq!(
+            constraint_glyph_position(
+                &Constraint::PointOnLine((0, 0).into(), (0, 1).into(), (0, 2).into()),
+                &control_points,
+                &style
+            ),
+            Some(Pos2::new(40.0 / 3.0, 40.0 / 3.0))
+        );
+        assert_eq!(
+            constraint_glyph_position(
+                &Constraint::Horizontal((0, 0).into(), (0, 3).into()),
+                &control_points,
+                &style
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn range_glyph_is_offset_from_its_point() {
+        let style = Light::default();
+        let offset = style.constraint_glyph_radius() * 2.0;
+        assert_eq!(
+            constraint_glyph_position(
+                &Constraint::PointPositionRange((0, 1).into(), (.., ..).into()),
+                &control_points(),
+                &style
+            ),
+            Some(Pos2::new(20.0 + offset, -offset))
+        );
+    }
+
+    #[test]
+    fn hover_picks_the_closest_glyph_within_radius() {
+        let style = Light::default();
+        let control_points = control_points();
+        let horizontal = Constraint::Horizontal((0, 0).into(), (0, 1).into());
+        let vertical = Constraint::Vertical((0, 1).into(), (0, 2).into());
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(horizontal);
+        constraints.add_constraint(vertical);
+
+        let hovered = |pos| hovered_constraint(pos, &constraints, &control_points, &style);
+        assert_eq!(hovered(Some(Pos2::new(11.0, 1.0))), Some(horizontal));
+        assert_eq!(hovered(Some(Pos2::new(20.0, 18.0))), Some(vertical));
+        assert_eq!(hovered(Some(Pos2::new(10.0, 10.0))), None);
+        assert_eq!(hovered(None), None);
+    }
+}
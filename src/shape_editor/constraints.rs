@@ -1,16 +1,20 @@
 use crate::shape_editor::shape_visitor::ShapePointIndex;
+use crate::shape_editor::Selection;
 use egui::ahash::{HashMap, HashSet};
-use egui::{Pos2, Vec2};
+use egui::{Pos2, Rect, Vec2};
 use num_traits::Bounded;
 use ordered_float::NotNan;
 use std::ops::{Bound, RangeBounds};
 
+mod solver;
+
 #[derive(Default, Clone)]
 pub struct Constraints {
     constraints: HashSet<Constraint>,
 
-    pub(crate) translation_propagation: HashMap<ShapePointIndex, HashSet<ShapePointIndex>>,
+    pub(crate) translation_closure: HashMap<ShapePointIndex, HashSet<ShapePointIndex>>,
     pub(crate) point_position_range: HashMap<ShapePointIndex, PositionRange>,
+    pub(crate) geometric: Vec<Constraint>,
 }
 
 #[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
@@ -40,37 +44,117 @@ impl Constraints {
         self.constraints.iter()
     }
 
+    pub(crate) fn remap_points(
+        &mut self,
+        remap: impl Fn(&ShapePointIndex) -> Option<ShapePointIndex>,
+    ) -> Vec<Constraint> {
+        let mut removed = Vec::new();
+        self.constraints = self
+            .constraints
+            .iter()
+            .filter_map(|constraint| {
+                let remapped = constraint.remap_points(&remap);
+                if remapped.is_none() {
+                    removed.push(*constraint);
+                }
+                remapped
+            })
+            .collect();
+        self.rebuild_index();
+        removed
+    }
+
+    pub(crate) fn geometric_points(&self) -> HashSet<ShapePointIndex> {
+        self.geometric
+            .iter()
+            .flat_map(|constraint| constraint.points())
+            .collect()
+    }
+
+    pub(crate) fn solve(
+        &self,
+        positions: &mut HashMap<ShapePointIndex, Pos2>,
+        fixed: &HashSet<ShapePointIndex>,
+    ) {
+        solver::Solver::new(positions, fixed, &self.point_position_range).solve(&self.geometric);
+    }
+
+    pub fn affected_points(&self, selection: &Selection) -> HashSet<ShapePointIndex> {
+        let mut affected: HashSet<ShapePointIndex> =
+            selection.control_points().iter().copied().collect();
+        for index in selection.control_points() {
+            if let Some(closure) = self.translation_closure.get(index) {
+                affected.extend(closure);
+            }
+        }
+        loop {
+            let connected: Vec<ShapePointIndex> = self
+                .geometric
+                .iter()
+                .map(|constraint| constraint.points())
+                .filter(|points| points.iter().any(|index| affected.contains(index)))
+                .flatten()
+                .filter(|index| !affected.contains(index))
+                .collect();
+            if connected.is_empty() {
+                break;
+            }
+            affected.extend(connected);
+        }
+        affected
+    }
+
     fn rebuild_index(&mut self) {
         self.clear_index();
+        let mut translation_propagation = HashMap::default();
         for &constraint in &self.constraints {
             match constraint {
                 Constraint::LinkTranslationBidirectional(index1, index2) => {
-                    insert_translation_propagation(
-                        &mut self.translation_propagation,
-                        index1,
-                        index2,
-                    );
-                    insert_translation_propagation(
-                        &mut self.translation_propagation,
-                        index2,
-                        index1,
-                    );
+                    insert_translation_propagation(&mut translation_propagation, index1, index2);
+                    insert_translation_propagation(&mut translation_propagation, index2, index1);
                 }
                 Constraint::LinkTranslationFromTo(from, to) => {
-                    insert_translation_propagation(&mut self.translation_propagation, from, to);
+                    insert_translation_propagation(&mut translation_propagation, from, to);
                 }
                 Constraint::PointPositionRange(index, position_range) => {
                     self.point_position_range.insert(index, position_range);
                 }
+                _ => {
+                    self.geometric.push(constraint);
+                }
             }
         }
+        self.geometric.sort_unstable_by_key(Constraint::solve_order);
+        self.translation_closure = translation_closure(&translation_propagation);
     }
 
     fn clear_index(&mut self) {
-        self.translation_propagation.clear();
+        self.translation_closure.clear();
+        self.point_position_range.clear();
+        self.geometric.clear();
     }
 }
 
+fn translation_closure(
+    translation_propagation: &HashMap<ShapePointIndex, HashSet<ShapePointIndex>>,
+) -> HashMap<ShapePointIndex, HashSet<ShapePointIndex>> {
+    translation_propagation
+        .keys()
+        .map(|&from| {
+            let mut reachable = HashSet::default();
+            let mut stack = vec![from];
+            while let Some(index) = stack.pop() {
+                for &to in translation_propagation.get(&index).into_iter().flatten() {
+                    if to != from && reachable.insert(to) {
+                        stack.push(to);
+                    }
+                }
+            }
+            (from, reachable)
+        })
+        .collect()
+}
+
 fn insert_translation_propagation(
     translation_propagation: &mut HashMap<ShapePointIndex, HashSet<ShapePointIndex>>,
     from: ShapePointIndex,
@@ -84,14 +168,174 @@ fn insert_translation_propagation(
         .or_insert_with(|| HashSet::from_iter([to]));
 }
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug)]
 pub enum Constraint {
     LinkTranslationBidirectional(ShapePointIndex, ShapePointIndex),
     LinkTranslationFromTo(ShapePointIndex, ShapePointIndex),
     PointPositionRange(ShapePointIndex, PositionRange),
+    Horizontal(ShapePointIndex, ShapePointIndex),
+    Vertical(ShapePointIndex, ShapePointIndex),
+    Coincident(ShapePointIndex, ShapePointIndex),
+    FixedDistance(ShapePointIndex, ShapePointIndex, NotNan<f32>),
+    PointOnLine(ShapePointIndex, ShapePointIndex, ShapePointIndex),
+    Parallel(
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+    ),
+    Perpendicular(
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+    ),
+    EqualLength(
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+    ),
+    FixedAngle(
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+        NotNan<f32>,
+    ),
+    Tangent(
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+        ShapePointIndex,
+    ),
+}
+
+impl Constraint {
+    pub fn fixed_distance(
+        index1: ShapePointIndex,
+        index2: ShapePointIndex,
+        distance: f32,
+    ) -> Option<Self> {
+        NotNan::new(distance)
+            .ok()
+            .map(|distance| Constraint::FixedDistance(index1, index2, distance))
+    }
+
+    pub fn fixed_angle(
+        line1: (ShapePointIndex, ShapePointIndex),
+        line2: (ShapePointIndex, ShapePointIndex),
+        angle: f32,
+    ) -> Option<Self> {
+        NotNan::new(angle)
+            .ok()
+            .map(|angle| Constraint::FixedAngle(line1.0, line1.1, line2.0, line2.1, angle))
+    }
+
+    pub(crate) fn remap_points(
+        &self,
+        remap: impl Fn(&ShapePointIndex) -> Option<ShapePointIndex>,
+    ) -> Option<Self> {
+        Some(match self {
+            Constraint::LinkTranslationBidirectional(index1, index2) => {
+                Constraint::LinkTranslationBidirectional(remap(index1)?, remap(index2)?)
+            }
+            Constraint::LinkTranslationFromTo(from, to) => {
+                Constraint::LinkTranslationFromTo(remap(from)?, remap(to)?)
+            }
+            Constraint::PointPositionRange(index, position_range) => {
+                Constraint::PointPositionRange(remap(index)?, *position_range)
+            }
+            Constraint::Horizontal(index1, index2) => {
+                Constraint::Horizontal(remap(index1)?, remap(index2)?)
+            }
+            Constraint::Vertical(index1, index2) => {
+                Constraint::Vertical(remap(index1)?, remap(index2)?)
+            }
+            Constraint::Coincident(index1, index2) => {
+                Constraint::Coincident(remap(index1)?, remap(index2)?)
+            }
+            Constraint::FixedDistance(index1, index2, distance) => {
+                Constraint::FixedDistance(remap(index1)?, remap(index2)?, *distance)
+            }
+            Constraint::PointOnLine(point, start, end) => {
+                Constraint::PointOnLine(remap(point)?, remap(start)?, remap(end)?)
+            }
+            Constraint::Parallel(a1, a2, b1, b2) => {
+                Constraint::Parallel(remap(a1)?, remap(a2)?, remap(b1)?, remap(b2)?)
+            }
+            Constraint::Perpendicular(a1, a2, b1, b2) => {
+                Constraint::Perpendicular(remap(a1)?, remap(a2)?, remap(b1)?, remap(b2)?)
+            }
+            Constraint::EqualLength(a1, a2, b1, b2) => {
+                Constraint::EqualLength(remap(a1)?, remap(a2)?, remap(b1)?, remap(b2)?)
+            }
+            Constraint::FixedAngle(a1, a2, b1, b2, angle) => {
+                Constraint::FixedAngle(remap(a1)?, remap(a2)?, remap(b1)?, remap(b2)?, *angle)
+            }
+            Constraint::Tangent(start, end, center, radius) => {
+                Constraint::Tangent(remap(start)?, remap(end)?, remap(center)?, remap(radius)?)
+            }
+        })
+    }
+
+    pub fn points(&self) -> Vec<ShapePointIndex> {
+        match *self {
+            Constraint::LinkTranslationBidirectional(index1, index2)
+            | Constraint::LinkTranslationFromTo(index1, index2)
+            | Constraint::Horizontal(index1, index2)
+            | Constraint::Vertical(index1, index2)
+            | Constraint::Coincident(index1, index2)
+            | Constraint::FixedDistance(index1, index2, _) => vec![index1, index2],
+            Constraint::PointPositionRange(index, _) => vec![index],
+            Constraint::PointOnLine(point, start, end) => vec![point, start, end],
+            Constraint::Parallel(a1, a2, b1, b2)
+            | Constraint::Perpendicular(a1, a2, b1, b2)
+            | Constraint::EqualLength(a1, a2, b1, b2)
+            | Constraint::FixedAngle(a1, a2, b1, b2, _)
+            | Constraint::Tangent(a1, a2, b1, b2) => vec![a1, a2, b1, b2],
+        }
+    }
+
+    fn solve_order(&self) -> (Vec<ShapePointIndex>, u8, Option<NotNan<f32>>) {
+        let (kind, value) = match *self {
+            Constraint::LinkTranslationBidirectional(_, _) => (0, None),
+            Constraint::LinkTranslationFromTo(_, _) => (1, None),
+            Constraint::PointPositionRange(_, _) => (2, None),
+            Constraint::Horizontal(_, _) => (3, None),
+            Constraint::Vertical(_, _) => (4, None),
+            Constraint::Coincident(_, _) => (5, None),
+            Constraint::FixedDistance(_, _, distance) => (6, Some(distance)),
+            Constraint::PointOnLine(_, _, _) => (7, None),
+            Constraint::Parallel(_, _, _, _) => (8, None),
+            Constraint::Perpendicular(_, _, _, _) => (9, None),
+            Constraint::EqualLength(_, _, _, _) => (10, None),
+            Constraint::FixedAngle(_, _, _, _, angle) => (11, Some(angle)),
+            Constraint::Tangent(_, _, _, _) => (12, None),
+        };
+        (self.points(), kind, value)
+    }
 }
 
 impl PositionRange {
+    pub fn to_rect(&self) -> Rect {
+        Rect::from_min_max(
+            Pos2::new(
+                bound_value(self.x_min, f32::NEG_INFINITY),
+                bound_value(self.y_min, f32::NEG_INFINITY),
+            ),
+            Pos2::new(
+                bound_value(self.x_max, f32::INFINITY),
+                bound_value(self.y_max, f32::INFINITY),
+            ),
+        )
+    }
+
+    pub fn clamp_position(&self, position: Pos2) -> Pos2 {
+        let rect = self.to_rect();
+        position.clamp(rect.min, rect.max)
+    }
+
     pub fn clamp_translation(&self, mut translation: Vec2, position: Pos2) -> Vec2 {
         match self.x_max {
             Bound::Included(x_max) => {
@@ -137,6 +381,13 @@ impl PositionRange {
     }
 }
 
+fn bound_value(bound: Bound<NotNan<f32>>, unbounded: f32) -> f32 {
+    match bound {
+        Bound::Included(value) | Bound::Excluded(value) => *value,
+        Bound::Unbounded => unbounded,
+    }
+}
+
 impl<X: RangeBounds<f32>, Y: RangeBounds<f32>> From<(X, Y)> for PositionRange {
     fn from(value: (X, Y)) -> Self {
         let x_min = value
@@ -164,3 +415,84 @@ impl<X: RangeBounds<f32>, Y: RangeBounds<f32>> From<(X, Y)> for PositionRange {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(shape_index: usize, point_index: usize) -> ShapePointIndex {
+        (shape_index, point_index).into()
+    }
+
+    fn constraints(constraints: &[Constraint]) -> Constraints {
+        let mut all = Constraints::default();
+        for constraint in constraints {
+            all.add_constraint(*constraint);
+        }
+        all
+    }
+
+    #[test]
+    fn translation_closure_is_transitive() {
+        let constraints = constraints(&[
+            Constraint::LinkTranslationFromTo(index(0, 0), index(0, 1)),
+            Constraint::LinkTranslationFromTo(index(0, 1), index(1, 0)),
+        ]);
+        assert_eq!(
+            constraints.translation_closure[&index(0, 0)],
+            HashSet::from_iter([index(0, 1), index(1, 0)])
+        );
+        assert_eq!(
+            constraints.translation_closure[&index(0, 1)],
+            HashSet::from_iter([index(1, 0)])
+        );
+        assert!(!constraints.translation_closure.contains_key(&index(1, 0)));
+    }
+
+    #[test]
+    fn translation_closure_survives_cycles() {
+        let constraints = constraints(&[
+            Constraint::LinkTranslationBidirectional(index(0, 0), index(0, 1)),
+            Constraint::LinkTranslationFromTo(index(0, 1), index(0, 2)),
+            Constraint::LinkTranslationFromTo(index(0, 2), index(0, 0)),
+        ]);
+        assert_eq!(
+            constraints.translation_closure[&index(0, 0)],
+            HashSet::from_iter([index(0, 1), index(0, 2)])
+        );
+        assert_eq!(
+            constraints.translation_closure[&index(0, 2)],
+            HashSet::from_iter([index(0, 0), index(0, 1)])
+        );
+    }
+
+    #[test]
+    fn affected_points_follow_links_and_geometric_constraints() {
+        let constraints = constraints(&[
+            Constraint::LinkTranslationFromTo(index(0, 0), index(0, 1)),
+            Constraint::Horizontal(index(0, 1), index(1, 0)),
+            Constraint::Vertical(index(1, 0), index(1, 1)),
+            Constraint::LinkTranslationFromTo(index(2, 0), index(0, 0)),
+        ]);
+        let mut selection = Selection::default();
+        selection.select_control_point(index(0, 0));
+        assert_eq!(
+            constraints.affected_points(&selection),
+            HashSet::from_iter([index(0, 0), index(0, 1), index(1, 0), index(1, 1)])
+        );
+    }
+
+    #[test]
+    fn link_and_range_constraints_are_not_geometric() {
+        let constraints = constraints(&[
+            Constraint::LinkTranslationBidirectional(index(0, 0), index(0, 1)),
+            Constraint::PointPositionRange(index(0, 2), (0.0..1.0, ..).into()),
+            Constraint::Coincident(index(1, 0), index(1, 1)),
+        ]);
+        assert_eq!(
+            constraints.geometric_points(),
+            HashSet::from_iter([index(1, 0), index(1, 1)])
+        );
+        assert!(constraints.point_position_range.contains_key(&index(0, 2)));
+    }
+}
@@ -0,0 +1,499 @@
+use crate::shape_editor::constraints::{Constraint, PositionRange};
+use crate::shape_editor::shape_visitor::ShapePointIndex;
+use egui::ahash::{HashMap, HashSet};
+use egui::{Pos2, Vec2};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+const MAX_ITERATIONS: usize = 64;
+const TOLERANCE: f32 = 1e-3;
+
+pub(super) struct Solver<'a> {
+    positions: &'a mut HashMap<ShapePointIndex, Pos2>,
+    fixed: &'a HashSet<ShapePointIndex>,
+    ranges: &'a HashMap<ShapePointIndex, PositionRange>,
+}
+
+impl<'a> Solver<'a> {
+    pub(super) fn new(
+        positions: &'a mut HashMap<ShapePointIndex, Pos2>,
+        fixed: &'a HashSet<ShapePointIndex>,
+        ranges: &'a HashMap<ShapePointIndex, PositionRange>,
+    ) -> Self {
+        Self {
+            positions,
+            fixed,
+            ranges,
+        }
+    }
+
+    pub(super) fn solve(&mut self, constraints: &[Constraint]) {
+        for _ in 0..MAX_ITERATIONS {
+            let error = constraints
+                .iter()
+                .map(|constraint| self.project(constraint))
+                .fold(0.0, f32::max);
+            self.clamp_to_ranges();
+            if error < TOLERANCE {
+                break;
+            }
+        }
+    }
+
+    fn clamp_to_ranges(&mut self) {
+        for (index, range) in self.ranges {
+            if !self.fixed.contains(index) {
+                if let Some(position) = self.positions.get_mut(index) {
+                    *position = range.clamp_position(*position);
+                }
+            }
+        }
+    }
+
+    fn project(&mut self, constraint: &Constraint) -> f32 {
+        self.try_project(constraint).unwrap_or(0.0)
+    }
+
+    fn try_project(&mut self, constraint: &Constraint) -> Option<f32> {
+        match *constraint {
+            Constraint::Horizontal(a, b) => {
+                let delta = Vec2::new(0.0, self.pos(a)?.y - self.pos(b)?.y);
+                self.move_groups(&[a], &[b], delta)
+            }
+            Constraint::Vertical(a, b) => {
+                let delta = Vec2::new(self.pos(a)?.x - self.pos(b)?.x, 0.0);
+                self.move_groups(&[a], &[b], delta)
+            }
+            Constraint::Coincident(a, b) => {
+                let delta = self.pos(a)? - self.pos(b)?;
+                self.move_groups(&[a], &[b], delta)
+            }
+            Constraint::FixedDistance(a, b, distance) => {
+                let vector = self.pos(b)? - self.pos(a)?;
+                let length = vector.length();
+                let direction = if length > f32::EPSILON {
+                    vector / length
+                } else {
+                    Vec2::X
+                };
+                self.move_groups(&[a], &[b], direction * (*distance - length))
+            }
+            Constraint::PointOnLine(point, start, end) => {
+                let start_pos = self.pos(start)?;
+                let direction = (self.pos(end)? - start_pos).normalized();
+                let point_pos = self.pos(point)?;
+                let projection = start_pos + direction * (point_pos - start_pos).dot(direction);
+                self.move_groups(&[start, end], &[point], projection - point_pos)
+            }
+            Constraint::Tangent(start, end, center, radius_point) => {
+                let start_pos = self.pos(start)?;
+                let center_pos = self.pos(center)?;
+                let radius = self.pos(radius_point)?.distance(center_pos);
+                let normal = (self.pos(end)? - start_pos).normalized().rot90();
+                let distance = (center_pos - start_pos).dot(normal);
+                let target = if distance < 0.0 { -radius } else { radius };
+                self.move_groups(
+                    &[start, end],
+                    &[center, radius_point],
+                    normal * (target - distance),
+                )
+            }
+            Constraint::Parallel(a1, a2, b1, b2) => self.align_lines((a1, a2), (b1, b2), 0.0, PI),
+            Constraint::Perpendicular(a1, a2, b1, b2) => {
+                self.align_lines((a1, a2), (b1, b2), FRAC_PI_2, PI)
+            }
+            Constraint::FixedAngle(a1, a2, b1, b2, angle) => {
+                self.align_lines((a1, a2), (b1, b2), *angle, TAU)
+            }
+            Constraint::EqualLength(a1, a2, b1, b2) => {
+                let length_a = self.pos(a1)?.distance(self.pos(a2)?);
+                let length_b = self.pos(b1)?.distance(self.pos(b2)?);
+                let error = (length_a - length_b).abs();
+                let target = match (self.is_line_free(a1, a2), self.is_line_free(b1, b2)) {
+                    (true, true) => (length_a + length_b) / 2.0,
+                    (false, true) => length_a,
+                    (true, false) => length_b,
+                    (false, false) => return Some(0.0),
+                };
+                self.scale_line(a1, a2, target);
+                self.scale_line(b1, b2, target);
+                Some(error)
+            }
+            Constraint::LinkTranslationBidirectional(_, _)
+            | Constraint::LinkTranslationFromTo(_, _)
+            | Constraint::PointPositionRange(_, _) => None,
+        }
+    }
+
+    fn align_lines(
+        &mut self,
+        line_a: (ShapePointIndex, ShapePointIndex),
+        line_b: (ShapePointIndex, ShapePointIndex),
+        angle: f32,
+        period: f32,
+    ) -> Option<f32> {
+        let vector_a = self.pos(line_a.1)? - self.pos(line_a.0)?;
+        let vector_b = self.pos(line_b.1)? - self.pos(line_b.0)?;
+        let diff = wrap_angle(vector_a.angle() + angle - vector_b.angle(), period);
+        let error = diff.abs() * vector_a.length().max(vector_b.length());
+        match (
+            self.is_line_free(line_a.0, line_a.1),
+            self.is_line_free(line_b.0, line_b.1),
+        ) {
+            (true, true) => {
+                self.rotate_line(line_a.0, line_a.1, -diff / 2.0);
+                self.rotate_line(line_b.0, line_b.1, diff / 2.0);
+            }
+            (true, false) => self.rotate_line(line_a.0, line_a.1, -diff),
+            (false, true) => self.rotate_line(line_b.0, line_b.1, diff),
+            (false, false) => return Some(0.0),
+        }
+        Some(error)
+    }
+
+    fn rotate_line(&mut self, start: ShapePointIndex, end: ShapePointIndex, angle: f32) {
+        let (Some(start_pos), Some(end_pos)) = (self.pos(start), self.pos(end)) else {
+            return;
+        };
+        let pivot = match (self.is_free(&start), self.is_free(&end)) {
+            (true, true) => start_pos.lerp(end_pos, 0.5),
+            (false, true) => start_pos,
+            (true, false) => end_pos,
+            (false, false) => return,
+        };
+        let rotation = egui::emath::Rot2::from_angle(angle);
+        self.set_pos(start, pivot + rotation * (start_pos - pivot));
+        self.set_pos(end, pivot + rotation * (end_pos - pivot));
+    }
+
+    fn scale_line(&mut self, start: ShapePointIndex, end: ShapePointIndex, length: f32) {
+        let (Some(start_pos), Some(end_pos)) = (self.pos(start), self.pos(end)) else {
+            return;
+        };
+        let vector = end_pos - start_pos;
+        let direction = if vector.length() > f32::EPSILON {
+            vector.normalized()
+        } else {
+            Vec2::X
+        };
+        match (self.is_free(&start), self.is_free(&end)) {
+            (true, true) => {
+                let center = start_pos.lerp(end_pos, 0.5);
+                self.set_pos(start, center - direction * length / 2.0);
+                self.set_pos(end, center + direction * length / 2.0);
+            }
+            (false, true) => self.set_pos(end, start_pos + direction * length),
+            (true, false) => self.set_pos(start, end_pos - direction * length),
+            (false, false) => {}
+        }
+    }
+
+    fn move_groups(
+        &mut self,
+        group_a: &[ShapePointIndex],
+        group_b: &[ShapePointIndex],
+        delta: Vec2,
+    ) -> Option<f32> {
+        let free_a = group_a.iter().all(|index| self.is_free(index));
+        let free_b = group_b.iter().all(|index| self.is_free(index));
+        let (factor_a, factor_b) = match (free_a, free_b) {
+            (true, true) => (-0.5, 0.5),
+            (true, false) => (-1.0, 0.0),
+            (false, true) => (0.0, 1.0),
+            (false, false) => return Some(0.0),
+        };
+        for index in group_a {
+            self.translate(*index, delta * factor_a);
+        }
+        for index in group_b {
+            self.translate(*index, delta * factor_b);
+        }
+        Some(delta.length())
+    }
+
+    fn is_line_free(&self, start: ShapePointIndex, end: ShapePointIndex) -> bool {
+        self.is_free(&start) || self.is_free(&end)
+    }
+
+    fn is_free(&self, index: &ShapePointIndex) -> bool {
+        !self.fixed.contains(index) && self.positions.contains_key(index)
+    }
+
+    fn pos(&self, index: ShapePointIndex) -> Option<Pos2> {
+        self.positions.get(&index).copied()
+    }
+
+    fn set_pos(&mut self, index: ShapePointIndex, pos: Pos2) {
+        if let Some(position) = self.positions.get_mut(&index) {
+            *position = pos;
+        }
+    }
+
+    fn translate(&mut self, index: ShapePointIndex, translation: Vec2) {
+        if let Some(position) = self.positions.get_mut(&index) {
+            *position += translation;
+        }
+    }
+}
+
+fn wrap_angle(angle: f32, period: f32) -> f32 {
+    let wrapped = angle.rem_euclid(period);
+    if wrapped > period / 2.0 {
+        wrapped - period
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::constraints::Constraints;
+
+    fn index(point_index: usize) -> ShapePointIndex {
+        (0, point_index).into()
+    }
+
+    fn solve(
+        constraints: &[Constraint],
+        points: &[(f32, f32)],
+        fixed: &[usize],
+    ) -> HashMap<ShapePointIndex, Pos2> {
+        let mut all = Constraints::default();
+        for constraint in constraints {
+            all.add_constraint(*constraint);
+        }
+        let mut positions = points
+            .iter()
+            .enumerate()
+            .map(|(i, (x, y))| (index(i), Pos2::new(*x, *y)))
+            .collect();
+        all.solve(&mut positions, &fixed.iter().copied().map(index).collect());
+        positions
+    }
+
+    fn vector(positions: &HashMap<ShapePointIndex, Pos2>, start: usize, end: usize) -> Vec2 {
+        positions[&index(end)] - positions[&index(start)]
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-2,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn assert_pos(actual: Pos2, expected: (f32, f32)) {
+        assert_near(actual.x, expected.0);
+        assert_near(actual.y, expected.1);
+    }
+
+    #[test]
+    fn horizontal_moves_free_point() {
+        let positions = solve(
+            &[Constraint::Horizontal(index(0), index(1))],
+            &[(0.0, 0.0), (1.0, 1.0)],
+            &[0],
+        );
+        assert_pos(positions[&index(0)], (0.0, 0.0));
+        assert_pos(positions[&index(1)], (1.0, 0.0));
+    }
+
+    #[test]
+    fn vertical_moves_free_point() {
+        let positions = solve(
+            &[Constraint::Vertical(index(0), index(1))],
+            &[(0.0, 0.0), (1.0, 1.0)],
+            &[1],
+        );
+        assert_pos(positions[&index(0)], (1.0, 0.0));
+        assert_pos(positions[&index(1)], (1.0, 1.0));
+    }
+
+    #[test]
+    fn coincident_meets_halfway_when_both_free() {
+        let positions = solve(
+            &[Constraint::Coincident(index(0), index(1))],
+            &[(0.0, 0.0), (2.0, 4.0)],
+            &[],
+        );
+        assert_pos(positions[&index(0)], (1.0, 2.0));
+        assert_pos(positions[&index(1)], (1.0, 2.0));
+    }
+
+    #[test]
+    fn fixed_distance_keeps_direction() {
+        let positions = solve(
+            &[Constraint::fixed_distance(index(0), index(1), 10.0).unwrap()],
+            &[(0.0, 0.0), (3.0, 4.0)],
+            &[0],
+        );
+        assert_pos(positions[&index(1)], (6.0, 8.0));
+    }
+
+    #[test]
+    fn point_on_line_projects_point() {
+        let positions = solve(
+            &[Constraint::PointOnLine(index(2), index(0), index(1))],
+            &[(0.0, 0.0), (2.0, 0.0), (1.0, 1.0)],
+            &[0, 1],
+        );
+        assert_pos(positions[&index(2)], (1.0, 0.0));
+    }
+
+    #[test]
+    fn tangent_moves_circle_to_radius_distance() {
+        let positions = solve(
+            &[Constraint::Tangent(index(0), index(1), index(2), index(3))],
+            &[(0.0, 0.0), (4.0, 0.0), (2.0, 3.0), (2.0, 4.0)],
+            &[0, 1],
+        );
+        assert_pos(positions[&index(2)], (2.0, 1.0));
+        assert_pos(positions[&index(3)], (2.0, 2.0));
+    }
+
+    #[test]
+    fn parallel_rotates_free_line() {
+        let positions = solve(
+            &[Constraint::Parallel(index(0), index(1), index(2), index(3))],
+            &[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 2.0)],
+            &[0, 1],
+        );
+        assert_pos(positions[&index(1)], (1.0, 0.0));
+        assert_near(vector(&positions, 2, 3).y, 0.0);
+        assert_near(vector(&positions, 2, 3).length(), 2.0_f32.sqrt());
+    }
+
+    #[test]
+    fn perpendicular_rotates_free_line() {
+        let positions = solve(
+            &[Constraint::Perpendicular(
+                index(0),
+                index(1),
+                index(2),
+                index(3),
+            )],
+            &[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 2.0)],
+            &[0, 1],
+        );
+        assert_near(vector(&positions, 0, 1).dot(vector(&positions, 2, 3)), 0.0);
+    }
+
+    #[test]
+    fn fixed_angle_rotates_free_line() {
+        let positions =
+            solve(
+                &[Constraint::fixed_angle(
+                    (index(0), index(1)),
+                    (index(2), index(3)),
+                    FRAC_PI_2 / 2.0,
+                )
+                .unwrap()],
+                &[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.0, 3.0)],
+                &[0, 1, 2],
+            );
+        assert_near(vector(&positions, 2, 3).angle(), FRAC_PI_2 / 2.0);
+        assert_near(vector(&positions, 2, 3).length(), 2.0);
+    }
+
+    #[test]
+    fn equal_length_scales_free_line() {
+        let positions = solve(
+            &[Constraint::EqualLength(
+                index(0),
+                index(1),
+                index(2),
+                index(3),
+            )],
+            &[(0.0, 0.0), (2.0, 0.0), (0.0, 1.0), (4.0, 1.0)],
+            &[0, 1, 2],
+        );
+        assert_pos(positions[&index(3)], (2.0, 1.0));
+    }
+
+    #[test]
+    fn equal_length_averages_free_lines() {
+        let positions = solve(
+            &[Constraint::EqualLength(
+                index(0),
+                index(1),
+                index(2),
+                index(3),
+            )],
+            &[(0.0, 0.0), (2.0, 0.0), (0.0, 1.0), (4.0, 1.0)],
+            &[],
+        );
+        assert_near(vector(&positions, 0, 1).length(), 3.0);
+        assert_near(vector(&positions, 2, 3).length(), 3.0);
+    }
+
+    #[test]
+    fn translation_links_are_not_solved() {
+        let points = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)];
+        let positions = solve(
+            &[
+                Constraint::LinkTranslationBidirectional(index(0), index(1)),
+                Constraint::LinkTranslationFromTo(index(1), index(2)),
+            ],
+            &points,
+            &[],
+        );
+        for (i, point) in points.into_iter().enumerate() {
+            assert_pos(positions[&index(i)], point);
+        }
+    }
+
+    #[test]
+    fn fully_fixed_constraint_is_left_alone() {
+        let positions = solve(
+            &[Constraint::Coincident(index(0), index(1))],
+            &[(0.0, 0.0), (1.0, 1.0)],
+            &[0, 1],
+        );
+        assert_pos(positions[&index(0)], (0.0, 0.0));
+        assert_pos(positions[&index(1)], (1.0, 1.0));
+    }
+
+    #[test]
+    fn chained_constraints_converge() {
+        let positions = solve(
+            &[
+                Constraint::Horizontal(index(0), index(1)),
+                Constraint::Vertical(index(1), index(2)),
+                Constraint::fixed_distance(index(1), index(2), 2.0).unwrap(),
+            ],
+            &[(0.0, 0.0), (3.0, 1.0), (4.0, 5.0)],
+            &[0],
+        );
+        assert_near(positions[&index(1)].y, 0.0);
+        assert_near(positions[&index(1)].x, positions[&index(2)].x);
+        assert_near(vector(&positions, 1, 2).length(), 2.0);
+    }
+
+    #[test]
+    fn position_range_clamps_free_points() {
+        let positions = solve(
+            &[
+                Constraint::Horizontal(index(0), index(1)),
+                Constraint::PointPositionRange(index(1), (.., ..=0.5).into()),
+            ],
+            &[(0.0, 2.0), (1.0, 0.0)],
+            &[0],
+        );
+        assert_pos(positions[&index(0)], (0.0, 2.0));
+        assert_pos(positions[&index(1)], (1.0, 0.5));
+    }
+
+    #[test]
+    fn position_range_does_not_move_fixed_points() {
+        let positions = solve(
+            &[
+                Constraint::Horizontal(index(0), index(1)),
+                Constraint::PointPositionRange(index(0), (.., ..=0.5).into()),
+            ],
+            &[(0.0, 2.0), (1.0, 0.0)],
+            &[0],
+        );
+        assert_pos(positions[&index(0)], (0.0, 2.0));
+        assert_pos(positions[&index(1)], (1.0, 2.0));
+    }
+}
@@ -4,6 +4,7 @@ use crate::shape_editor::shape_visitor::indexed_shape_control_points_visitor::{
 };
 use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeType, ShapeVisitor};
 use crate::shape_editor::style;
+use crate::shape_editor::transform::Transform;
 use egui::ahash::HashMap;
 use egui::{Color32, Pos2, Rect, Shape, Stroke};
 use std::collections::hash_map::Iter;
@@ -20,13 +21,6 @@ pub enum ShapeControlPoint {
     },
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
-pub enum PointRemovingStrategy {
-    ControlPoint,
-    Shape,
-    None,
-}
-
 impl ShapeControlPoint {
     pub fn position(&self) -> Pos2 {
         match self {
@@ -35,6 +29,24 @@ impl ShapeControlPoint {
         }
     }
 
+    fn transform(&self, transform: &Transform) -> Self {
+        match self {
+            ShapeControlPoint::PathPoint { position } => ShapeControlPoint::PathPoint {
+                position: transform.transform_pos(*position),
+            },
+            ShapeControlPoint::ControlPoint {
+                position,
+                connected_points,
+            } => ShapeControlPoint::ControlPoint {
+                position: transform.transform_pos(*position),
+                connected_points: connected_points
+                    .iter()
+                    .map(|(index, pos)| (*index, transform.transform_pos(*pos)))
+                    .collect(),
+            },
+        }
+    }
+
     fn stroke(&self, style: &dyn style::Style) -> Stroke {
         match self {
             ShapeControlPoint::PathPoint { .. } => style.path_point_stroke(),
@@ -94,6 +106,20 @@ impl ShapeControlPoints {
         slf
     }
 
+    pub fn transform(&self, transform: &Transform) -> Self {
+        puffin_egui::puffin::profile_function!();
+        let mut slf = Self {
+            shapes: self.shapes.clone(),
+            ..Default::default()
+        };
+        for (index, control_point) in &self.control_points {
+            let control_point = control_point.transform(transform);
+            slf.index.insert(control_point.position(), *index);
+            slf.control_points.insert(*index, control_point);
+        }
+        slf
+    }
+
     pub fn snap_x(
         &self,
         pos: Pos2,
@@ -163,11 +189,7 @@ impl ShapeControlPoints {
         self.shapes.get(&index.shape_index).cloned()
     }
 
-    pub fn shape_by_index(&self, shape_index: usize) -> Option<ShapeType> {
-        self.shapes.get(&shape_index).cloned()
-    }
-
-    pub fn iter(&self) -> Iter<ShapePointIndex, ShapeControlPoint> {
+    pub fn iter(&self) -> Iter<'_, ShapePointIndex, ShapeControlPoint> {
         self.control_points.iter()
     }
 }
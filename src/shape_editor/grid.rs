@@ -1,11 +1,95 @@
 use crate::shape_editor::canvas::CanvasContext;
 use crate::shape_editor::index::GridLineType;
-use crate::shape_editor::style;
-use egui::emath::Pos2;
+use crate::shape_editor::units::UnitOptions;
+use crate::shape_editor::{style, utils};
+use egui::emath::{Pos2, Vec2};
 use egui::epaint::Shape;
+use std::f32::consts::{FRAC_PI_6, TAU};
+
+const MIN_SPACING: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridStepProgression {
+    OneTwoFive,
+    PowersOf2,
+    PowersOf5,
+    PowersOf10,
+}
+
+impl GridStepProgression {
+    fn base(&self) -> f32 {
+        match self {
+            GridStepProgression::OneTwoFive => 10.0,
+            GridStepProgression::PowersOf2 => 2.0,
+            GridStepProgression::PowersOf5 => 5.0,
+            GridStepProgression::PowersOf10 => 10.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum GridMode {
+    #[default]
+    Rectangular,
+    Isometric,
+    Polar {
+        spokes: u32,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct GridOptions {
+    pub mode: GridMode,
+    pub spacing: Vec2,
+    pub secondary_subdivisions: u32,
+    pub sub_subdivisions: u32,
+    pub step_progression: GridStepProgression,
+    pub origin: Pos2,
+}
+
+impl Default for GridOptions {
+    fn default() -> Self {
+        Self {
+            mode: GridMode::Rectangular,
+            spacing: Vec2::splat(50.0),
+            secondary_subdivisions: 2,
+            sub_subdivisions: 3,
+            step_progression: GridStepProgression::PowersOf5,
+            origin: Pos2::ZERO,
+        }
+    }
+}
+
+impl GridOptions {
+    pub fn step(&self, scale: Vec2, units: &UnitOptions) -> Vec2 {
+        let step = |spacing: f32, scale: f32| {
+            let spacing = units.to_unit(spacing);
+            match self.step_progression {
+                GridStepProgression::OneTwoFive => utils::nice_grid_step(spacing, scale),
+                progression => utils::grid_step(spacing, scale, progression.base()),
+            }
+        };
+        let spacing = self.spacing.max(Vec2::splat(MIN_SPACING));
+        Vec2::new(
+            units.from_unit(step(spacing.x, scale.x)),
+            units.from_unit(step(spacing.y, scale.y)),
+        )
+    }
+}
 
 pub fn paint_grid(ctx: &CanvasContext, style: &dyn style::Style) {
     puffin_egui::puffin::profile_function!();
+    if !is_valid_step(ctx.grid_index.step.x) || !is_valid_step(ctx.grid_index.step.y) {
+        return;
+    }
+    match ctx.grid_index.mode {
+        GridMode::Rectangular => paint_rectangular_grid(ctx, style),
+        GridMode::Isometric => paint_isometric_grid(ctx, style),
+        GridMode::Polar { spokes } => paint_polar_grid(ctx, spokes, style),
+    }
+}
+
+fn paint_rectangular_grid(ctx: &CanvasContext, style: &dyn style::Style) {
     let canvas_viewport = ctx.transform.canvas_content_viewport();
     let ui_x_range = ctx
         .transform
@@ -78,3 +162,264 @@ pub fn paint_grid(ctx: &CanvasContext, style: &dyn style::Style) {
     }
     ctx.painter.add(Shape::Vec(vec));
 }
+
+fn paint_isometric_grid(ctx: &CanvasContext, style: &dyn style::Style) {
+    let transform = &ctx.transform.canvas_content_to_ui;
+    let grid_index = &ctx.grid_index;
+    let subdivisions = grid_index.secondary_subdivisions as i32;
+    let spacing = grid_index.step.x / subdivisions as f32;
+    let viewport = ctx.transform.canvas_content_viewport();
+    let origin = grid_index.origin;
+    let tan30 = FRAC_PI_6.tan();
+    let column_width = spacing * FRAC_PI_6.cos();
+    let mut vec = Vec::new();
+    let mut add_line = |index: i32, points: [Pos2; 2]| {
+        let points = points.map(|point| transform.transform_pos(point));
+        if index % subdivisions != 0 {
+            vec.extend(Shape::dashed_line(
+                &points,
+                style.grid_line_secondary_stroke(),
+                style.grid_line_secondary_length(),
+                style.grid_line_secondary_gap(),
+            ));
+        } else {
+            vec.push(Shape::line_segment(points, style.grid_isometric_stroke()));
+        }
+    };
+    let min = ((viewport.left() - origin.x) / column_width).floor() as i32;
+    let max = ((viewport.right() - origin.x) / column_width).ceil() as i32;
+    for index in min..=max {
+        let x = origin.x + index as f32 * column_width;
+        add_line(
+            index,
+            [
+                Pos2::new(x, viewport.top()),
+                Pos2::new(x, viewport.bottom()),
+            ],
+        );
+    }
+    for slope in [tan30, -tan30] {
+        let line_offset = |pos: Pos2| (pos.y - origin.y - (pos.x - origin.x) * slope) / spacing;
+        let offsets = [
+            viewport.left_top(),
+            viewport.right_top(),
+            viewport.left_bottom(),
+            viewport.right_bottom(),
+        ]
+        .map(line_offset);
+        let min = offsets.iter().copied().fold(f32::MAX, f32::min).floor() as i32;
+        let max = offsets.iter().copied().fold(f32::MIN, f32::max).ceil() as i32;
+        for index in min..=max {
+            let y_at = |x: f32| origin.y + index as f32 * spacing + (x - origin.x) * slope;
+            add_line(
+                index,
+                [
+                    Pos2::new(viewport.left(), y_at(viewport.left())),
+                    Pos2::new(viewport.right(), y_at(viewport.right())),
+                ],
+            );
+        }
+    }
+    ctx.painter.add(Shape::Vec(vec));
+}
+
+fn paint_polar_grid(ctx: &CanvasContext, spokes: u32, style: &dyn style::Style) {
+    let transform = &ctx.transform.canvas_content_to_ui;
+    let grid_index = &ctx.grid_index;
+    let subdivisions = grid_index.secondary_subdivisions;
+    let spacing = grid_index.step.x / subdivisions as f32;
+    let viewport = ctx.transform.canvas_content_viewport();
+    let center = grid_index.origin;
+    let min_radius = if viewport.contains(center) {
+        0.0
+    } else {
+        viewport.distance_to_pos(center)
+    };
+    let max_radius = [
+        viewport.left_top(),
+        viewport.right_top(),
+        viewport.left_bottom(),
+        viewport.right_bottom(),
+    ]
+    .iter()
+    .map(|corner| corner.distance(center))
+    .fold(0.0, f32::max);
+    let ui_center = transform.transform_pos(center);
+    let scale = transform.scale().x;
+    let mut vec = Vec::new();
+    let min = (min_radius / spacing).floor().max(1.0) as u32;
+    let max = (max_radius / spacing).ceil() as u32;
+    for index in min..=max {
+        let stroke = if index % subdivisions == 0 {
+            style.grid_polar_ring_stroke()
+        } else {
+            style.grid_line_secondary_stroke()
+        };
+        vec.push(Shape::circle_stroke(
+            ui_center,
+            index as f32 * spacing * scale,
+            stroke,
+        ));
+    }
+    for spoke in 0..spokes {
+        let direction = Vec2::angled(TAU * spoke as f32 / spokes as f32);
+        vec.push(Shape::line_segment(
+            [ui_center, ui_center + direction * max_radius * scale],
+            style.grid_polar_spoke_stroke(),
+        ));
+    }
+    ctx.painter.add(Shape::Vec(vec));
+}
+
+pub(crate) fn is_valid_step(step: f32) -> bool {
+    step.is_finite() && step > 0.0
+}
+
+pub(crate) fn lattice_point(mode: GridMode, origin: Pos2, spacing: f32, pos: Pos2) -> Option<Pos2> {
+    let local = pos - origin;
+    match mode {
+        GridMode::Rectangular => None,
+        GridMode::Isometric => {
+            let column_width = spacing * FRAC_PI_6.cos();
+            let column = local.x / column_width;
+            let row = (local.y - column * spacing / 2.0) / spacing;
+            [column.floor(), column.ceil()]
+                .into_iter()
+                .flat_map(|column| {
+                    [row.floor(), row.ceil()].map(|row| {
+                        origin
+                            + Vec2::new(
+                                column * column_width,
+                                row * spacing + column * spacing / 2.0,
+                            )
+                    })
+                })
+                .min_by(|a, b| a.distance(pos).total_cmp(&b.distance(pos)))
+        }
+        GridMode::Polar { spokes } => {
+            let radius = (local.length() / spacing).round() * spacing;
+            if radius == 0.0 || spokes == 0 {
+                return Some(origin);
+            }
+            let angle_step = TAU / spokes as f32;
+            let angle = (local.angle() / angle_step).round() * angle_step;
+            Some(origin + Vec2::angled(angle) * radius)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::units::Unit;
+
+    fn step(progression: GridStepProgression, spacing: f32, scale: f32) -> Vec2 {
+        GridOptions {
+            spacing: Vec2::new(spacing, spacing * 2.0),
+            step_progression: progression,
+            ..Default::default()
+        }
+        .step(Vec2::splat(scale), &UnitOptions::default())
+    }
+
+    #[test]
+    fn step_follows_progression_as_zoom_changes() {
+        assert_eq!(
+            step(GridStepProgression::PowersOf5, 50.0, 1.0),
+            Vec2::new(50.0, 100.0)
+        );
+        assert_eq!(
+            step(GridStepProgression::PowersOf5, 50.0, 5.0),
+            Vec2::new(10.0, 20.0)
+        );
+        assert_eq!(
+            step(GridStepProgression::PowersOf2, 50.0, 0.5),
+            Vec2::new(100.0, 200.0)
+        );
+        assert_eq!(
+            step(GridStepProgression::PowersOf10, 50.0, 10.0),
+            Vec2::new(5.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn one_two_five_steps_are_nice_numbers() {
+        assert_eq!(step(GridStepProgression::OneTwoFive, 50.0, 1.0).x, 50.0);
+        assert_eq!(step(GridStepProgression::OneTwoFive, 50.0, 3.0).x, 20.0);
+        assert_eq!(step(GridStepProgression::OneTwoFive, 50.0, 0.3).x, 200.0);
+        assert_eq!(step(GridStepProgression::OneTwoFive, 70.0, 1.0).x, 50.0);
+    }
+
+    #[test]
+    fn one_two_five_steps_are_nice_in_units() {
+        let units = UnitOptions {
+            unit: Unit::Mm,
+            ..Default::default()
+        };
+        let step = GridOptions {
+            step_progression: GridStepProgression::OneTwoFive,
+            ..Default::default()
+        }
+        .step(Vec2::splat(1.0), &units);
+        assert!((units.to_unit(step.x) - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn spacing_is_clamped_to_minimum() {
+        assert_eq!(
+            step(GridStepProgression::PowersOf10, 0.0, 1.0),
+            Vec2::splat(MIN_SPACING)
+        );
+        assert_eq!(
+            step(GridStepProgression::PowersOf10, -5.0, 1.0),
+            Vec2::splat(MIN_SPACING)
+        );
+    }
+
+    fn assert_near(actual: Option<Pos2>, expected: Pos2) {
+        let actual = actual.expect("expected a lattice point");
+        assert!(
+            actual.distance(expected) < 1e-3,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn rectangular_grid_has_no_lattice_points() {
+        assert_eq!(
+            lattice_point(GridMode::Rectangular, Pos2::ZERO, 10.0, Pos2::new(1.0, 1.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn isometric_lattice_points_follow_staggered_columns() {
+        let column_width = 10.0 * FRAC_PI_6.cos();
+        let origin = Pos2::new(100.0, 50.0);
+        let lattice =
+            |x, y| lattice_point(GridMode::Isometric, origin, 10.0, origin + Vec2::new(x, y));
+        assert_near(lattice(1.0, -1.0), origin);
+        assert_near(lattice(8.0, 5.5), origin + Vec2::new(column_width, 5.0));
+        assert_near(lattice(8.0, -4.0), origin + Vec2::new(column_width, -5.0));
+        assert_near(
+            lattice(-17.0, 1.0),
+            origin + Vec2::new(-2.0 * column_width, 0.0),
+        );
+    }
+
+    #[test]
+    fn polar_lattice_points_snap_radius_and_spoke() {
+        let origin = Pos2::new(10.0, 10.0);
+        let lattice = |x, y| {
+            lattice_point(
+                GridMode::Polar { spokes: 4 },
+                origin,
+                10.0,
+                origin + Vec2::new(x, y),
+            )
+        };
+        assert_near(lattice(1.0, 19.0), origin + Vec2::new(0.0, 20.0));
+        assert_near(lattice(-9.0, -2.0), origin + Vec2::new(-10.0, 0.0));
+        assert_near(lattice(2.0, 2.0), origin);
+    }
+}
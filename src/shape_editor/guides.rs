@@ -0,0 +1,144 @@
+use crate::shape_editor::canvas::{CanvasContext, CanvasTransform};
+use crate::shape_editor::style;
+use egui::{Pos2, Shape};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GuideOrientation {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guide {
+    pub orientation: GuideOrientation,
+    pub position: f32,
+    pub locked: bool,
+}
+
+impl Guide {
+    pub fn horizontal(y: f32) -> Self {
+        Self {
+            orientation: GuideOrientation::Horizontal,
+            position: y,
+            locked: false,
+        }
+    }
+
+    pub fn vertical(x: f32) -> Self {
+        Self {
+            orientation: GuideOrientation::Vertical,
+            position: x,
+            locked: false,
+        }
+    }
+
+    pub fn locked(mut self) -> Self {
+        self.locked = true;
+        self
+    }
+
+    pub(crate) fn position_at(&self, pos: Pos2) -> f32 {
+        match self.orientation {
+            GuideOrientation::Horizontal => pos.y,
+            GuideOrientation::Vertical => pos.x,
+        }
+    }
+
+    fn ui_points(&self, transform: &CanvasTransform) -> [Pos2; 2] {
+        let canvas_rect = transform.ui_canvas_rect();
+        match self.orientation {
+            GuideOrientation::Horizontal => {
+                let y = transform.canvas_content_to_ui.transform_y(self.position);
+                [
+                    Pos2::new(canvas_rect.left(), y),
+                    Pos2::new(canvas_rect.right(), y),
+                ]
+            }
+            GuideOrientation::Vertical => {
+                let x = transform.canvas_content_to_ui.transform_x(self.position);
+                [
+                    Pos2::new(x, canvas_rect.top()),
+                    Pos2::new(x, canvas_rect.bottom()),
+                ]
+            }
+        }
+    }
+}
+
+pub(crate) fn hovered_guide(
+    mouse_hover_pos: Option<Pos2>,
+    guides: &[Guide],
+    transform: &CanvasTransform,
+    hover_distance: f32,
+) -> Option<usize> {
+    let mouse_hover_pos = mouse_hover_pos?;
+    guides
+        .iter()
+        .enumerate()
+        .filter_map(|(index, guide)| {
+            let [start, _] = guide.ui_points(transform);
+            let distance = (guide.position_at(start) - guide.position_at(mouse_hover_pos)).abs();
+            (distance <= hover_distance).then_some((index, distance))
+        })
+        .min_by(|(_, distance1), (_, distance2)| distance1.total_cmp(distance2))
+        .map(|(index, _)| index)
+}
+
+pub(crate) fn paint_guides(
+    ctx: &CanvasContext,
+    guides: &[Guide],
+    hovered_guide: Option<usize>,
+    style: &dyn style::Style,
+) {
+    puffin_egui::puffin::profile_function!();
+    ctx.painter.add(Shape::Vec(
+        guides
+            .iter()
+            .enumerate()
+            .map(|(index, guide)| guide_shape(ctx, guide, hovered_guide == Some(index), style))
+            .collect(),
+    ));
+}
+
+pub(crate) fn guide_shape(
+    ctx: &CanvasContext,
+    guide: &Guide,
+    hovered: bool,
+    style: &dyn style::Style,
+) -> Shape {
+    style.guide_shape(guide.ui_points(&ctx.transform), hovered, guide.locked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::transform::Transform;
+    use egui::{Rect, Vec2};
+
+    fn transform() -> CanvasTransform {
+        CanvasTransform::new(
+            Rect::from_min_size(Pos2::new(100.0, 50.0), Vec2::splat(400.0)),
+            &Transform::from_scale(Vec2::splat(2.0)),
+        )
+    }
+
+    #[test]
+    fn hovered_guide_is_measured_in_ui_space() {
+        let guides = [Guide::vertical(10.0), Guide::horizontal(10.0)];
+        let transform = transform();
+        let hovered = |x, y| hovered_guide(Some(Pos2::new(x, y)), &guides, &transform, 3.0);
+        assert_eq!(hovered(122.0, 300.0), Some(0));
+        assert_eq!(hovered(300.0, 68.0), Some(1));
+        assert_eq!(hovered(125.0, 300.0), None);
+        assert_eq!(hovered(121.5, 70.5), Some(1));
+        assert_eq!(hovered_guide(None, &guides, &transform, 3.0), None);
+    }
+
+    #[test]
+    fn guide_position_follows_orientation() {
+        let pos = Pos2::new(3.0, 7.0);
+        assert_eq!(Guide::vertical(0.0).position_at(pos), 3.0);
+        assert_eq!(Guide::horizontal(0.0).position_at(pos), 7.0);
+        assert!(Guide::vertical(0.0).locked().locked);
+    }
+}
@@ -1,8 +1,10 @@
 use crate::shape_editor::canvas::CanvasTransform;
+use crate::shape_editor::grid;
+use crate::shape_editor::grid::{GridMode, GridOptions};
 use crate::shape_editor::shape_visitor::ShapePointIndex;
-use crate::shape_editor::utils;
+use crate::shape_editor::units::UnitOptions;
 use egui::ahash::HashMap;
-use egui::{Pos2, Rect};
+use egui::{Pos2, Rangef, Rect, Vec2};
 use num_traits::{Bounded, Zero};
 use ordered_float::{FloatCore, NotNan};
 use std::collections::BTreeMap;
@@ -19,7 +21,7 @@ impl<K: FloatCore, V: Eq + Hash + Copy + Ord> FloatIndex<K, V> {
     pub fn find_in_range<R: RangeBounds<NotNan<K>>>(
         &self,
         range: R,
-    ) -> btree_map::Range<NotNan<K>, BTreeSet<V>> {
+    ) -> btree_map::Range<'_, NotNan<K>, BTreeSet<V>> {
         self.0.range(range)
     }
 
@@ -27,7 +29,7 @@ impl<K: FloatCore, V: Eq + Hash + Copy + Ord> FloatIndex<K, V> {
         &self,
         key: NotNan<K>,
         max_distance: NotNan<K>,
-    ) -> btree_map::Range<NotNan<K>, BTreeSet<V>> {
+    ) -> btree_map::Range<'_, NotNan<K>, BTreeSet<V>> {
         let d = max_distance.abs();
         self.find_in_range(key - d..=key + d)
     }
@@ -51,11 +53,10 @@ impl<K: FloatCore, V: Eq + Hash + Copy + Ord> FloatIndex<K, V> {
                     .map(|(key, value)| (*key, value.sub(ignore_values))),
                 self.0
                     .range(key..=key + d)
-                    .filter(|(_, value)| {
+                    .find(|(_, value)| {
                         ignore_values.is_empty()
                             || value.iter().any(|value| !ignore_values.contains(value))
                     })
-                    .next()
                     .map(|(key, value)| (*key, value.sub(ignore_values))),
             ]
             .into_iter()
@@ -142,15 +143,13 @@ impl ShapeControlPointsIndex {
         max_distance: f32,
         ignore: &BTreeSet<ShapePointIndex>,
     ) -> Option<SnapComponent> {
-        let x = self
-            .x_index
+        self.x_index
             .find_closest_in_distance_and_ignore(
                 not_nan_f32(pos.x),
                 not_nan_f32(max_distance),
                 ignore,
             )
-            .map(|(x, index)| (x.into_inner(), index));
-        x
+            .map(|(x, index)| (x.into_inner(), index))
     }
 
     pub fn snap_y(
@@ -159,15 +158,13 @@ impl ShapeControlPointsIndex {
         max_distance: f32,
         ignore: &BTreeSet<ShapePointIndex>,
     ) -> Option<SnapComponent> {
-        let y = self
-            .y_index
+        self.y_index
             .find_closest_in_distance_and_ignore(
                 not_nan_f32(pos.y),
                 not_nan_f32(max_distance),
                 ignore,
             )
-            .map(|(y, index)| (y.into_inner(), index));
-        y
+            .map(|(y, index)| (y.into_inner(), index))
     }
 }
 
@@ -184,46 +181,37 @@ pub enum GridLineType {
 pub(crate) struct GridIndex {
     pub horizontal: FloatIndex<f32, GridLineType>,
     pub vertical: FloatIndex<f32, GridLineType>,
+    pub origin: Pos2,
+    pub mode: GridMode,
+    pub step: Vec2,
+    pub secondary_subdivisions: u32,
 }
 
 impl GridIndex {
-    pub fn from_transform(transform: &CanvasTransform) -> Self {
+    pub fn from_transform(
+        transform: &CanvasTransform,
+        options: &GridOptions,
+        units: &UnitOptions,
+    ) -> Self {
         puffin_egui::puffin::profile_function!();
-        let mut slf = Self::default();
+        let step = options.step(transform.canvas_content_to_ui.scale(), units);
+        let mut slf = Self {
+            origin: options.origin,
+            mode: options.mode,
+            step,
+            secondary_subdivisions: options.secondary_subdivisions.max(1),
+            ..Default::default()
+        };
         let canvas_viewport = transform.canvas_content_viewport();
-        let x_range = canvas_viewport.x_range();
-        let y_range = canvas_viewport.y_range();
-        let scale = transform.canvas_content_to_ui.scale().x;
-        let step = utils::grid_step(scale);
-        let half_step = step / 2.0;
-        let sub_step = half_step / 3.0;
-        for x in utils::step_by(x_range, step) {
-            if x == 0.0 {
-                slf.add_horizontal(x, GridLineType::Zero);
-            } else {
-                slf.add_horizontal(x, GridLineType::Primary);
-            }
-            slf.add_horizontal(x + half_step, GridLineType::Secondary);
-            slf.add_horizontal(x + sub_step, GridLineType::Sub);
-            slf.add_horizontal(x + sub_step * 2.0, GridLineType::Sub);
-            slf.add_horizontal(x + half_step + sub_step, GridLineType::Sub);
-            slf.add_horizontal(x + half_step + sub_step * 2.0, GridLineType::Sub);
+        for (x, line_type) in
+            grid_lines(canvas_viewport.x_range(), options.origin.x, step.x, options)
+        {
+            slf.add_horizontal(x, line_type);
         }
-        let scale = transform.canvas_content_to_ui.scale().y;
-        let step = utils::grid_step(scale);
-        let half_step = step / 2.0;
-        let sub_step = half_step / 3.0;
-        for y in utils::step_by(y_range, step) {
-            if y == 0.0 {
-                slf.add_vertical(y, GridLineType::Zero);
-            } else {
-                slf.add_vertical(y, GridLineType::Primary);
-            }
-            slf.add_vertical(y + half_step, GridLineType::Secondary);
-            slf.add_vertical(y + sub_step, GridLineType::Sub);
-            slf.add_vertical(y + sub_step * 2.0, GridLineType::Sub);
-            slf.add_vertical(y + half_step + sub_step, GridLineType::Sub);
-            slf.add_vertical(y + half_step + sub_step * 2.0, GridLineType::Sub);
+        for (y, line_type) in
+            grid_lines(canvas_viewport.y_range(), options.origin.y, step.y, options)
+        {
+            slf.add_vertical(y, line_type);
         }
         slf
     }
@@ -234,15 +222,13 @@ impl GridIndex {
         max_distance: f32,
         ignore: &BTreeSet<GridLineType>,
     ) -> Option<f32> {
-        let x = self
-            .horizontal
+        self.horizontal
             .find_closest_in_distance_and_ignore(
                 not_nan_f32(pos.x),
                 not_nan_f32(max_distance),
                 ignore,
             )
-            .map(|(x, _)| x.into_inner());
-        x
+            .map(|(x, _)| x.into_inner())
     }
 
     pub fn snap_y(
@@ -251,15 +237,31 @@ impl GridIndex {
         max_distance: f32,
         ignore: &BTreeSet<GridLineType>,
     ) -> Option<f32> {
-        let y = self
-            .vertical
+        self.vertical
             .find_closest_in_distance_and_ignore(
                 not_nan_f32(pos.y),
                 not_nan_f32(max_distance),
                 ignore,
             )
-            .map(|(y, _)| y.into_inner());
-        y
+            .map(|(y, _)| y.into_inner())
+    }
+
+    pub fn snap_point(&self, pos: Pos2, max_distance: f32, subdivided: bool) -> Option<Pos2> {
+        let subdivisions = if subdivided {
+            self.secondary_subdivisions
+        } else {
+            1
+        };
+        if !grid::is_valid_step(self.step.x) {
+            return None;
+        }
+        grid::lattice_point(
+            self.mode,
+            self.origin,
+            self.step.x / subdivisions as f32,
+            pos,
+        )
+        .filter(|point| point.distance(pos) <= max_distance)
     }
 
     pub fn add_horizontal(&mut self, x: f32, line_type: GridLineType) {
@@ -271,6 +273,40 @@ impl GridIndex {
     }
 }
 
+fn grid_lines(
+    range: Rangef,
+    origin: f32,
+    step: f32,
+    options: &GridOptions,
+) -> impl Iterator<Item = (f32, GridLineType)> {
+    let secondary_subdivisions = options.secondary_subdivisions.max(1);
+    let sub_subdivisions = options.sub_subdivisions.max(1);
+    let subdivisions = secondary_subdivisions * sub_subdivisions;
+    let (min, max) = if grid::is_valid_step(step) {
+        (
+            ((range.min - origin) / step).floor() as i32,
+            ((range.max - origin) / step).ceil() as i32,
+        )
+    } else {
+        (0, 0)
+    };
+    (min..max).flat_map(move |i| {
+        (0..subdivisions).map(move |j| {
+            let line_type = if j == 0 && i == 0 {
+                GridLineType::Zero
+            } else if j == 0 {
+                GridLineType::Primary
+            } else if j % sub_subdivisions == 0 {
+                GridLineType::Secondary
+            } else {
+                GridLineType::Sub
+            };
+            let position = origin + (i as f32 + j as f32 / subdivisions as f32) * step;
+            (position, line_type)
+        })
+    })
+}
+
 fn not_nan_pos2(pos: Pos2) -> (NotNan<f32>, NotNan<f32>) {
     (not_nan_f32(pos.x), not_nan_f32(pos.y))
 }
@@ -278,3 +314,94 @@ fn not_nan_pos2(pos: Pos2) -> (NotNan<f32>, NotNan<f32>) {
 pub(crate) fn not_nan_f32(v: f32) -> NotNan<f32> {
     NotNan::new(v).unwrap_or(NotNan::max_value())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(secondary_subdivisions: u32, sub_subdivisions: u32) -> GridOptions {
+        GridOptions {
+            secondary_subdivisions,
+            sub_subdivisions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn grid_lines_cover_range_from_origin_with_subdivisions() {
+        let lines: Vec<(f32, GridLineType)> =
+            grid_lines(Rangef::new(-20.0, 60.0), 10.0, 40.0, &options(2, 2)).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (-30.0, GridLineType::Primary),
+                (-20.0, GridLineType::Sub),
+                (-10.0, GridLineType::Secondary),
+                (0.0, GridLineType::Sub),
+                (10.0, GridLineType::Zero),
+                (20.0, GridLineType::Sub),
+                (30.0, GridLineType::Secondary),
+                (40.0, GridLineType::Sub),
+                (50.0, GridLineType::Primary),
+                (60.0, GridLineType::Sub),
+                (70.0, GridLineType::Secondary),
+                (80.0, GridLineType::Sub),
+            ]
+        );
+    }
+
+    #[test]
+    fn zero_subdivisions_yield_primary_lines_only() {
+        let lines: Vec<(f32, GridLineType)> =
+            grid_lines(Rangef::new(0.0, 100.0), 0.0, 50.0, &options(0, 0)).collect();
+        assert_eq!(
+            lines,
+            vec![(0.0, GridLineType::Zero), (50.0, GridLineType::Primary)]
+        );
+    }
+
+    #[test]
+    fn invalid_step_yields_no_lines() {
+        for step in [0.0, -10.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                grid_lines(Rangef::new(0.0, 100.0), 0.0, step, &options(2, 3)).count(),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn snap_skips_ignored_line_types() {
+        let mut grid_index = GridIndex::default();
+        grid_index.add_horizontal(10.0, GridLineType::Primary);
+        grid_index.add_horizontal(12.0, GridLineType::Sub);
+        let pos = Pos2::new(12.5, 0.0);
+        assert_eq!(grid_index.snap_x(pos, 5.0, &BTreeSet::new()), Some(12.0));
+        assert_eq!(
+            grid_index.snap_x(pos, 5.0, &BTreeSet::from([GridLineType::Sub])),
+            Some(10.0)
+        );
+        assert_eq!(grid_index.snap_y(pos, 5.0, &BTreeSet::new()), None);
+    }
+
+    #[test]
+    fn snap_point_uses_secondary_subdivisions_and_distance() {
+        let grid_index = GridIndex {
+            mode: GridMode::Polar { spokes: 4 },
+            step: Vec2::splat(10.0),
+            secondary_subdivisions: 2,
+            ..Default::default()
+        };
+        let pos = Pos2::new(6.0, 0.5);
+        assert_eq!(
+            grid_index.snap_point(pos, 5.0, false),
+            Some(Pos2::new(10.0, 0.0))
+        );
+        assert_eq!(
+            grid_index.snap_point(pos, 5.0, true),
+            Some(Pos2::new(5.0, 0.0))
+        );
+        assert_eq!(grid_index.snap_point(pos, 1.0, false), None);
+        assert_eq!(GridIndex::default().snap_point(pos, 5.0, false), None);
+    }
+}
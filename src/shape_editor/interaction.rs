@@ -1,27 +1,46 @@
-use crate::shape_editor::canvas::{CanvasContext, KeyboardAction};
+use crate::shape_editor::canvas::{ActionModifier, CanvasContext, KeyboardAction};
 use crate::shape_editor::constraints::Constraint;
 use crate::shape_editor::control_point::ShapeControlPoint;
+use crate::shape_editor::guides::{Guide, GuideOrientation};
 use crate::shape_editor::memory::ShapeEditorMemory;
 use crate::shape_editor::shape_action::add_shape_points::AddShapePoints;
+use crate::shape_editor::shape_action::cut_path::CutPath;
+use crate::shape_editor::shape_action::edit_constraints::RemoveConstraint;
 use crate::shape_editor::shape_action::insert_shape::InsertShape;
 use crate::shape_editor::shape_action::remove_shape_points::RemoveShapePoints;
-use crate::shape_editor::shape_action::{move_shape_points, ShapeAction, ShapePoint};
+use crate::shape_editor::shape_action::{move_shape_points, Combined, ShapeAction, ShapePoint};
+use crate::shape_editor::shape_visitor::closest_path_segment::ClosestPathSegment;
+use crate::shape_editor::shape_visitor::count_shapes::CountShapes;
 use crate::shape_editor::shape_visitor::last_shape_point_index::LastShapePointIndex;
 use crate::shape_editor::shape_visitor::ShapeType;
 use crate::shape_editor::style::Style;
-use crate::shape_editor::{utils, ShapeEditorOptions};
+use crate::shape_editor::{guides, utils, ShapeEditorOptions};
 use dyn_clone::DynClone;
 use egui::epaint::{CubicBezierShape, PathShape, QuadraticBezierShape, Vertex};
 use egui::{Color32, Mesh, Pos2, Rect, Shape, Vec2};
 use std::fmt::Debug;
-use std::mem;
 use std::ops::Mul;
 
 impl ShapeEditorMemory {
     pub(crate) fn next_frame_interactions(&mut self, ctx: &CanvasContext) {
         puffin_egui::puffin::profile_function!();
         let mouse_pos = ctx.input.mouse_pos;
-        if ctx.input.primary_drag_started() && !ctx.input.action_modifier.add_point_on_click() {
+        if ctx.input.primary_drag_started()
+            && !ctx.input.action_modifier.add_point_on_click()
+            && self.interaction().is_empty()
+        {
+            if let Some((index, guide)) = ctx
+                .hovered_guide
+                .filter(|_| ctx.hovered_ui_shape_points.is_empty())
+                .and_then(|index| Some((index, *self.guides.get(index)?)))
+                .filter(|(_, guide)| !guide.locked)
+            {
+                self.begin_interaction(MoveGuide {
+                    index: Some(index),
+                    guide,
+                });
+                return;
+            }
             if !ctx.input.action_modifier.do_not_deselect_selected_points() {
                 if let Some(closest_selected_control_point) =
                     ctx.closest_selected_control_point(self.selection())
@@ -29,14 +48,15 @@ impl ShapeEditorMemory {
                     self.begin_interaction(MoveShapeControlPoints {
                         start_pos: closest_selected_control_point.position(),
                         end_pos: closest_selected_control_point.position(),
+                        undo: Default::default(),
                     });
                     return;
                 }
             }
             self.begin_interaction(Selection {
-                rect: Rect::from_min_max(mouse_pos, mouse_pos),
+                start_pos: ctx.input.canvas_content_mouse_pos,
             });
-        } else if ctx.input.secondary_drag_started() {
+        } else if ctx.input.pan_drag_started() {
             self.begin_interaction(Pan {
                 start_pos: mouse_pos,
             });
@@ -50,6 +70,9 @@ impl ShapeEditorMemory {
                 KeyboardAction::AddPoint => self.begin_interaction(AddPoint),
                 KeyboardAction::DeletePoint => self.begin_interaction(DeletePoints),
                 KeyboardAction::Undo => self.begin_interaction(Undo),
+                KeyboardAction::ZoomToFit => self.begin_interaction(ZoomToFit),
+                KeyboardAction::ZoomToActualSize => self.begin_interaction(ZoomToActualSize),
+                KeyboardAction::ZoomToSelection => self.begin_interaction(ZoomToSelection),
             }
         } else if ctx.input.mouse_primary_clicked {
             if ctx.input.action_modifier.add_point_on_click() {
@@ -61,11 +84,14 @@ impl ShapeEditorMemory {
             self.begin_interaction(ChangeSelectionOnPrimary)
         }
 
-        if ctx.input.mouse_zoom_delta != 1.0 {
+        if ctx.input.mouse_zoom_delta != 1.0 || ctx.input.touch_zoom_delta != 1.0 {
             self.begin_interaction(Zoom);
         } else if ctx.input.mouse_scroll_delta != Vec2::ZERO {
             self.begin_interaction(Scroll);
         }
+        if ctx.input.touch_pan_delta != Vec2::ZERO {
+            self.begin_interaction(TouchPan);
+        }
     }
 
     pub(crate) fn update_interaction(
@@ -75,10 +101,7 @@ impl ShapeEditorMemory {
         options: &ShapeEditorOptions,
         ctx: &CanvasContext,
     ) {
-        let interactions = mem::replace(self.interaction_mut(), Vec::new());
-        if !interactions.is_empty() {
-            println!("Interactions for current frame: {:?}", interactions);
-        }
+        let interactions = std::mem::take(self.interaction_mut());
         for interaction in interactions {
             if let Some(result) = interaction.update(self, shape, style, options, ctx) {
                 self.interaction_mut().push(result)
@@ -100,6 +123,18 @@ pub(crate) trait Interaction: DynClone + Send + Sync + Debug {
         options: &ShapeEditorOptions,
         ctx: &CanvasContext,
     ) -> Option<Box<dyn Interaction>>;
+
+    fn snap_anchor(&self) -> Option<Pos2> {
+        None
+    }
+
+    fn moved_point(&self) -> Option<Pos2> {
+        None
+    }
+
+    fn is_drag_creating(&self) -> bool {
+        false
+    }
 }
 dyn_clone::clone_trait_object!(Interaction);
 
@@ -107,11 +142,23 @@ dyn_clone::clone_trait_object!(Interaction);
 pub(crate) struct MoveShapeControlPoints {
     pub start_pos: Pos2,
     pub end_pos: Pos2,
+    pub undo: move_shape_points::TranslateShapePoints,
 }
 
 #[derive(Clone, Debug)]
 struct Selection {
-    rect: Rect,
+    start_pos: Pos2,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MoveGuide {
+    index: Option<usize>,
+    guide: Guide,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MoveOrigin {
+    previous_origin: Option<Pos2>,
 }
 
 #[derive(Clone, Debug)]
@@ -126,12 +173,33 @@ pub(crate) struct AddPointsThanShape {
     shape_fn: fn(&Vec<Pos2>, &ShapeEditorOptions) -> Option<Shape>,
 }
 
+#[derive(Clone, Debug)]
+pub(crate) struct DragShape {
+    shape_type: ShapeType,
+    start_pos: Option<Pos2>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Scissors;
+
 #[derive(Clone, Debug)]
 struct Scroll;
 
 #[derive(Clone, Debug)]
 struct Zoom;
 
+#[derive(Clone, Debug)]
+struct TouchPan;
+
+#[derive(Clone, Debug)]
+struct ZoomToFit;
+
+#[derive(Clone, Debug)]
+struct ZoomToActualSize;
+
+#[derive(Clone, Debug)]
+struct ZoomToSelection;
+
 #[derive(Clone, Debug)]
 struct AddPoint;
 
@@ -150,18 +218,17 @@ impl Interaction for MoveShapeControlPoints {
         memory: &mut ShapeEditorMemory,
         shape: &mut Shape,
         _style: &dyn Style,
-        _options: &ShapeEditorOptions,
+        options: &ShapeEditorOptions,
         ctx: &CanvasContext,
     ) -> Option<Box<dyn Interaction>> {
         puffin_egui::puffin::profile_function!();
-        if ctx.input.drag_stopped || ctx.input.mouse_primary_pressed {
+        if ctx.input.drag_stopped
+            || ctx.input.mouse_primary_pressed
+            || ctx.input.primary_interrupted()
+        {
             if self.end_pos != self.start_pos && memory.selection().has_control_points() {
-                let move_action = move_shape_points::MoveShapePoints::from_index_and_translation(
-                    memory.selection().control_points(),
-                    &(self.end_pos - self.start_pos),
-                );
-                memory
-                    .push_action_history(Box::new(move_action.invert()), move_action.short_name());
+                let short_name = self.undo.short_name();
+                memory.push_action_history(Box::new(self.undo), short_name);
             }
             None
         } else {
@@ -173,57 +240,177 @@ impl Interaction for MoveShapeControlPoints {
                     .snap()
                     .snap_point
                     .unwrap_or(ctx.input.canvas_content_mouse_pos);
-                Box::new(
-                    move_shape_points::MoveShapePoints::from_index_and_translation(
-                        memory.selection().control_points(),
-                        &(snap_point - self.end_pos),
-                    ),
+                let undo = move_shape_points::MoveShapePoints::from_index_and_translation(
+                    memory.selection().control_points(),
+                    &(snap_point - self.end_pos),
                 )
-                .apply(shape, &mut memory.constraints);
+                .apply_and_invert(shape, &memory.constraints);
+                self.undo.merge(undo);
                 self.end_pos = snap_point;
             }
+            auto_scroll(memory, options, ctx);
             Some(self)
         }
     }
+
+    fn snap_anchor(&self) -> Option<Pos2> {
+        Some(self.start_pos)
+    }
+
+    fn moved_point(&self) -> Option<Pos2> {
+        Some(self.end_pos)
+    }
 }
 
 impl Interaction for Selection {
     fn update(
-        mut self: Box<Self>,
+        self: Box<Self>,
         memory: &mut ShapeEditorMemory,
         _shape: &mut Shape,
         style: &dyn Style,
-        _options: &ShapeEditorOptions,
+        options: &ShapeEditorOptions,
         ctx: &CanvasContext,
     ) -> Option<Box<dyn Interaction>> {
         puffin_egui::puffin::profile_function!();
-        if ctx.input.drag_stopped || ctx.input.mouse_primary_pressed {
+        if ctx.input.drag_stopped
+            || ctx.input.mouse_primary_pressed
+            || ctx.input.primary_interrupted()
+        {
             None
         } else {
-            self.rect.max = ctx.input.mouse_pos;
-
-            if self.rect.max != self.rect.min {
+            let end_pos = ctx.input.canvas_content_mouse_pos;
+            if end_pos != self.start_pos {
                 if !ctx.input.action_modifier.do_not_deselect_selected_points() {
                     memory.selection_mut().clear_selected_control_points();
                 }
                 ctx.shape_control_points
-                    .find_points_in_rect(
-                        &ctx.transform
-                            .ui_to_canvas_content
-                            .transform_rect(&utils::normalize_rect(&self.rect)),
-                    )
+                    .find_points_in_rect(&Rect::from_two_pos(self.start_pos, end_pos))
                     .iter()
                     .for_each(|(_, index)| {
                         memory.selection_mut().select_control_point(*index);
                     });
-                let selection_shape = style.selection_shape(self.rect.min, self.rect.max);
+                let selection_shape = style.selection_shape(
+                    ctx.transform
+                        .canvas_content_to_ui
+                        .transform_pos(self.start_pos),
+                    ctx.input.mouse_pos,
+                );
                 ctx.painter.add(selection_shape);
             }
+            auto_scroll(memory, options, ctx);
+            Some(self)
+        }
+    }
+}
+
+impl Interaction for MoveGuide {
+    fn update(
+        mut self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        _shape: &mut Shape,
+        style: &dyn Style,
+        _options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        puffin_egui::puffin::profile_function!();
+        let over_canvas = ctx
+            .input
+            .pointer_pos
+            .is_some_and(|pos| ctx.transform.ui_canvas_rect().contains(pos));
+        if let Some(pointer_pos) = ctx.input.pointer_pos {
+            self.guide.position = self.guide.position_at(
+                ctx.transform
+                    .ui_to_canvas_content
+                    .transform_pos(pointer_pos),
+            );
+        }
+        if ctx.input.mouse_primary_down {
+            match self.index.and_then(|index| memory.guides.get_mut(index)) {
+                Some(guide) => *guide = self.guide,
+                None if over_canvas => {
+                    ctx.painter
+                        .add(guides::guide_shape(ctx, &self.guide, true, style));
+                }
+                None => {}
+            }
+            Some(self)
+        } else {
+            match (self.index, over_canvas) {
+                (Some(index), true) => {
+                    if let Some(guide) = memory.guides.get_mut(index) {
+                        *guide = self.guide;
+                    }
+                }
+                (Some(index), false) => {
+                    if index < memory.guides.len() {
+                        memory.guides.remove(index);
+                    }
+                }
+                (None, true) => memory.guides.push(self.guide),
+                (None, false) => {}
+            }
+            None
+        }
+    }
+}
+
+impl Interaction for MoveOrigin {
+    fn update(
+        self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        _shape: &mut Shape,
+        style: &dyn Style,
+        _options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        puffin_egui::puffin::profile_function!();
+        let pointer_pos = ctx
+            .input
+            .pointer_pos
+            .filter(|pos| ctx.transform.ui_canvas_rect().contains(*pos));
+        let origin = pointer_pos.map(|pointer_pos| {
+            memory.snap().snap_point.unwrap_or(
+                ctx.transform
+                    .ui_to_canvas_content
+                    .transform_pos(pointer_pos),
+            )
+        });
+        if ctx.input.mouse_primary_down {
+            if let Some(origin) = origin {
+                memory.grid_origin = Some(origin);
+                for guide in [Guide::horizontal(origin.y), Guide::vertical(origin.x)] {
+                    ctx.painter
+                        .add(guides::guide_shape(ctx, &guide, true, style));
+                }
+            } else {
+                memory.grid_origin = self.previous_origin;
+            }
             Some(self)
+        } else {
+            memory.grid_origin = origin.or(self.previous_origin);
+            None
         }
     }
 }
 
+fn auto_scroll(memory: &mut ShapeEditorMemory, options: &ShapeEditorOptions, ctx: &CanvasContext) {
+    let canvas_rect = ctx.transform.ui_canvas_rect();
+    let margin = options
+        .auto_scroll_margin
+        .min(canvas_rect.size().min_elem() / 2.0);
+    let scroll_rect = canvas_rect.shrink(margin);
+    let mouse_pos = ctx.input.mouse_pos;
+    let overshoot = mouse_pos - mouse_pos.clamp(scroll_rect.min, scroll_rect.max);
+    if overshoot != Vec2::ZERO {
+        memory.set_transform(
+            memory
+                .transform()
+                .translate(-overshoot * options.auto_scroll_speed * ctx.input.dt),
+        );
+        ctx.painter.ctx().request_repaint();
+    }
+}
+
 impl Interaction for Pan {
     fn update(
         mut self: Box<Self>,
@@ -267,23 +454,11 @@ impl Interaction for AddPointsThanShape {
         if self.points.len() < self.points_count {
             let mut preview_points = self.points.clone();
             preview_points.push(mouse_pos);
-            let mut preview_vec_shape: Vec<Shape> = preview_points
-                .iter()
-                .map(|p| {
-                    Shape::circle_stroke(
-                        *p,
-                        ctx.transform.ui_to_canvas_content.scale().x * style.control_point_radius(),
-                        style.preview_point_stroke(),
-                    )
-                })
-                .collect();
-            if let Some(shape_preview) = (self.shape_fn)(&preview_points, options) {
-                preview_vec_shape.insert(0, shape_preview);
-            }
-            ctx.painter.add(
-                ctx.transform
-                    .canvas_content_to_ui
-                    .transform_shape(&Shape::Vec(preview_vec_shape)),
+            paint_shape_preview(
+                ctx,
+                style,
+                (self.shape_fn)(&preview_points, options),
+                &preview_points,
             );
             Some(self)
         } else {
@@ -294,6 +469,122 @@ impl Interaction for AddPointsThanShape {
             None
         }
     }
+
+    fn snap_anchor(&self) -> Option<Pos2> {
+        self.points.last().copied()
+    }
+}
+
+impl Interaction for DragShape {
+    fn update(
+        mut self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        shape: &mut Shape,
+        style: &dyn Style,
+        options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        let mouse_pos = memory
+            .snap()
+            .snap_point
+            .unwrap_or(ctx.input.canvas_content_mouse_pos);
+        if ctx.input.mouse_secondary_pressed {
+            return None;
+        }
+        let Some(start_pos) = self.start_pos else {
+            if ctx.input.mouse_primary_pressed {
+                self.start_pos = Some(mouse_pos);
+            }
+            paint_shape_preview(ctx, style, None, &[mouse_pos]);
+            return Some(self);
+        };
+        let (from, to) = self.drag_points(start_pos, mouse_pos, &ctx.input.action_modifier);
+        if ctx.input.mouse_primary_down {
+            paint_shape_preview(ctx, style, self.shape(from, to, options), &[from, to]);
+            Some(self)
+        } else {
+            if from != to {
+                if let Some(new_shape) = self.shape(from, to, options) {
+                    let action = InsertShape::from_shape(new_shape);
+                    memory.apply_boxed_action(Box::new(action), shape);
+                }
+            }
+            None
+        }
+    }
+
+    fn snap_anchor(&self) -> Option<Pos2> {
+        self.start_pos
+    }
+
+    fn is_drag_creating(&self) -> bool {
+        self.start_pos.is_some()
+    }
+}
+
+impl Interaction for Scissors {
+    fn update(
+        self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        shape: &mut Shape,
+        style: &dyn Style,
+        _options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        if ctx.input.mouse_secondary_pressed {
+            return None;
+        }
+        let cut_action = if let Some(&hovered_path_point) =
+            ctx.hovered_ui_shape_points.keys().find(|index| {
+                ctx.shape_control_points.shape_type_by_control_point(index) == Some(ShapeType::Path)
+            }) {
+            ctx.shape_control_points
+                .pos_by_index(&hovered_path_point)
+                .map(|pos| (CutPath::at_point(hovered_path_point), pos))
+        } else {
+            ClosestPathSegment::find(
+                shape,
+                ctx.input.canvas_content_mouse_pos,
+                ctx.transform.ui_to_canvas_content.scale().x * ctx.hit_radius,
+            )
+            .map(|(segment_start, pos)| (CutPath::at_segment(segment_start, pos), pos))
+        };
+        if let Some((action, pos)) = cut_action {
+            if ctx.input.mouse_primary_clicked {
+                memory.apply_boxed_action(Box::new(action), shape);
+                return None;
+            }
+            paint_shape_preview(ctx, style, None, &[pos]);
+        }
+        Some(self)
+    }
+}
+
+fn paint_shape_preview(
+    ctx: &CanvasContext,
+    style: &dyn Style,
+    shape_preview: Option<Shape>,
+    preview_points: &[Pos2],
+) {
+    let mut preview_vec_shape: Vec<Shape> = preview_points
+        .iter()
+        .map(|p| {
+            Shape::circle_stroke(
+                *p,
+                ctx.transform.ui_to_canvas_content.scale().x * style.control_point_radius(),
+                style.preview_point_stroke(),
+            )
+        })
+        .collect();
+    if let Some(shape_preview) = shape_preview {
+        preview_vec_shape.insert(0, shape_preview);
+    }
+    ctx.painter.add(
+        ctx.transform
+            .canvas_content_to_ui
+            .transform_shape(&Shape::Vec(preview_vec_shape))
+            .0,
+    );
 }
 
 impl Interaction for Scroll {
@@ -314,6 +605,20 @@ impl Interaction for Scroll {
     }
 }
 
+impl Interaction for TouchPan {
+    fn update(
+        self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        _shape: &mut Shape,
+        _style: &dyn Style,
+        _options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        memory.set_transform(memory.transform().translate(ctx.input.touch_pan_delta));
+        None
+    }
+}
+
 impl Interaction for Zoom {
     fn update(
         self: Box<Self>,
@@ -325,7 +630,7 @@ impl Interaction for Zoom {
     ) -> Option<Box<dyn Interaction>> {
         if let Some(canvas_hover_pos) = ctx.input.canvas_mouse_hover_pos {
             let new_transform = memory.transform().resize_at(
-                ctx.input.mouse_zoom_delta.powf(options.zoom_factor),
+                ctx.input.mouse_zoom_delta.powf(options.zoom_factor) * ctx.input.touch_zoom_delta,
                 canvas_hover_pos,
             );
             let new_transform_scale = new_transform.scale();
@@ -342,6 +647,59 @@ impl Interaction for Zoom {
     }
 }
 
+impl Interaction for ZoomToFit {
+    fn update(
+        self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        shape: &mut Shape,
+        _style: &dyn Style,
+        options: &ShapeEditorOptions,
+        _ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        memory.zoom_to_rect(
+            shape.visual_bounding_rect(),
+            options.zoom_to_fit_margin,
+            &options.scaling_range,
+        );
+        None
+    }
+}
+
+impl Interaction for ZoomToActualSize {
+    fn update(
+        self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        _shape: &mut Shape,
+        _style: &dyn Style,
+        options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        let anchor = ctx
+            .transform
+            .ui_to_canvas_content
+            .transform_pos(ctx.transform.ui_canvas_rect().center());
+        memory.zoom_to(1.0, anchor, &options.scaling_range);
+        None
+    }
+}
+
+impl Interaction for ZoomToSelection {
+    fn update(
+        self: Box<Self>,
+        memory: &mut ShapeEditorMemory,
+        _shape: &mut Shape,
+        _style: &dyn Style,
+        options: &ShapeEditorOptions,
+        ctx: &CanvasContext,
+    ) -> Option<Box<dyn Interaction>> {
+        let bounds = memory
+            .selection()
+            .control_points_bounds(&ctx.shape_control_points)?;
+        memory.zoom_to_rect(bounds, options.zoom_to_fit_margin, &options.scaling_range);
+        None
+    }
+}
+
 impl Interaction for AddPoint {
     fn update(
         self: Box<Self>,
@@ -351,9 +709,7 @@ impl Interaction for AddPoint {
         options: &ShapeEditorOptions,
         ctx: &CanvasContext,
     ) -> Option<Box<dyn Interaction>> {
-        let Some(&selected_point) = memory.selection().single_control_point() else {
-            return None;
-        };
+        let &selected_point = memory.selection().single_control_point()?;
         let Some(ShapeControlPoint::PathPoint { position, .. }) =
             ctx.shape_control_points.by_index(&selected_point).cloned()
         else {
@@ -367,13 +723,25 @@ impl Interaction for AddPoint {
                 .snap()
                 .snap_point
                 .unwrap_or(ctx.input.canvas_content_mouse_pos);
+            let chain_constraints = options
+                .connect_chained_shapes
+                .then(|| {
+                    Constraint::LinkTranslationBidirectional(
+                        selected_point,
+                        (CountShapes::count(shape), 0).into(),
+                    )
+                })
+                .into_iter();
             let new_point_index = match shape_type {
                 ShapeType::LineSegment => {
                     memory.apply_boxed_action(
-                        Box::new(InsertShape::from_shape(Shape::LineSegment {
-                            points: [position, mouse_pos],
-                            stroke: options.stroke,
-                        })),
+                        Box::new(
+                            InsertShape::from_shape(Shape::LineSegment {
+                                points: [position, mouse_pos],
+                                stroke: options.stroke,
+                            })
+                            .with_constraints(chain_constraints),
+                        ),
                         shape,
                     );
                     LastShapePointIndex::last_index(shape).unwrap_or(selected_point)
@@ -394,12 +762,15 @@ impl Interaction for AddPoint {
                         .shape_control_points
                         .connected_bezier_control_point(&selected_point);
                     memory.apply_boxed_action(
-                        Box::new(InsertShape::quadratic_bezier_from_two_points(
-                            position,
-                            control_point,
-                            mouse_pos,
-                            options.stroke,
-                        )),
+                        Box::new(
+                            InsertShape::quadratic_bezier_from_two_points(
+                                position,
+                                control_point,
+                                mouse_pos,
+                                options.stroke,
+                            )
+                            .with_constraints(chain_constraints),
+                        ),
                         shape,
                     );
                     LastShapePointIndex::last_index(shape).unwrap_or(selected_point)
@@ -409,12 +780,15 @@ impl Interaction for AddPoint {
                         .shape_control_points
                         .connected_bezier_control_point(&selected_point);
                     memory.apply_boxed_action(
-                        Box::new(InsertShape::cubic_bezier_from_two_points(
-                            position,
-                            start_control_point,
-                            mouse_pos,
-                            options.stroke,
-                        )),
+                        Box::new(
+                            InsertShape::cubic_bezier_from_two_points(
+                                position,
+                                start_control_point,
+                                mouse_pos,
+                                options.stroke,
+                            )
+                            .with_constraints(chain_constraints),
+                        ),
                         shape,
                     );
                     LastShapePointIndex::last_index(shape).unwrap_or(selected_point)
@@ -424,16 +798,6 @@ impl Interaction for AddPoint {
             memory
                 .selection_mut()
                 .select_single_control_point(new_point_index);
-            if options.connect_chained_shapes
-                && selected_point.shape_index != new_point_index.shape_index
-            {
-                memory
-                    .constraints
-                    .add_constraint(Constraint::LinkTranslationBidirectional(
-                        selected_point,
-                        new_point_index.first_point(),
-                    ));
-            }
         }
         None
     }
@@ -448,12 +812,23 @@ impl Interaction for DeletePoints {
         _options: &ShapeEditorOptions,
         _ctx: &CanvasContext,
     ) -> Option<Box<dyn Interaction>> {
-        memory.apply_boxed_action(
-            Box::new(RemoveShapePoints(
+        let mut actions: Vec<Box<dyn ShapeAction>> = Vec::new();
+        if let Some(constraint) = memory.selection().selected_constraint().copied() {
+            actions.push(Box::new(RemoveConstraint(constraint)));
+            memory.selection_mut().clear_selected_constraint();
+        }
+        if memory.selection().has_control_points() {
+            actions.push(Box::new(RemoveShapePoints(
                 memory.selection().control_points().clone(),
-            )),
-            shape,
-        );
+            )));
+        }
+        match actions.len() {
+            0 => {}
+            1 => memory.apply_boxed_action(actions.remove(0), shape),
+            _ => {
+                memory.apply_boxed_action(Box::new(Combined::new("Delete".into(), actions)), shape)
+            }
+        }
         None
     }
 }
@@ -491,8 +866,7 @@ impl Interaction for ChangeSelectionOnPrimary {
                         ctx.hovered_ui_shape_points
                             .keys()
                             .skip_while(|hovered_index| **hovered_index != *single_selected_index)
-                            .skip(1)
-                            .next()
+                            .nth(1)
                             .copied()
                     });
             if !(ctx.input.action_modifier.do_not_deselect_selected_points()
@@ -505,16 +879,38 @@ impl Interaction for ChangeSelectionOnPrimary {
                 memory.selection_mut().clear_selected_control_points();
             }
 
+            memory.selection_mut().clear_selected_constraint();
             if let Some(index_to_select) =
                 next_selected.or(ctx.hovered_ui_shape_points.keys().next().copied())
             {
                 memory.selection_mut().select_control_point(index_to_select);
+            } else if let Some(constraint) = ctx.hovered_constraint {
+                memory.selection_mut().select_constraint(constraint);
             }
         }
         None
     }
 }
 
+impl MoveGuide {
+    pub fn new(orientation: GuideOrientation) -> Self {
+        Self {
+            index: None,
+            guide: Guide {
+                orientation,
+                position: 0.0,
+                locked: false,
+            },
+        }
+    }
+}
+
+impl MoveOrigin {
+    pub fn new(previous_origin: Option<Pos2>) -> Self {
+        Self { previous_origin }
+    }
+}
+
 impl AddPointsThanShape {
     pub fn with_start_point(
         start_point: Pos2,
@@ -632,3 +1028,63 @@ impl AddPointsThanShape {
         }
     }
 }
+
+impl DragShape {
+    pub fn with_shape_type(shape_type: ShapeType) -> Option<Self> {
+        matches!(
+            shape_type,
+            ShapeType::Circle
+                | ShapeType::Ellipse
+                | ShapeType::LineSegment
+                | ShapeType::Path
+                | ShapeType::Rect
+        )
+        .then_some(Self {
+            shape_type,
+            start_pos: None,
+        })
+    }
+
+    fn drag_points(&self, start: Pos2, end: Pos2, modifier: &ActionModifier) -> (Pos2, Pos2) {
+        let mut delta = end - start;
+        match self.shape_type {
+            ShapeType::LineSegment | ShapeType::Path => {
+                if modifier.constrain_proportions() && delta != Vec2::ZERO {
+                    let step = std::f32::consts::FRAC_PI_4;
+                    delta = Vec2::angled((delta.angle() / step).round() * step) * delta.length();
+                }
+            }
+            _ => {
+                if modifier.constrain_proportions() || self.shape_type == ShapeType::Circle {
+                    let side = delta.x.abs().max(delta.y.abs());
+                    delta = Vec2::new(side.copysign(delta.x), side.copysign(delta.y));
+                }
+            }
+        }
+        if modifier.draw_from_center() {
+            (start - delta, start + delta)
+        } else {
+            (start, start + delta)
+        }
+    }
+
+    fn shape(&self, from: Pos2, to: Pos2, options: &ShapeEditorOptions) -> Option<Shape> {
+        let rect = utils::normalize_rect(&Rect::from_two_pos(from, to));
+        match self.shape_type {
+            ShapeType::Circle => Some(Shape::circle_stroke(
+                rect.center(),
+                rect.width() / 2.0,
+                options.stroke,
+            )),
+            ShapeType::Ellipse => Some(Shape::ellipse_stroke(
+                rect.center(),
+                rect.size() / 2.0,
+                options.stroke,
+            )),
+            ShapeType::LineSegment => Some(Shape::line_segment([from, to], options.stroke)),
+            ShapeType::Path => Some(Shape::Path(PathShape::line(vec![from, to], options.stroke))),
+            ShapeType::Rect => Some(Shape::rect_stroke(rect, 0.0, options.stroke)),
+            _ => None,
+        }
+    }
+}
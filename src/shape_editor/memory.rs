@@ -1,10 +1,14 @@
 use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::guides::Guide;
 use crate::shape_editor::interaction::Interaction;
 use crate::shape_editor::shape_action::ShapeAction;
-use crate::shape_editor::snap::SnapInfo;
+use crate::shape_editor::snap::{SnapInfo, SnapTargetKind, SnapTargetOverride};
+use crate::shape_editor::stable_ids::StableIds;
 use crate::shape_editor::transform::Transform;
 use crate::shape_editor::Selection;
-use egui::{Context, Id, Pos2, Shape};
+use egui::ahash::HashMap;
+use egui::{Context, Id, Pos2, Rect, Shape, Vec2};
+use std::ops::Range;
 
 #[derive(Clone)]
 pub struct ShapeEditorMemory {
@@ -15,7 +19,14 @@ pub struct ShapeEditorMemory {
     last_canvas_mouse_hover_pos: Pos2,
     selection: Selection,
     pub(crate) snap: SnapInfo,
+    pub(crate) snap_target_overrides: HashMap<SnapTargetKind, SnapTargetOverride>,
     pub(crate) constraints: Constraints,
+    pub(crate) stable_ids: StableIds,
+    pub(crate) guides: Vec<Guide>,
+    pub(crate) context_menu_guide: Option<usize>,
+    pub(crate) grid_origin: Option<Pos2>,
+    pub(crate) canvas_size: Vec2,
+    pub(crate) transform_preset: bool,
 }
 
 impl Default for ShapeEditorMemory {
@@ -28,7 +39,14 @@ impl Default for ShapeEditorMemory {
             last_canvas_mouse_hover_pos: Pos2::ZERO,
             selection: Default::default(),
             snap: Default::default(),
+            snap_target_overrides: HashMap::default(),
             constraints: Constraints::default(),
+            stable_ids: StableIds::default(),
+            guides: Vec::new(),
+            context_menu_guide: None,
+            grid_origin: None,
+            canvas_size: Vec2::ZERO,
+            transform_preset: false,
         }
     }
 }
@@ -42,10 +60,43 @@ impl ShapeEditorMemory {
         ctx.data_mut(|data| data.insert_temp(id, self))
     }
 
+    pub(crate) fn zoom_to(&mut self, zoom: f32, anchor: Pos2, scaling_range: &Range<Vec2>) {
+        let zoom = zoom.clamp(scaling_range.start.x, scaling_range.end.x);
+        self.transform = self.transform.zoom_to(zoom, anchor);
+    }
+
+    pub(crate) fn center_on(&mut self, point: Pos2) {
+        if self.canvas_size == Vec2::ZERO {
+            return;
+        }
+        self.transform = self.transform.center_on(point, self.canvas_size);
+    }
+
+    pub(crate) fn zoom_to_rect(&mut self, rect: Rect, margin: f32, scaling_range: &Range<Vec2>) {
+        if !rect.is_finite() || self.canvas_size == Vec2::ZERO {
+            return;
+        }
+        let available_size = (self.canvas_size - Vec2::splat(margin * 2.0)).max(Vec2::splat(1.0));
+        let zoom = [
+            available_size.x / rect.width(),
+            available_size.y / rect.height(),
+        ]
+        .into_iter()
+        .filter(|zoom| zoom.is_finite())
+        .reduce(f32::min)
+        .unwrap_or(self.transform.scale().x);
+        self.zoom_to(zoom, rect.center(), scaling_range);
+        self.center_on(rect.center());
+    }
+
     pub(crate) fn apply_boxed_action(&mut self, action: Box<dyn ShapeAction>, shape: &mut Shape) {
         let short_name = action.short_name();
-        let undo_action =
-            action.apply_with_selection(shape, &mut self.constraints, &mut self.selection);
+        let undo_action = action.apply_with_selection(
+            shape,
+            &mut self.constraints,
+            &mut self.stable_ids,
+            &mut self.selection,
+        );
         self.push_action_history(undo_action, short_name)
     }
 
@@ -55,7 +106,12 @@ impl ShapeEditorMemory {
 
     pub(crate) fn undo(&mut self, shape: &mut Shape) {
         if let Some((action, _)) = self.action_history.pop() {
-            action.apply_with_selection(shape, &mut self.constraints, &mut self.selection);
+            action.apply_with_selection(
+                shape,
+                &mut self.constraints,
+                &mut self.stable_ids,
+                &mut self.selection,
+            );
         }
     }
 
@@ -0,0 +1,205 @@
+use crate::shape_editor::memory::ShapeEditorMemory;
+use crate::shape_editor::style;
+use crate::shape_editor::transform::Transform;
+use egui::{CursorIcon, Id, Pos2, Rect, Response, Sense, Shape, Ui, Vec2, Widget};
+
+pub struct Minimap<'a> {
+    id: Id,
+    shape: &'a Shape,
+    style: &'a dyn style::Style,
+    size: Option<Vec2>,
+}
+
+impl<'a> Minimap<'a> {
+    pub fn new(id: Id, shape: &'a Shape, style: &'a dyn style::Style) -> Self {
+        Self {
+            id,
+            shape,
+            style,
+            size: None,
+        }
+    }
+
+    pub fn size(mut self, size: Vec2) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+impl<'a> Widget for Minimap<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let size = self.size.unwrap_or(self.style.minimap_size());
+        let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+        let mut memory = ShapeEditorMemory::load(ui.ctx(), self.id);
+        let response = show_minimap(ui, self.id, rect, self.shape, &mut memory, self.style);
+        memory.store(ui.ctx(), self.id);
+        response
+    }
+}
+
+pub(crate) fn overlay_rect(style: &dyn style::Style, canvas_rect: Rect) -> Rect {
+    let size = style.minimap_size();
+    Rect::from_min_size(
+        canvas_rect.max - size - Vec2::splat(style.minimap_margin()),
+        size,
+    )
+}
+
+pub(crate) fn show_minimap(
+    ui: &Ui,
+    id: Id,
+    rect: Rect,
+    shape: &Shape,
+    memory: &mut ShapeEditorMemory,
+    style: &dyn style::Style,
+) -> Response {
+    puffin_egui::puffin::profile_function!();
+    let response = ui.interact(rect, id.with("minimap"), Sense::click_and_drag());
+    let viewport = content_viewport(memory);
+    let content_rect = shape.visual_bounding_rect();
+    let content_rect = if content_rect.is_finite() {
+        content_rect.union(viewport)
+    } else {
+        viewport
+    };
+    let scale = (rect.size() / content_rect.size().max(Vec2::splat(1.0))).min_elem();
+    let minimap_transform = Transform::from_to(
+        Rect::from_center_size(content_rect.center(), rect.size() / scale),
+        rect,
+    );
+    let ui_viewport = minimap_transform.transform_rect(&viewport);
+    if let Some(pointer_pos) = response.interact_pointer_pos() {
+        let content_pointer_pos = minimap_transform.inverse().transform_pos(pointer_pos);
+        if response.clicked() || (response.drag_started() && !ui_viewport.contains(pointer_pos)) {
+            memory.center_on(content_pointer_pos);
+        } else if response.dragged() {
+            let content_delta = response.drag_delta() / scale;
+            let transform = memory
+                .transform()
+                .translate(-content_delta * memory.transform().scale());
+            memory.set_transform(transform);
+        }
+    }
+    if response.dragged() {
+        ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+    } else if response.hovered() {
+        ui.ctx().set_cursor_icon(CursorIcon::Grab);
+    }
+
+    let painter = ui.painter_at(rect);
+    painter.rect(
+        rect,
+        0.0,
+        style.minimap_bg_color(),
+        style.minimap_border_stroke(),
+    );
+    painter.add(ui.fonts(|fonts| minimap_transform.transform_shape_with_fonts(shape, fonts).0));
+    painter.rect(
+        minimap_transform.transform_rect(&content_viewport(memory)),
+        0.0,
+        style.minimap_viewport_fill(),
+        style.minimap_viewport_stroke(),
+    );
+    response
+}
+
+fn content_viewport(memory: &ShapeEditorMemory) -> Rect {
+    memory
+        .transform()
+        .inverse()
+        .transform_rect(&Rect::from_min_size(Pos2::ZERO, memory.canvas_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::style::Light;
+    use egui::{CentralPanel, Color32, Context, Event, Modifiers, PointerButton, RawInput, Stroke};
+
+    const MINIMAP_ID: &str = "minimap_test";
+
+    fn document() -> Shape {
+        Shape::rect_stroke(
+            Rect::from_min_size(Pos2::ZERO, Vec2::splat(1000.0)),
+            0.0,
+            Stroke::new(1.0, Color32::BLACK),
+        )
+    }
+
+    fn frame(ctx: &Context, events: Vec<Event>) {
+        let shape = document();
+        let _ = ctx.run(
+            RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::splat(400.0))),
+                events,
+                ..Default::default()
+            },
+            |ctx| {
+                CentralPanel::default().show(ctx, |ui| {
+                    ui.add(
+                        Minimap::new(Id::new(MINIMAP_ID), &shape, &Light::default())
+                            .size(Vec2::splat(100.0)),
+                    );
+                });
+            },
+        );
+    }
+
+    fn context() -> Context {
+        let ctx = Context::default();
+        let mut memory = ShapeEditorMemory::default();
+        memory.canvas_size = Vec2::splat(200.0);
+        memory.store(&ctx, Id::new(MINIMAP_ID));
+        frame(&ctx, Vec::new());
+        ctx
+    }
+
+    fn viewport(ctx: &Context) -> Rect {
+        content_viewport(&ShapeEditorMemory::load(ctx, Id::new(MINIMAP_ID)))
+    }
+
+    fn button(pos: Pos2, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn overlay_sits_in_the_bottom_right_corner() {
+        let canvas_rect = Rect::from_min_size(Pos2::new(10.0, 10.0), Vec2::splat(400.0));
+        assert_eq!(
+            overlay_rect(&Light::default(), canvas_rect),
+            Rect::from_min_size(Pos2::new(242.0, 282.0), Vec2::new(160.0, 120.0))
+        );
+    }
+
+    #[test]
+    fn click_centers_viewport_on_content() {
+        let ctx = context();
+        let center = Pos2::new(58.0, 58.0);
+        frame(&ctx, vec![Event::PointerMoved(center)]);
+        frame(&ctx, vec![button(center, true)]);
+        frame(&ctx, vec![button(center, false)]);
+        let viewport = viewport(&ctx);
+        assert!(viewport.center().distance(Pos2::new(500.0, 500.0)) < 1.0);
+        assert_eq!(viewport.size(), Vec2::splat(200.0));
+    }
+
+    #[test]
+    fn dragging_viewport_pans_by_scaled_delta() {
+        let ctx = context();
+        let start = Pos2::new(18.0, 18.0);
+        frame(&ctx, vec![Event::PointerMoved(start)]);
+        frame(&ctx, vec![button(start, true)]);
+        for x in [28.0, 38.0] {
+            frame(&ctx, vec![Event::PointerMoved(Pos2::new(x, 18.0))]);
+        }
+        frame(&ctx, vec![button(Pos2::new(38.0, 18.0), false)]);
+        let viewport = viewport(&ctx);
+        assert!((viewport.min.x - 200.0).abs() < 1.0, "{viewport:?}");
+        assert_eq!(viewport.min.y, 0.0);
+    }
+}
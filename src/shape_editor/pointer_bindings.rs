@@ -0,0 +1,169 @@
+use egui::ahash::HashMap;
+use egui::{Modifiers, PointerButton};
+use strum::EnumIter;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter)]
+pub enum ModifierAction {
+    KeepSelection,
+    AddPointOnClick,
+    ToggleSnap,
+    ConstrainProportions,
+    DrawFromCenter,
+}
+
+impl ModifierAction {
+    fn default_modifiers(&self) -> Modifiers {
+        match self {
+            ModifierAction::KeepSelection => Modifiers::SHIFT,
+            ModifierAction::AddPointOnClick => Modifiers::CTRL | Modifiers::COMMAND,
+            ModifierAction::ToggleSnap => Modifiers::ALT,
+            ModifierAction::ConstrainProportions => Modifiers::SHIFT,
+            ModifierAction::DrawFromCenter => Modifiers::ALT,
+        }
+    }
+
+    pub(crate) fn is_drag_create(&self) -> bool {
+        matches!(
+            self,
+            ModifierAction::ConstrainProportions | ModifierAction::DrawFromCenter
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollAction {
+    Pan,
+    Zoom,
+}
+
+#[derive(Clone, Debug)]
+pub struct PointerBindings {
+    pub pan_buttons: Vec<PointerButton>,
+    pub pan_with_space_drag: bool,
+    pub scroll: ScrollAction,
+    pub scroll_zoom_speed: f32,
+    pub modifiers: HashMap<ModifierAction, Modifiers>,
+}
+
+impl Default for PointerBindings {
+    fn default() -> Self {
+        Self {
+            pan_buttons: vec![PointerButton::Secondary],
+            pan_with_space_drag: false,
+            scroll: ScrollAction::Pan,
+            scroll_zoom_speed: 1.0 / 200.0,
+            modifiers: Default::default(),
+        }
+    }
+}
+
+impl PointerBindings {
+    pub fn modifiers(&self, action: ModifierAction) -> Modifiers {
+        self.modifiers
+            .get(&action)
+            .copied()
+            .unwrap_or(action.default_modifiers())
+    }
+
+    pub fn set_modifiers(&mut self, action: ModifierAction, modifiers: Modifiers) {
+        self.modifiers.insert(action, modifiers);
+    }
+
+    pub(crate) fn is_active(&self, action: ModifierAction, modifiers: Modifiers) -> bool {
+        let binding = self.modifiers(action);
+        !binding.is_none() && modifiers.contains(binding)
+    }
+
+    pub(crate) fn is_overridden(&self, action: ModifierAction, by: ModifierAction) -> bool {
+        let binding = self.modifiers(action);
+        let other = self.modifiers(by);
+        other.contains(binding)
+            && (!binding.contains(other) || (by.is_drag_create() && !action.is_drag_create()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shape_editor::pointer_bindings::{ModifierAction, PointerBindings};
+    use egui::Modifiers;
+
+    #[test]
+    fn active_requires_all_bound_modifiers() {
+        let mut bindings = PointerBindings::default();
+        bindings.set_modifiers(
+            ModifierAction::ToggleSnap,
+            Modifiers::ALT | Modifiers::SHIFT,
+        );
+        assert!(!bindings.is_active(ModifierAction::ToggleSnap, Modifiers::ALT));
+        assert!(!bindings.is_active(ModifierAction::ToggleSnap, Modifiers::SHIFT));
+        assert!(bindings.is_active(
+            ModifierAction::ToggleSnap,
+            Modifiers::ALT | Modifiers::SHIFT
+        ));
+        assert!(bindings.is_active(
+            ModifierAction::ToggleSnap,
+            Modifiers::ALT | Modifiers::SHIFT | Modifiers::CTRL
+        ));
+    }
+
+    #[test]
+    fn unbound_action_is_never_active() {
+        let mut bindings = PointerBindings::default();
+        bindings.set_modifiers(ModifierAction::KeepSelection, Modifiers::NONE);
+        assert!(!bindings.is_active(ModifierAction::KeepSelection, Modifiers::NONE));
+        assert!(!bindings.is_active(ModifierAction::KeepSelection, Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn ctrl_and_command_are_equivalent() {
+        let bindings = PointerBindings::default();
+        assert!(bindings.is_active(ModifierAction::AddPointOnClick, Modifiers::CTRL));
+        assert!(bindings.is_active(ModifierAction::AddPointOnClick, Modifiers::COMMAND));
+        assert!(!bindings.is_active(ModifierAction::AddPointOnClick, Modifiers::SHIFT));
+    }
+
+    #[test]
+    fn most_specific_binding_wins() {
+        let mut bindings = PointerBindings::default();
+        bindings.set_modifiers(
+            ModifierAction::ToggleSnap,
+            Modifiers::SHIFT | Modifiers::ALT,
+        );
+        assert!(bindings.is_overridden(ModifierAction::KeepSelection, ModifierAction::ToggleSnap));
+        assert!(!bindings.is_overridden(ModifierAction::ToggleSnap, ModifierAction::KeepSelection));
+        assert!(!bindings.is_overridden(
+            ModifierAction::KeepSelection,
+            ModifierAction::AddPointOnClick
+        ));
+    }
+
+    #[test]
+    fn equal_bindings_do_not_override() {
+        let mut bindings = PointerBindings::default();
+        bindings.set_modifiers(ModifierAction::ToggleSnap, Modifiers::SHIFT);
+        assert!(!bindings.is_overridden(ModifierAction::KeepSelection, ModifierAction::ToggleSnap));
+        assert!(!bindings.is_overridden(ModifierAction::ToggleSnap, ModifierAction::KeepSelection));
+    }
+
+    #[test]
+    fn drag_create_actions_win_over_equal_bindings() {
+        let bindings = PointerBindings::default();
+        assert!(bindings.is_overridden(
+            ModifierAction::KeepSelection,
+            ModifierAction::ConstrainProportions
+        ));
+        assert!(bindings.is_overridden(ModifierAction::ToggleSnap, ModifierAction::DrawFromCenter));
+        assert!(!bindings.is_overridden(ModifierAction::DrawFromCenter, ModifierAction::ToggleSnap));
+    }
+
+    #[test]
+    fn command_binding_overrides_its_ctrl_subset() {
+        let mut bindings = PointerBindings::default();
+        bindings.set_modifiers(
+            ModifierAction::ToggleSnap,
+            Modifiers::COMMAND | Modifiers::SHIFT,
+        );
+        assert!(bindings.is_overridden(ModifierAction::AddPointOnClick, ModifierAction::ToggleSnap));
+        assert!(bindings.is_overridden(ModifierAction::KeepSelection, ModifierAction::ToggleSnap));
+    }
+}
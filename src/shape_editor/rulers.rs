@@ -1,25 +1,96 @@
 use crate::shape_editor::canvas::CanvasContext;
+use crate::shape_editor::guides::GuideOrientation;
 use crate::shape_editor::index::GridLineType;
 use crate::shape_editor::style;
 use egui::emath::{Pos2, Rect, Vec2};
-use egui::epaint::{Color32, Shape, TextShape};
-use egui::Painter;
+use egui::epaint::{Color32, Galley, Shape, TextShape};
+use egui::{CursorIcon, Id, Painter, Response, Sense, Ui};
 use std::ops::Add;
+use std::sync::Arc;
+
+fn rulers_rects(style: &dyn style::Style, rulers_rect: Rect) -> (Rect, Rect) {
+    (
+        Rect::from_min_max(
+            Pos2::new(rulers_rect.min.x + style.rulers_width(), rulers_rect.min.y),
+            Pos2::new(rulers_rect.max.x, rulers_rect.min.y + style.rulers_width()),
+        ),
+        Rect::from_min_max(
+            Pos2::new(rulers_rect.min.x, rulers_rect.min.y + style.rulers_width()),
+            Pos2::new(rulers_rect.min.x + style.rulers_width(), rulers_rect.max.y),
+        ),
+    )
+}
+
+pub(crate) fn ruler_drag_started(
+    style: &dyn style::Style,
+    ui: &Ui,
+    id: Id,
+    rulers_rect: Rect,
+) -> Option<GuideOrientation> {
+    let (horizontal_ruler_rect, vertical_ruler_rect) = rulers_rects(style, rulers_rect);
+    [
+        (
+            horizontal_ruler_rect,
+            GuideOrientation::Horizontal,
+            CursorIcon::ResizeVertical,
+        ),
+        (
+            vertical_ruler_rect,
+            GuideOrientation::Vertical,
+            CursorIcon::ResizeHorizontal,
+        ),
+    ]
+    .into_iter()
+    .find_map(|(rect, orientation, cursor_icon)| {
+        let response = ui
+            .interact(rect, id.with(orientation), Sense::drag())
+            .on_hover_cursor(cursor_icon);
+        response.drag_started().then_some(orientation)
+    })
+}
+
+fn ruler_corner_rect(style: &dyn style::Style, rulers_rect: Rect) -> Rect {
+    Rect::from_min_size(rulers_rect.min, Vec2::splat(style.rulers_width()))
+}
+
+pub(crate) fn ruler_corner_response(
+    style: &dyn style::Style,
+    ui: &Ui,
+    id: Id,
+    rulers_rect: Rect,
+) -> Response {
+    ui.interact(
+        ruler_corner_rect(style, rulers_rect),
+        id.with("rulers_corner"),
+        Sense::click_and_drag(),
+    )
+    .on_hover_cursor(CursorIcon::Crosshair)
+}
 
 pub(crate) fn paint_rulers(
     style: &dyn style::Style,
     ui_painter: &Painter,
     rulers_rect: Rect,
     ctx: &CanvasContext,
+    selection_bounds: Option<Rect>,
 ) {
     let transform = &ctx.transform.canvas_content_to_canvas;
+    let (horizontal_ruler_rect, vertical_ruler_rect) = rulers_rects(style, rulers_rect);
+    let selection_bounds =
+        selection_bounds.map(|bounds| ctx.transform.canvas_content_to_ui.transform_rect(&bounds));
+    let cursor_pos = ctx.input.mouse_hover_pos;
 
-    let ruler_rect = Rect::from_min_max(
-        Pos2::new(rulers_rect.min.x + style.rulers_width(), rulers_rect.min.y),
-        Pos2::new(rulers_rect.max.x, rulers_rect.min.y + style.rulers_width()),
-    );
+    let ruler_rect = horizontal_ruler_rect;
     let painter = ui_painter.with_clip_rect(ruler_rect);
     let mut vec = vec![Shape::rect_filled(ruler_rect, 0.0, Color32::WHITE)];
+    if let Some(bounds) = selection_bounds {
+        vec.push(Shape::rect_filled(
+            Rect::from_x_y_ranges(bounds.x_range(), ruler_rect.y_range()),
+            0.0,
+            style.rulers_selection_fill(),
+        ));
+    }
+    let mut labels_end = f32::NEG_INFINITY;
     for (x, line_types) in &ctx.grid_index.horizontal.0 {
         let ui_x = transform.transform_pos(Pos2::new(x.into_inner(), 0.0)).x + ruler_rect.left();
         for line_type in line_types {
@@ -35,16 +106,18 @@ pub(crate) fn paint_rulers(
                     let label = TextShape::new(
                         Pos2::new(ui_x, ruler_rect.min.y)
                             .add(style.rulers_text_position().to_vec2()),
-                        painter.fonts(|fonts| {
-                            fonts.layout_no_wrap(
-                                x.to_string(),
-                                style.rulers_font().clone(),
-                                style.rulers_font_color(),
-                            )
-                        }),
+                        ruler_label(
+                            &painter,
+                            style,
+                            ctx.units.format(x.into_inner() - ctx.grid_index.origin.x),
+                        ),
                         style.rulers_font_color(),
                     );
-                    vec.push(Shape::Text(label));
+                    if label.pos.x >= labels_end {
+                        labels_end =
+                            label.pos.x + label.galley.size().x + style.rulers_label_spacing();
+                        vec.push(Shape::Text(label));
+                    }
                 }
                 GridLineType::Secondary => {
                     vec.push(Shape::line_segment(
@@ -67,14 +140,48 @@ pub(crate) fn paint_rulers(
             }
         }
     }
+    if let Some(cursor_pos) = cursor_pos {
+        let cursor_x = ctx
+            .transform
+            .ui_to_canvas_content
+            .transform_pos(cursor_pos)
+            .x;
+        vec.push(Shape::line_segment(
+            [
+                Pos2::new(cursor_pos.x, ruler_rect.min.y),
+                Pos2::new(cursor_pos.x, ruler_rect.max.y),
+            ],
+            style.rulers_cursor_stroke(),
+        ));
+        let label = TextShape::new(
+            Pos2::new(cursor_pos.x, ruler_rect.min.y).add(style.rulers_text_position().to_vec2()),
+            ruler_label(
+                &painter,
+                style,
+                ctx.units.format(cursor_x - ctx.grid_index.origin.x),
+            ),
+            style.rulers_cursor_stroke().color,
+        );
+        vec.push(Shape::rect_filled(
+            label.visual_bounding_rect(),
+            0.0,
+            Color32::WHITE,
+        ));
+        vec.push(Shape::Text(label));
+    }
     painter.extend(vec);
 
-    let ruler_rect = Rect::from_min_max(
-        Pos2::new(rulers_rect.min.x, rulers_rect.min.y + style.rulers_width()),
-        Pos2::new(rulers_rect.min.x + style.rulers_width(), rulers_rect.max.y),
-    );
+    let ruler_rect = vertical_ruler_rect;
     let painter = ui_painter.with_clip_rect(ruler_rect);
     let mut vec = vec![Shape::rect_filled(ruler_rect, 0.0, Color32::WHITE)];
+    if let Some(bounds) = selection_bounds {
+        vec.push(Shape::rect_filled(
+            Rect::from_x_y_ranges(ruler_rect.x_range(), bounds.y_range()),
+            0.0,
+            style.rulers_selection_fill(),
+        ));
+    }
+    let mut labels_end = f32::NEG_INFINITY;
     for (y, line_types) in &ctx.grid_index.vertical.0 {
         let ui_y = transform.transform_pos(Pos2::new(0.0, y.into_inner())).y + ruler_rect.top();
         for line_type in line_types {
@@ -87,22 +194,21 @@ pub(crate) fn paint_rulers(
                         ],
                         style.rulers_stroke(),
                     ));
-                    let label = TextShape::new(
-                        Pos2::new(ruler_rect.min.x, ui_y).add(Vec2::new(
-                            style.rulers_text_position().y,
-                            style.rulers_text_position().x,
-                        )),
-                        painter.fonts(|fonts| {
-                            fonts.layout_no_wrap(
-                                y.to_string(),
-                                style.rulers_font().clone(),
-                                style.rulers_font_color(),
-                            )
-                        }),
+                    let label = vertical_ruler_label(
+                        ruler_rect,
+                        ui_y,
+                        ruler_label(
+                            &painter,
+                            style,
+                            ctx.units.format(y.into_inner() - ctx.grid_index.origin.y),
+                        ),
                         style.rulers_font_color(),
-                    )
-                    .with_angle(-std::f32::consts::FRAC_PI_2);
-                    vec.push(Shape::Text(label));
+                        style,
+                    );
+                    if ui_y - label.galley.size().x >= labels_end {
+                        labels_end = ui_y + style.rulers_label_spacing();
+                        vec.push(Shape::Text(label));
+                    }
                 }
                 GridLineType::Secondary => {
                     vec.push(Shape::line_segment(
@@ -125,5 +231,114 @@ pub(crate) fn paint_rulers(
             }
         }
     }
+    if let Some(cursor_pos) = cursor_pos {
+        let cursor_y = ctx
+            .transform
+            .ui_to_canvas_content
+            .transform_pos(cursor_pos)
+            .y;
+        vec.push(Shape::line_segment(
+            [
+                Pos2::new(ruler_rect.min.x, cursor_pos.y),
+                Pos2::new(ruler_rect.max.x, cursor_pos.y),
+            ],
+            style.rulers_cursor_stroke(),
+        ));
+        let label = vertical_ruler_label(
+            ruler_rect,
+            cursor_pos.y,
+            ruler_label(
+                &painter,
+                style,
+                ctx.units.format(cursor_y - ctx.grid_index.origin.y),
+            ),
+            style.rulers_cursor_stroke().color,
+            style,
+        );
+        let label_size = label.galley.size();
+        vec.push(Shape::rect_filled(
+            Rect::from_min_max(
+                Pos2::new(label.pos.x, label.pos.y - label_size.x),
+                Pos2::new(label.pos.x + label_size.y, label.pos.y),
+            ),
+            0.0,
+            Color32::WHITE,
+        ));
+        vec.push(Shape::Text(label));
+    }
     painter.extend(vec);
+
+    let corner_rect = ruler_corner_rect(style, rulers_rect);
+    let painter = ui_painter.with_clip_rect(corner_rect);
+    let center = corner_rect.center();
+    let half_size = style.rulers_width() / 4.0;
+    painter.extend([
+        Shape::rect_filled(corner_rect, 0.0, Color32::WHITE),
+        Shape::line_segment(
+            [
+                center - Vec2::new(half_size, 0.0),
+                center + Vec2::new(half_size, 0.0),
+            ],
+            style.rulers_origin_stroke(),
+        ),
+        Shape::line_segment(
+            [
+                center - Vec2::new(0.0, half_size),
+                center + Vec2::new(0.0, half_size),
+            ],
+            style.rulers_origin_stroke(),
+        ),
+    ]);
+}
+
+fn ruler_label(painter: &Painter, style: &dyn style::Style, text: String) -> Arc<Galley> {
+    painter.fonts(|fonts| {
+        fonts.layout_no_wrap(text, style.rulers_font().clone(), style.rulers_font_color())
+    })
+}
+
+fn vertical_ruler_label(
+    ruler_rect: Rect,
+    ui_y: f32,
+    galley: Arc<Galley>,
+    color: Color32,
+    style: &dyn style::Style,
+) -> TextShape {
+    TextShape::new(
+        Pos2::new(ruler_rect.min.x, ui_y).add(Vec2::new(
+            style.rulers_text_position().y,
+            style.rulers_text_position().x,
+        )),
+        galley,
+        color,
+    )
+    .with_angle(-std::f32::consts::FRAC_PI_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::style::Light;
+
+    #[test]
+    fn rulers_frame_the_canvas_around_the_corner() {
+        let style = Light::default();
+        let rulers_rect = Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::new(200.0, 100.0));
+        let (horizontal, vertical) = rulers_rects(&style, rulers_rect);
+        let corner = ruler_corner_rect(&style, rulers_rect);
+        assert_eq!(
+            corner,
+            Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::splat(16.0))
+        );
+        assert_eq!(
+            horizontal,
+            Rect::from_min_max(Pos2::new(26.0, 20.0), Pos2::new(210.0, 36.0))
+        );
+        assert_eq!(
+            vertical,
+            Rect::from_min_max(Pos2::new(10.0, 36.0), Pos2::new(26.0, 120.0))
+        );
+        assert!(!corner.intersects(horizontal.shrink(0.5)));
+        assert!(!corner.intersects(vertical.shrink(0.5)));
+    }
 }
@@ -1,4 +1,5 @@
 use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::stable_ids::StableIds;
 use crate::shape_editor::Selection;
 use dyn_clone::DynClone;
 use egui::emath::Pos2;
@@ -6,25 +7,33 @@ use egui::epaint::Vertex;
 use egui::Shape;
 
 pub mod add_shape_points;
+pub mod cut_path;
+pub mod edit_constraints;
 pub mod insert_shape;
+pub mod join_paths;
 pub mod move_shape_points;
+pub mod remap_point_indices;
 pub mod remove_shape_points;
 pub mod replace_shapes;
+pub mod reverse_path;
+pub mod toggle_path_closed;
 
 pub trait ShapeAction: DynClone + Send + Sync {
     fn apply(
         self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction>;
     fn apply_with_selection(
         self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
         selection: &mut Selection,
     ) -> Box<dyn ShapeAction> {
         Box::new(RestoreSelectionActionWrapper::new(
-            self.apply(shape, constraints),
+            self.apply(shape, constraints, stable_ids),
             selection.clone(),
         ))
     }
@@ -41,6 +50,7 @@ impl ShapeAction for Noop {
         self: Box<Self>,
         _shape: &mut Shape,
         _constraints: &mut Constraints,
+        _stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
         self
     }
@@ -70,14 +80,32 @@ impl ShapeAction for Combined {
         self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
         let owned = *self;
-        let inverted: Vec<Box<dyn ShapeAction>> = owned
+        let mut inverted: Vec<Box<dyn ShapeAction>> = owned
             .actions
             .into_iter()
-            .map(|action| action.apply(shape, constraints))
-            .rev()
+            .map(|action| action.apply(shape, constraints, stable_ids))
             .collect();
+        inverted.reverse();
+        Box::new(Self::new(format!("Undo {}", owned.short_name), inverted))
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let owned = *self;
+        let mut inverted: Vec<Box<dyn ShapeAction>> = owned
+            .actions
+            .into_iter()
+            .map(|action| action.apply_with_selection(shape, constraints, stable_ids, selection))
+            .collect();
+        inverted.reverse();
         Box::new(Self::new(format!("Undo {}", owned.short_name), inverted))
     }
 
@@ -103,19 +131,21 @@ impl ShapeAction for RestoreSelectionActionWrapper {
         self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
-        self.action.apply(shape, constraints)
+        self.action.apply(shape, constraints, stable_ids)
     }
 
     fn apply_with_selection(
         self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
         selection: &mut Selection,
     ) -> Box<dyn ShapeAction> {
         let result = self
             .action
-            .apply_with_selection(shape, constraints, selection);
+            .apply_with_selection(shape, constraints, stable_ids, selection);
         *selection = self.selection;
         result
     }
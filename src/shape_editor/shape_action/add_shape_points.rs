@@ -1,10 +1,13 @@
 use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
 use crate::shape_editor::shape_action::remove_shape_points::RemoveShapePoints;
-use crate::shape_editor::shape_action::{ShapeAction, ShapePoint};
+use crate::shape_editor::shape_action::{RestoreSelectionActionWrapper, ShapeAction, ShapePoint};
 use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
     IndexedShapesVisitor, IndexedShapesVisitorAdapter,
 };
 use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeVisitor};
+use crate::shape_editor::stable_ids::StableIds;
+use crate::shape_editor::Selection;
 use egui::Shape;
 use itertools::Itertools;
 use std::collections::{BTreeMap, BTreeSet};
@@ -25,15 +28,33 @@ impl ShapeAction for AddShapePoints {
     fn apply(
         self: Box<Self>,
         shape: &mut Shape,
-        _constraints: &mut Constraints,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
     ) -> Box<dyn ShapeAction> {
         let owned = *self;
         let mut visitor = AddShapePointsVisitor {
             index_to_add: owned.0,
             added: Default::default(),
+            remap: Default::default(),
         };
+        let selection_state = selection.clone();
         IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
-        Box::new(RemoveShapePoints(visitor.added))
+        visitor.remap.apply_to_constraints(constraints, stable_ids);
+        visitor.remap.apply_to_selection(selection);
+        Box::new(RestoreSelectionActionWrapper::new(
+            Box::new(RemoveShapePoints(visitor.added)),
+            selection_state,
+        ))
     }
 
     fn short_name(&self) -> String {
@@ -44,17 +65,38 @@ impl ShapeAction for AddShapePoints {
 struct AddShapePointsVisitor {
     index_to_add: BTreeMap<usize, BTreeMap<usize, ShapePoint>>,
     added: BTreeSet<ShapePointIndex>,
+    remap: RemapPointIndices,
+}
+
+impl AddShapePointsVisitor {
+    fn remap_shape_points(&mut self, shape_index: usize, points_count: usize, added: &[usize]) {
+        for i in 0..points_count {
+            let new_index = added.iter().fold(
+                i,
+                |index, added| if *added <= index { index + 1 } else { index },
+            );
+            self.remap
+                .insert((shape_index, i).into(), (shape_index, new_index).into());
+        }
+    }
 }
 
 impl IndexedShapesVisitor for AddShapePointsVisitor {
     fn indexed_single_shape(&mut self, shape_index: usize, shape: &mut Shape) -> Option<()> {
         if let Some(points_to_add) = self.index_to_add.remove(&shape_index) {
+            let points_count = match shape {
+                Shape::Path(path) => path.points.len(),
+                Shape::Mesh(mesh) => mesh.vertices.len(),
+                _ => 0,
+            };
+            let mut added = Vec::new();
             for (point_index, shape_point) in points_to_add.into_iter().sorted_by_key(|(k, _)| *k) {
                 match shape {
                     Shape::Path(path) => {
                         if let ShapePoint::Pos(pos) = shape_point {
                             path.points.insert(point_index, pos);
                             self.added.insert((shape_index, point_index).into());
+                            added.push(point_index);
                         }
                     }
                     Shape::Mesh(mesh) => {
@@ -62,11 +104,13 @@ impl IndexedShapesVisitor for AddShapePointsVisitor {
                             mesh.vertices.insert(point_index, vertex);
                             mesh.indices.insert(point_index, vertex_index);
                             self.added.insert((shape_index, point_index).into());
+                            added.push(point_index);
                         }
                     }
                     _ => {}
                 }
             }
+            self.remap_shape_points(shape_index, points_count, &added);
         }
 
         self.index_to_add.is_empty().then_some(())
@@ -0,0 +1,342 @@
+use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::shape_action::insert_shape::InsertShape;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
+use crate::shape_editor::shape_action::replace_shapes::ReplaceShapes;
+use crate::shape_editor::shape_action::toggle_path_closed::OpenPath;
+use crate::shape_editor::shape_action::{Combined, Noop, ShapeAction};
+use crate::shape_editor::shape_visitor::count_shapes::CountShapes;
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
+use crate::shape_editor::shape_visitor::ShapePointIndex;
+use crate::shape_editor::stable_ids::StableIds;
+use crate::shape_editor::Selection;
+use egui::ahash::HashMap;
+use egui::{Pos2, Shape};
+
+#[derive(Clone)]
+pub struct CutPath {
+    index: ShapePointIndex,
+    insert_point: Option<Pos2>,
+}
+
+impl CutPath {
+    pub fn at_point(index: ShapePointIndex) -> Self {
+        Self {
+            index,
+            insert_point: None,
+        }
+    }
+
+    pub fn at_segment(segment_start: ShapePointIndex, point: Pos2) -> Self {
+        Self {
+            index: segment_start,
+            insert_point: Some(point),
+        }
+    }
+}
+
+impl ShapeAction for CutPath {
+    fn apply(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let ShapePointIndex {
+            shape_index,
+            point_index,
+        } = self.index;
+        let Some(Shape::Path(path)) = GetShapes::single(shape, shape_index) else {
+            return Box::new(Noop);
+        };
+        let points = &path.points;
+        let points_count = points.len();
+        let mut remap = RemapPointIndices::default();
+        let actions: Vec<Box<dyn ShapeAction>> = match (path.closed, self.insert_point) {
+            (true, _) if point_index >= points_count => return Box::new(Noop),
+            (true, None) => {
+                return Box::new(OpenPath(self.index)).apply_with_selection(
+                    shape,
+                    constraints,
+                    stable_ids,
+                    selection,
+                )
+            }
+            (true, Some(insert_point)) => {
+                let mut opened = path.clone();
+                opened.points.rotate_left((point_index + 1) % points_count);
+                opened.points.insert(0, insert_point);
+                opened.points.push(insert_point);
+                opened.closed = false;
+                for i in 0..points_count {
+                    remap.insert(
+                        (shape_index, i).into(),
+                        (
+                            shape_index,
+                            (i + points_count - point_index - 1) % points_count + 1,
+                        )
+                            .into(),
+                    );
+                }
+                vec![replace_path(shape_index, Shape::Path(opened))]
+            }
+            (false, None) if point_index == 0 || point_index + 1 >= points_count => {
+                return Box::new(Noop)
+            }
+            (false, Some(_)) if point_index + 1 >= points_count => return Box::new(Noop),
+            (false, insert_point) => {
+                let new_shape_index = CountShapes::count(shape);
+                let mut first = path.clone();
+                first.points.truncate(point_index + 1);
+                let mut second = path.clone();
+                second.points = points[point_index..].to_vec();
+                if let Some(insert_point) = insert_point {
+                    first.points.push(insert_point);
+                    second.points[0] = insert_point;
+                }
+                for i in point_index + 1..points_count {
+                    remap.insert(
+                        (shape_index, i).into(),
+                        (new_shape_index, i - point_index).into(),
+                    );
+                }
+                vec![
+                    replace_path(shape_index, Shape::Path(first)),
+                    Box::new(InsertShape::from_shape(Shape::Path(second))),
+                ]
+            }
+        };
+        Box::new(Combined::new(
+            "Cut Path".into(),
+            actions
+                .into_iter()
+                .chain([Box::new(remap) as Box<dyn ShapeAction>])
+                .collect(),
+        ))
+        .apply_with_selection(shape, constraints, stable_ids, selection)
+    }
+
+    fn short_name(&self) -> String {
+        "Cut Path".into()
+    }
+}
+
+fn replace_path(shape_index: usize, path: Shape) -> Box<dyn ShapeAction> {
+    Box::new(ReplaceShapes::new(HashMap::from_iter([(
+        shape_index,
+        path,
+    )])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::constraints::Constraint;
+    use egui::ahash::HashSet;
+    use egui::epaint::PathShape;
+    use egui::{Color32, Stroke};
+    use std::collections::BTreeSet;
+
+    fn points(points: &[(f32, f32)]) -> Vec<Pos2> {
+        points.iter().map(|(x, y)| Pos2::new(*x, *y)).collect()
+    }
+
+    fn square() -> Shape {
+        Shape::Vec(vec![Shape::Path(PathShape::closed_line(
+            points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]),
+            Stroke::new(1.0, Color32::BLACK),
+        ))])
+    }
+
+    fn polyline() -> Shape {
+        Shape::Vec(vec![Shape::Path(PathShape::line(
+            points(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]),
+            Stroke::new(1.0, Color32::BLACK),
+        ))])
+    }
+
+    fn path(shape: &Shape, index: usize) -> &PathShape {
+        match shape {
+            Shape::Vec(shapes) => match shapes.get(index) {
+                Some(Shape::Path(path)) => path,
+                shape => panic!("expected path, got {:?}", shape),
+            },
+            shape => panic!("expected vec shape, got {:?}", shape),
+        }
+    }
+
+    struct Document {
+        shape: Shape,
+        constraints: Constraints,
+        stable_ids: StableIds,
+        selection: Selection,
+    }
+
+    impl Document {
+        fn new(shape: Shape) -> Self {
+            Self {
+                shape,
+                constraints: Constraints::default(),
+                stable_ids: StableIds::default(),
+                selection: Selection::default(),
+            }
+        }
+
+        fn apply(&mut self, action: Box<dyn ShapeAction>) -> Box<dyn ShapeAction> {
+            action.apply_with_selection(
+                &mut self.shape,
+                &mut self.constraints,
+                &mut self.stable_ids,
+                &mut self.selection,
+            )
+        }
+    }
+
+    #[test]
+    fn cut_closed_path_at_point_opens_it() {
+        let mut document = Document::new(square());
+        let undo = document.apply(Box::new(CutPath::at_point((0, 1).into())));
+        let opened = path(&document.shape, 0);
+        assert!(!opened.closed);
+        assert_eq!(
+            opened.points,
+            points(&[(1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0), (1.0, 0.0)])
+        );
+        document.apply(undo);
+        assert_eq!(format!("{:?}", document.shape), format!("{:?}", square()));
+    }
+
+    #[test]
+    fn cut_closed_path_at_segment_opens_it_at_the_cut() {
+        let mut document = Document::new(square());
+        let point_id = document.stable_ids.point_id((0, 0).into());
+        document.selection.select_control_point((0, 2).into());
+        let undo = document.apply(Box::new(CutPath::at_segment(
+            (0, 0).into(),
+            Pos2::new(0.5, 0.0),
+        )));
+        let opened = path(&document.shape, 0);
+        assert!(!opened.closed);
+        assert_eq!(
+            opened.points,
+            points(&[
+                (0.5, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+                (0.0, 1.0),
+                (0.0, 0.0),
+                (0.5, 0.0)
+            ])
+        );
+        assert_eq!(
+            document.stable_ids.point_index(point_id),
+            Some((0, 4).into())
+        );
+        assert_eq!(
+            document.selection.control_points(),
+            &BTreeSet::from([(0, 2).into()])
+        );
+        document.apply(undo);
+        assert_eq!(format!("{:?}", document.shape), format!("{:?}", square()));
+        assert_eq!(
+            document.stable_ids.point_index(point_id),
+            Some((0, 0).into())
+        );
+    }
+
+    #[test]
+    fn cut_open_path_splits_into_two_shapes_and_remaps_constraints() {
+        let mut document = Document::new(polyline());
+        let before_cut = Constraint::Vertical((0, 0).into(), (0, 1).into());
+        let after_cut = Constraint::Horizontal((0, 2).into(), (0, 3).into());
+        document.constraints.add_constraint(before_cut);
+        document.constraints.add_constraint(after_cut);
+        let point_id = document.stable_ids.point_id((0, 3).into());
+        document.selection.select_control_point((0, 2).into());
+
+        let undo = document.apply(Box::new(CutPath::at_point((0, 1).into())));
+        assert_eq!(
+            path(&document.shape, 0).points,
+            points(&[(0.0, 0.0), (1.0, 0.0)])
+        );
+        assert_eq!(
+            path(&document.shape, 1).points,
+            points(&[(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)])
+        );
+        assert_eq!(
+            document
+                .constraints
+                .constraints()
+                .copied()
+                .collect::<HashSet<_>>(),
+            HashSet::from_iter([
+                before_cut,
+                Constraint::Horizontal((1, 1).into(), (1, 2).into())
+            ])
+        );
+        assert_eq!(
+            document.stable_ids.point_index(point_id),
+            Some((1, 2).into())
+        );
+        assert_eq!(
+            document.selection.control_points(),
+            &BTreeSet::from([(1, 1).into()])
+        );
+
+        document.apply(undo);
+        assert_eq!(path(&document.shape, 0).points, path(&polyline(), 0).points);
+        assert!(matches!(
+            &document.shape,
+            Shape::Vec(shapes) if matches!(shapes.get(1), None | Some(Shape::Noop))
+        ));
+        assert_eq!(
+            document
+                .constraints
+                .constraints()
+                .copied()
+                .collect::<HashSet<_>>(),
+            HashSet::from_iter([before_cut, after_cut])
+        );
+        assert_eq!(
+            document.stable_ids.point_index(point_id),
+            Some((0, 3).into())
+        );
+        assert_eq!(
+            document.selection.control_points(),
+            &BTreeSet::from([(0, 2).into()])
+        );
+    }
+
+    #[test]
+    fn cut_open_path_at_segment_inserts_the_cut_point_into_both_shapes() {
+        let mut document = Document::new(polyline());
+        document.apply(Box::new(CutPath::at_segment(
+            (0, 1).into(),
+            Pos2::new(1.5, 0.0),
+        )));
+        assert_eq!(
+            path(&document.shape, 0).points,
+            points(&[(0.0, 0.0), (1.0, 0.0), (1.5, 0.0)])
+        );
+        assert_eq!(
+            path(&document.shape, 1).points,
+            points(&[(1.5, 0.0), (2.0, 0.0), (3.0, 0.0)])
+        );
+    }
+
+    #[test]
+    fn cut_open_path_at_end_point_does_nothing() {
+        let mut document = Document::new(polyline());
+        document.apply(Box::new(CutPath::at_point((0, 3).into())));
+        assert_eq!(format!("{:?}", document.shape), format!("{:?}", polyline()));
+    }
+}
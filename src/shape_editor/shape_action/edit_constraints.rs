@@ -0,0 +1,132 @@
+use crate::shape_editor::constraints::{Constraint, Constraints};
+use crate::shape_editor::shape_action::{Noop, ShapeAction};
+use crate::shape_editor::stable_ids::StableIds;
+use egui::Shape;
+
+#[derive(Clone)]
+pub struct AddConstraint(pub Constraint);
+
+#[derive(Clone)]
+pub struct RemoveConstraint(pub Constraint);
+
+impl ShapeAction for AddConstraint {
+    fn apply(
+        self: Box<Self>,
+        _shape: &mut Shape,
+        constraints: &mut Constraints,
+        _stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        if constraints.add_constraint(self.0) {
+            Box::new(RemoveConstraint(self.0))
+        } else {
+            Box::new(Noop)
+        }
+    }
+
+    fn short_name(&self) -> String {
+        "Add Constraint".into()
+    }
+}
+
+impl ShapeAction for RemoveConstraint {
+    fn apply(
+        self: Box<Self>,
+        _shape: &mut Shape,
+        constraints: &mut Constraints,
+        _stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        if constraints.remove_constraint(&self.0) {
+            Box::new(AddConstraint(self.0))
+        } else {
+            Box::new(Noop)
+        }
+    }
+
+    fn short_name(&self) -> String {
+        "Remove Constraint".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::memory::ShapeEditorMemory;
+    use egui::ahash::HashSet;
+
+    fn constraint_set(memory: &ShapeEditorMemory) -> HashSet<Constraint> {
+        memory.constraints.constraints().copied().collect()
+    }
+
+    #[test]
+    fn add_and_remove_are_undone_in_order() {
+        let first = Constraint::Horizontal((0, 0).into(), (0, 1).into());
+        let second = Constraint::Vertical((0, 1).into(), (0, 2).into());
+        let mut memory = ShapeEditorMemory::default();
+        let mut shape = Shape::Noop;
+        memory.apply_boxed_action(Box::new(AddConstraint(first)), &mut shape);
+        memory.apply_boxed_action(Box::new(AddConstraint(second)), &mut shape);
+        memory.apply_boxed_action(Box::new(RemoveConstraint(first)), &mut shape);
+        assert_eq!(constraint_set(&memory), HashSet::from_iter([second]));
+        assert_eq!(
+            memory
+                .action_history()
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Add Constraint", "Add Constraint", "Remove Constraint"]
+        );
+
+        memory.undo(&mut shape);
+        assert_eq!(constraint_set(&memory), HashSet::from_iter([first, second]));
+        memory.undo(&mut shape);
+        assert_eq!(constraint_set(&memory), HashSet::from_iter([first]));
+        memory.undo(&mut shape);
+        assert!(constraint_set(&memory).is_empty());
+    }
+
+    #[test]
+    fn redundant_edits_undo_to_noop() {
+        let constraint = Constraint::Horizontal((0, 0).into(), (0, 1).into());
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(constraint);
+        let mut shape = Shape::Noop;
+        let mut stable_ids = StableIds::default();
+
+        Box::new(AddConstraint(constraint))
+            .apply(&mut shape, &mut constraints, &mut stable_ids)
+            .apply(&mut shape, &mut constraints, &mut stable_ids);
+        assert_eq!(constraints.constraints().count(), 1);
+
+        let other = Constraint::Vertical((0, 0).into(), (0, 1).into());
+        Box::new(RemoveConstraint(other))
+            .apply(&mut shape, &mut constraints, &mut stable_ids)
+            .apply(&mut shape, &mut constraints, &mut stable_ids);
+        assert_eq!(
+            constraints.constraints().copied().collect::<Vec<_>>(),
+            vec![constraint]
+        );
+    }
+
+    #[test]
+    fn remove_undo_redo_round_trip() {
+        let constraint = Constraint::Coincident((0, 0).into(), (1, 0).into());
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(constraint);
+        let mut shape = Shape::Noop;
+        let mut stable_ids = StableIds::default();
+
+        let undo = Box::new(RemoveConstraint(constraint)).apply(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+        );
+        assert_eq!(constraints.constraints().count(), 0);
+        let redo = undo.apply(&mut shape, &mut constraints, &mut stable_ids);
+        assert_eq!(
+            constraints.constraints().copied().collect::<Vec<_>>(),
+            vec![constraint]
+        );
+        redo.apply(&mut shape, &mut constraints, &mut stable_ids);
+        assert_eq!(constraints.constraints().count(), 0);
+    }
+}
@@ -1,9 +1,10 @@
-use crate::shape_editor::constraints::Constraints;
-use crate::shape_editor::shape_action::replace_shapes::ReplaceShapesVisitor;
-use crate::shape_editor::shape_action::{Noop, ShapeAction};
+use crate::shape_editor::constraints::{Constraint, Constraints};
+use crate::shape_editor::shape_action::edit_constraints::AddConstraint;
+use crate::shape_editor::shape_action::replace_shapes::ReplaceShapes;
+use crate::shape_editor::shape_action::{Combined, ShapeAction};
 use crate::shape_editor::shape_visitor::count_shapes::CountShapes;
-use crate::shape_editor::shape_visitor::indexed_shapes_visitor::IndexedShapesVisitorAdapter;
-use crate::shape_editor::shape_visitor::ShapeVisitor;
+use crate::shape_editor::stable_ids::StableIds;
+use egui::ahash::HashMap;
 use egui::epaint::{CubicBezierShape, QuadraticBezierShape};
 use egui::{Color32, Pos2, Shape, Stroke};
 use std::mem;
@@ -12,6 +13,7 @@ use std::mem;
 pub struct InsertShape {
     shape: Option<Shape>,
     replace: Option<usize>,
+    constraints: Vec<Constraint>,
 }
 
 impl InsertShape {
@@ -19,13 +21,20 @@ impl InsertShape {
         Self {
             shape: Some(shape),
             replace: None,
+            constraints: Vec::new(),
         }
     }
 
+    pub fn with_constraints(mut self, constraints: impl IntoIterator<Item = Constraint>) -> Self {
+        self.constraints.extend(constraints);
+        self
+    }
+
     pub fn replace_by_noop(index: usize) -> Self {
         Self {
             shape: None,
             replace: Some(index),
+            constraints: Vec::new(),
         }
     }
 
@@ -89,21 +98,15 @@ impl ShapeAction for InsertShape {
     fn apply(
         mut self: Box<Self>,
         shape: &mut Shape,
-        _constraints: &mut Constraints,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
         if let Some(replace) = self.replace {
-            let mut visitor =
-                ReplaceShapesVisitor::single(replace, self.shape.unwrap_or(Shape::Noop));
-            IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
-            visitor
-                .replaced_shapes
-                .remove(&replace)
-                .map_or(Box::new(Noop), |replaced| {
-                    Box::new(Self {
-                        shape: Some(replaced),
-                        replace: self.replace,
-                    })
-                })
+            Box::new(ReplaceShapes::new(HashMap::from_iter([(
+                replace,
+                self.shape.unwrap_or(Shape::Noop),
+            )])))
+            .apply(shape, constraints, stable_ids)
         } else {
             if !matches!(shape, Shape::Vec(_)) {
                 let original = mem::replace(shape, Shape::Noop);
@@ -113,7 +116,20 @@ impl ShapeAction for InsertShape {
                 vec.push(self.shape.take().unwrap_or(Shape::Noop));
             }
             let index = CountShapes::count(shape) - 1;
-            Box::new(InsertShape::replace_by_noop(index))
+            let mut revert_actions: Vec<Box<dyn ShapeAction>> = self
+                .constraints
+                .into_iter()
+                .map(|constraint| {
+                    Box::new(AddConstraint(constraint)).apply(shape, constraints, stable_ids)
+                })
+                .collect();
+            revert_actions.reverse();
+            if revert_actions.is_empty() {
+                Box::new(InsertShape::replace_by_noop(index))
+            } else {
+                revert_actions.push(Box::new(InsertShape::replace_by_noop(index)));
+                Box::new(Combined::new("Remove Shape".into(), revert_actions))
+            }
         }
     }
 
@@ -0,0 +1,246 @@
+use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
+use crate::shape_editor::shape_action::replace_shapes::ReplaceShapes;
+use crate::shape_editor::shape_action::{Combined, Noop, ShapeAction};
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
+use crate::shape_editor::shape_visitor::ShapePointIndex;
+use crate::shape_editor::stable_ids::StableIds;
+use crate::shape_editor::Selection;
+use egui::ahash::HashMap;
+use egui::epaint::PathShape;
+use egui::Shape;
+
+#[derive(Clone)]
+pub struct JoinPaths(pub ShapePointIndex, pub ShapePointIndex);
+
+impl JoinPaths {
+    pub fn is_path_end_point(path: &PathShape, point_index: usize) -> bool {
+        !path.closed && (point_index == 0 || point_index + 1 == path.points.len())
+    }
+}
+
+impl ShapeAction for JoinPaths {
+    fn apply(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let JoinPaths(first, second) = *self;
+        if first.shape_index == second.shape_index {
+            return Box::new(Noop);
+        }
+        let mut shapes = GetShapes::by_indexes(shape, [first.shape_index, second.shape_index]);
+        let (Some(Shape::Path(mut first_path)), Some(Shape::Path(second_path))) = (
+            shapes.remove(&first.shape_index),
+            shapes.remove(&second.shape_index),
+        ) else {
+            return Box::new(Noop);
+        };
+        if !Self::is_path_end_point(&first_path, first.point_index)
+            || !Self::is_path_end_point(&second_path, second.point_index)
+        {
+            return Box::new(Noop);
+        }
+
+        let first_count = first_path.points.len();
+        let second_count = second_path.points.len();
+        let reverse_first = first.point_index == 0;
+        let reverse_second = second.point_index != 0;
+        if reverse_first {
+            first_path.points.reverse();
+        }
+        let mut second_points = second_path.points;
+        if reverse_second {
+            second_points.reverse();
+        }
+        let skip_duplicate = first_path.points.last() == second_points.first();
+        let offset = first_count - usize::from(skip_duplicate);
+        first_path
+            .points
+            .extend(second_points.into_iter().skip(usize::from(skip_duplicate)));
+
+        let mut remap = RemapPointIndices::default();
+        for i in 0..first_count {
+            let point_index = if reverse_first {
+                first_count - 1 - i
+            } else {
+                i
+            };
+            remap.insert(
+                (first.shape_index, i).into(),
+                (first.shape_index, point_index).into(),
+            );
+        }
+        for i in 0..second_count {
+            let point_index = if reverse_second {
+                second_count - 1 - i
+            } else {
+                i
+            };
+            remap.insert(
+                (second.shape_index, i).into(),
+                (first.shape_index, point_index + offset).into(),
+            );
+        }
+        Box::new(Combined::new(
+            "Join Paths".into(),
+            vec![
+                Box::new(remap),
+                Box::new(ReplaceShapes::new(HashMap::from_iter([
+                    (first.shape_index, Shape::Path(first_path)),
+                    (second.shape_index, Shape::Noop),
+                ]))),
+            ],
+        ))
+        .apply_with_selection(shape, constraints, stable_ids, selection)
+    }
+
+    fn short_name(&self) -> String {
+        "Join Paths".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::constraints::Constraint;
+    use egui::ahash::HashSet;
+    use egui::{Color32, Pos2, Stroke};
+    use std::collections::BTreeSet;
+
+    fn path(points: &[(f32, f32)]) -> Shape {
+        Shape::Path(PathShape::line(
+            points.iter().map(|(x, y)| Pos2::new(*x, *y)).collect(),
+            Stroke::new(1.0, Color32::BLACK),
+        ))
+    }
+
+    fn undo_round_trip(first_points: &[(f32, f32)], first: ShapePointIndex) {
+        let original = Shape::Vec(vec![path(first_points), path(&[(2.0, 0.0), (3.0, 0.0)])]);
+        let first_constraint = Constraint::Horizontal(first, (0, 1).into());
+        let second_constraint = Constraint::Vertical((1, 0).into(), (1, 1).into());
+        let mut shape = original.clone();
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(first_constraint);
+        constraints.add_constraint(second_constraint);
+        let mut stable_ids = StableIds::default();
+        let first_id = stable_ids.point_id(first);
+        let second_id = stable_ids.point_id((1, 0).into());
+        let mut selection = Selection::default();
+        selection.select_control_point(first);
+
+        let undo = Box::new(JoinPaths(first, (1, 0).into())).apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+        let Shape::Vec(shapes) = &shape else {
+            panic!("expected vec shape");
+        };
+        assert!(matches!(&shapes[0], Shape::Path(path) if path.points.len() == 4));
+        assert!(stable_ids.point_index(first_id).is_some());
+        assert_eq!(stable_ids.point_index(second_id), None);
+        undo.apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+
+        assert_eq!(format!("{:?}", shape), format!("{:?}", original));
+        assert_eq!(
+            constraints.constraints().copied().collect::<HashSet<_>>(),
+            HashSet::from_iter([first_constraint, second_constraint])
+        );
+        assert_eq!(selection.control_points(), &BTreeSet::from([first]));
+        assert_eq!(stable_ids.point_index(first_id), Some(first));
+        assert_eq!(stable_ids.point_index(second_id), Some((1, 0).into()));
+    }
+
+    #[test]
+    fn join_undo_restores_joint_point() {
+        undo_round_trip(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)], (0, 2).into());
+    }
+
+    #[test]
+    fn join_reversed_undo_restores_joint_point() {
+        undo_round_trip(&[(2.0, 0.0), (1.0, 0.0), (0.0, 0.0)], (0, 0).into());
+    }
+
+    #[test]
+    fn join_undo_redo_removes_joined_shape() {
+        let original = Shape::Vec(vec![
+            path(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]),
+            path(&[(3.0, 0.0), (4.0, 0.0)]),
+        ]);
+        let mut shape = original.clone();
+        let mut constraints = Constraints::default();
+        let mut stable_ids = StableIds::default();
+        let first_shape_id = stable_ids.shape_id(0);
+        let second_shape_id = stable_ids.shape_id(1);
+        let second_point_id = stable_ids.point_id((1, 1).into());
+        let mut selection = Selection::default();
+        selection.select_control_point((0, 2).into());
+        selection.select_control_point((1, 0).into());
+
+        let undo = Box::new(JoinPaths((0, 2).into(), (1, 0).into())).apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+        let joined = format!("{:?}", shape);
+        let Shape::Vec(shapes) = &shape else {
+            panic!("expected vec shape");
+        };
+        assert!(matches!(&shapes[0], Shape::Path(path) if path.points.len() == 5));
+        assert!(matches!(&shapes[1], Shape::Noop));
+        assert_eq!(stable_ids.shape_index(first_shape_id), Some(0));
+        assert_eq!(stable_ids.shape_index(second_shape_id), None);
+        assert_eq!(stable_ids.point_index(second_point_id), Some((0, 4).into()));
+        assert_eq!(
+            selection.control_points(),
+            &BTreeSet::from([(0, 2).into(), (0, 3).into()])
+        );
+
+        let redo = undo.apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+        assert_eq!(format!("{:?}", shape), format!("{:?}", original));
+        assert_eq!(stable_ids.shape_index(second_shape_id), Some(1));
+        assert_eq!(stable_ids.point_index(second_point_id), Some((1, 1).into()));
+        assert_eq!(
+            selection.control_points(),
+            &BTreeSet::from([(0, 2).into(), (1, 0).into()])
+        );
+
+        redo.apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+        assert_eq!(format!("{:?}", shape), joined);
+        assert_eq!(stable_ids.shape_index(second_shape_id), None);
+        assert_eq!(stable_ids.point_index(second_point_id), Some((0, 4).into()));
+        assert_eq!(
+            selection.control_points(),
+            &BTreeSet::from([(0, 2).into(), (0, 3).into()])
+        );
+    }
+}
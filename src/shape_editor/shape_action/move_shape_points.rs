@@ -5,13 +5,14 @@ use crate::shape_editor::shape_visitor::indexed_shape_control_points_visitor::{
     IndexedShapeControlPointsVisitor, IndexedShapeControlPointsVisitorAdapter,
 };
 use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeType, ShapeVisitor};
-use egui::ahash::{HashMap, HashSet};
+use crate::shape_editor::stable_ids::StableIds;
+use egui::ahash::HashMap;
 use egui::emath::One;
 use egui::{Pos2, Shape, Vec2};
 use num_traits::Zero;
-use std::ops::{AddAssign, DerefMut, MulAssign, Neg};
+use std::ops::{AddAssign, MulAssign, Neg};
 
-#[derive(Clone)]
+#[derive(Clone, Default, Debug)]
 pub struct MoveShapePoints(HashMap<ShapePointIndex, Vec2>);
 
 impl IndexedShapeControlPointsVisitor for MoveShapePoints {
@@ -82,24 +83,62 @@ impl MoveShapePoints {
         )
     }
 
+    pub(crate) fn apply_and_invert(
+        mut self,
+        shape: &mut Shape,
+        constraints: &Constraints,
+    ) -> TranslateShapePoints {
+        self.apply_constraints(constraints, shape);
+        let moved = self.clone();
+        IndexedShapeControlPointsVisitorAdapter(&mut self).visit(shape);
+        let mut solved = Self::solve_constraints(constraints, shape, &moved);
+        let mut steps = vec![moved.invert()];
+        if !solved.0.is_empty() {
+            steps.insert(0, solved.invert());
+            IndexedShapeControlPointsVisitorAdapter(&mut solved).visit(shape);
+        }
+        TranslateShapePoints(steps)
+    }
+
+    fn solve_constraints(constraints: &Constraints, shape: &mut Shape, moved: &Self) -> Self {
+        let points = constraints.geometric_points();
+        if points.is_empty() {
+            return Self::default();
+        }
+        let mut positions_visitor = GetPointsPositions::new(points);
+        IndexedShapeControlPointsVisitorAdapter(&mut positions_visitor).visit(shape);
+        let initial = positions_visitor.into_not_found_and_positions().1;
+        let mut positions = initial.clone();
+        let fixed = moved.0.keys().copied().collect();
+        constraints.solve(&mut positions, &fixed);
+        Self(
+            positions
+                .into_iter()
+                .filter_map(|(index, pos)| {
+                    let translation = pos - initial[&index];
+                    (translation != Vec2::ZERO).then_some((index, translation))
+                })
+                .collect(),
+        )
+    }
+
     fn apply_constraints(&mut self, constraints: &Constraints, shape: &mut Shape) {
-        let mut connected_translations: HashMap<ShapePointIndex, Vec2> = HashMap::default();
-        for (from, transform) in &self.0 {
-            if let Some(mut connected_set) = constraints.translation_propagation.get(from).cloned()
-            {
-                while !connected_set.is_empty() {
-                    for to in std::mem::replace(&mut connected_set, HashSet::default()) {
-                        if !connected_translations.contains_key(&to) {
-                            connected_translations.insert(to, *transform);
-                            if let Some(set) = constraints.translation_propagation.get(&to) {
-                                connected_set.extend(set);
-                            }
-                        }
-                    }
-                }
+        let mut sources: Vec<(ShapePointIndex, Vec2)> = self
+            .0
+            .iter()
+            .map(|(index, translation)| (*index, *translation))
+            .collect();
+        sources.sort_unstable_by_key(|(index, _)| *index);
+        for (from, translation) in sources {
+            let Some(closure) = constraints.translation_closure.get(&from) else {
+                continue;
+            };
+            let mut closure: Vec<ShapePointIndex> = closure.iter().copied().collect();
+            closure.sort_unstable();
+            for to in closure {
+                self.0.entry(to).or_insert(translation);
             }
         }
-        self.0.extend(connected_translations);
         let mut positions_visitor = GetPointsPositions::new(
             self.0
                 .keys()
@@ -136,16 +175,194 @@ impl MoveShapePoints {
 
 impl ShapeAction for MoveShapePoints {
     fn apply(
-        mut self: Box<Self>,
+        self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        _stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
-        self.apply_constraints(constraints, shape);
-        IndexedShapeControlPointsVisitorAdapter(self.deref_mut()).visit(shape);
-        Box::new(self.invert())
+        Box::new(self.apply_and_invert(shape, constraints))
+    }
+
+    fn short_name(&self) -> String {
+        "Move".into()
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct TranslateShapePoints(Vec<MoveShapePoints>);
+
+impl TranslateShapePoints {
+    pub(crate) fn merge(&mut self, mut later: Self) {
+        later.0.append(&mut self.0);
+        self.0 = later.0;
+    }
+}
+
+impl ShapeAction for TranslateShapePoints {
+    fn apply(
+        self: Box<Self>,
+        shape: &mut Shape,
+        _constraints: &mut Constraints,
+        _stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        let mut inverted: Vec<MoveShapePoints> = self
+            .0
+            .into_iter()
+            .map(|mut step| {
+                let inverted = step.invert();
+                IndexedShapeControlPointsVisitorAdapter(&mut step).visit(shape);
+                inverted
+            })
+            .collect();
+        inverted.reverse();
+        Box::new(Self(inverted))
     }
 
     fn short_name(&self) -> String {
         "Move".into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::constraints::Constraint;
+    use egui::epaint::PathShape;
+    use egui::{Color32, Stroke};
+
+    fn path(points: &[(f32, f32)]) -> Shape {
+        Shape::Vec(vec![Shape::Path(PathShape::line(
+            points.iter().map(|(x, y)| Pos2::new(*x, *y)).collect(),
+            Stroke::new(1.0, Color32::BLACK),
+        ))])
+    }
+
+    fn points(shape: &Shape) -> Vec<Pos2> {
+        let Shape::Vec(shapes) = shape else {
+            panic!("expected vec shape");
+        };
+        let Shape::Path(path) = &shapes[0] else {
+            panic!("expected path");
+        };
+        path.points.clone()
+    }
+
+    fn move_point(
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        index: ShapePointIndex,
+        translation: Vec2,
+    ) -> Box<dyn ShapeAction> {
+        Box::new(MoveShapePoints::from_index_and_translation(
+            &[index],
+            &translation,
+        ))
+        .apply(shape, constraints, &mut StableIds::default())
+    }
+
+    #[test]
+    fn undo_ignores_position_range() {
+        let original = path(&[(2.0, 0.0), (3.0, 0.0)]);
+        let mut shape = original.clone();
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(Constraint::PointPositionRange(
+            (0, 0).into(),
+            (0.0..=1.0, ..).into(),
+        ));
+
+        let undo = move_point(
+            &mut shape,
+            &mut constraints,
+            (0, 0).into(),
+            Vec2::new(-0.5, 0.0),
+        );
+        assert_eq!(points(&shape)[0], Pos2::new(1.5, 0.0));
+        let redo = undo.apply(&mut shape, &mut constraints, &mut StableIds::default());
+        assert_eq!(format!("{:?}", shape), format!("{:?}", original));
+        redo.apply(&mut shape, &mut constraints, &mut StableIds::default());
+        assert_eq!(points(&shape)[0], Pos2::new(1.5, 0.0));
+    }
+
+    #[test]
+    fn undo_restores_linked_and_solved_points() {
+        let original = path(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        let mut shape = original.clone();
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(Constraint::LinkTranslationFromTo(
+            (0, 0).into(),
+            (0, 1).into(),
+        ));
+        constraints.add_constraint(Constraint::Horizontal((0, 1).into(), (0, 2).into()));
+
+        let undo = move_point(
+            &mut shape,
+            &mut constraints,
+            (0, 0).into(),
+            Vec2::new(0.0, 1.0),
+        );
+        assert_eq!(
+            points(&shape),
+            vec![
+                Pos2::new(0.0, 1.0),
+                Pos2::new(1.0, 1.0),
+                Pos2::new(2.0, 1.0)
+            ]
+        );
+        undo.apply(&mut shape, &mut constraints, &mut StableIds::default());
+        assert_eq!(format!("{:?}", shape), format!("{:?}", original));
+    }
+
+    #[test]
+    fn chained_links_scale_uniformly_to_downstream_range() {
+        let mut shape = path(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(Constraint::LinkTranslationFromTo(
+            (0, 0).into(),
+            (0, 1).into(),
+        ));
+        constraints.add_constraint(Constraint::LinkTranslationFromTo(
+            (0, 1).into(),
+            (0, 2).into(),
+        ));
+        constraints.add_constraint(Constraint::PointPositionRange(
+            (0, 2).into(),
+            (..=3.0, ..).into(),
+        ));
+
+        move_point(
+            &mut shape,
+            &mut constraints,
+            (0, 0).into(),
+            Vec2::new(4.0, 2.0),
+        );
+        assert_eq!(
+            points(&shape),
+            vec![
+                Pos2::new(1.0, 0.5),
+                Pos2::new(2.0, 0.5),
+                Pos2::new(3.0, 0.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_undo_reverts_every_step() {
+        let original = path(&[(2.0, 0.0), (3.0, 0.0)]);
+        let mut shape = original.clone();
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(Constraint::PointPositionRange(
+            (0, 0).into(),
+            (0.0..=1.0, ..).into(),
+        ));
+        let mut undo = TranslateShapePoints::default();
+        for translation in [Vec2::new(-0.5, 0.0), Vec2::new(0.0, 2.0)] {
+            undo.merge(
+                MoveShapePoints::from_index_and_translation(&[(0, 0).into()], &translation)
+                    .apply_and_invert(&mut shape, &constraints),
+            );
+        }
+        assert_eq!(points(&shape)[0], Pos2::new(1.5, 2.0));
+        Box::new(undo).apply(&mut shape, &mut constraints, &mut StableIds::default());
+        assert_eq!(format!("{:?}", shape), format!("{:?}", original));
+    }
+}
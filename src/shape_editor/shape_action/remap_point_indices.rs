@@ -0,0 +1,303 @@
+use crate::shape_editor::constraints::{Constraint, Constraints};
+use crate::shape_editor::shape_action::ShapeAction;
+use crate::shape_editor::shape_visitor::ShapePointIndex;
+use crate::shape_editor::stable_ids::{StableIdEntries, StableIds};
+use crate::shape_editor::Selection;
+use egui::Shape;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Clone, Default)]
+pub struct RemapPointIndices {
+    remap: BTreeMap<ShapePointIndex, ShapePointIndex>,
+    inverse_remap: BTreeMap<ShapePointIndex, ShapePointIndex>,
+    removed_points: BTreeSet<ShapePointIndex>,
+    removed_shapes: BTreeSet<usize>,
+    removed_constraints: Vec<Constraint>,
+    restored_constraints: Vec<Constraint>,
+    removed_stable_ids: StableIdEntries,
+    restored_stable_ids: StableIdEntries,
+}
+
+impl RemapPointIndices {
+    pub fn restore(constraints: Vec<Constraint>, stable_ids: StableIdEntries) -> Self {
+        Self {
+            restored_constraints: constraints,
+            restored_stable_ids: stable_ids,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_removed_points(
+        mut self,
+        points: impl IntoIterator<Item = ShapePointIndex>,
+    ) -> Self {
+        self.removed_points.extend(points);
+        self
+    }
+
+    pub fn with_removed_shapes(mut self, shapes: impl IntoIterator<Item = usize>) -> Self {
+        self.removed_shapes.extend(shapes);
+        self
+    }
+
+    pub fn insert(&mut self, from: ShapePointIndex, to: ShapePointIndex) {
+        self.inverse_remap.entry(to).or_insert(from);
+        if from != to {
+            self.remap.insert(from, to);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remap.is_empty()
+            && self.removed_points.is_empty()
+            && self.removed_shapes.is_empty()
+            && self.removed_constraints.is_empty()
+            && self.restored_constraints.is_empty()
+            && self.removed_stable_ids.is_empty()
+            && self.restored_stable_ids.is_empty()
+    }
+
+    pub fn remap(&self, index: &ShapePointIndex) -> Option<ShapePointIndex> {
+        if self.removed_shapes.contains(&index.shape_index) || self.removed_points.contains(index) {
+            None
+        } else {
+            Some(self.remap.get(index).copied().unwrap_or(*index))
+        }
+    }
+
+    fn is_merged(&self, index: &ShapePointIndex) -> bool {
+        self.remap
+            .get(index)
+            .is_some_and(|to| self.inverse_remap.get(to) != Some(index))
+    }
+
+    pub(crate) fn apply_to_constraints(
+        &self,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> (Vec<Constraint>, Vec<Constraint>, StableIdEntries) {
+        stable_ids.remove(&self.removed_stable_ids);
+        let merged: Vec<(Constraint, Constraint)> = constraints
+            .constraints()
+            .filter(|constraint| {
+                constraint
+                    .points()
+                    .iter()
+                    .any(|index| self.is_merged(index))
+            })
+            .filter_map(|constraint| {
+                Some((
+                    *constraint,
+                    constraint.remap_points(|index| self.remap(index))?,
+                ))
+            })
+            .collect();
+        let mut removed: Vec<Constraint> = self
+            .removed_constraints
+            .iter()
+            .filter(|constraint| constraints.remove_constraint(constraint))
+            .copied()
+            .collect();
+        if !self.remap.is_empty()
+            || !self.removed_points.is_empty()
+            || !self.removed_shapes.is_empty()
+        {
+            removed.extend(constraints.remap_points(|index| self.remap(index)));
+        }
+        let removed_stable_ids = stable_ids.remap(
+            |index| self.remap(index),
+            |index| self.is_merged(index),
+            |shape_index| self.removed_shapes.contains(&shape_index),
+        );
+        for constraint in &self.restored_constraints {
+            constraints.add_constraint(*constraint);
+        }
+        stable_ids.restore(&self.restored_stable_ids);
+        let (merged, merged_remapped): (Vec<Constraint>, Vec<Constraint>) =
+            merged.into_iter().unzip();
+        removed.extend(merged);
+        (removed, merged_remapped, removed_stable_ids)
+    }
+
+    pub(crate) fn apply_to_selection(&self, selection: &mut Selection) {
+        selection.remap_control_points(|index| self.remap(index));
+        selection.remap_constraint(|index| self.remap(index));
+    }
+
+    fn inverse(
+        &self,
+        removed_constraints: Vec<Constraint>,
+        merged_constraints: Vec<Constraint>,
+        removed_stable_ids: StableIdEntries,
+    ) -> Self {
+        Self {
+            remap: self
+                .inverse_remap
+                .iter()
+                .filter(|(to, from)| to != from)
+                .map(|(to, from)| (*to, *from))
+                .collect(),
+            inverse_remap: self.remap.clone(),
+            removed_points: Default::default(),
+            removed_shapes: Default::default(),
+            removed_constraints: self
+                .restored_constraints
+                .iter()
+                .chain(&merged_constraints)
+                .copied()
+                .collect(),
+            restored_constraints: removed_constraints,
+            removed_stable_ids: self.restored_stable_ids.clone(),
+            restored_stable_ids: removed_stable_ids,
+        }
+    }
+}
+
+impl ShapeAction for RemapPointIndices {
+    fn apply(
+        self: Box<Self>,
+        _shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        let (removed_constraints, merged_constraints, removed_stable_ids) =
+            self.apply_to_constraints(constraints, stable_ids);
+        Box::new(self.inverse(removed_constraints, merged_constraints, removed_stable_ids))
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_to_selection(selection);
+        self.apply(shape, constraints, stable_ids)
+    }
+
+    fn short_name(&self) -> String {
+        "Remap Points".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::ahash::HashSet;
+
+    fn constraints(constraints: &[Constraint]) -> Constraints {
+        let mut all = Constraints::default();
+        for constraint in constraints {
+            all.add_constraint(*constraint);
+        }
+        all
+    }
+
+    fn constraint_set(constraints: &Constraints) -> HashSet<Constraint> {
+        constraints.constraints().copied().collect()
+    }
+
+    fn apply(
+        remap: RemapPointIndices,
+        constraints: &mut Constraints,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        Box::new(remap).apply_with_selection(
+            &mut Shape::Noop,
+            constraints,
+            &mut StableIds::default(),
+            selection,
+        )
+    }
+
+    fn apply_undo(
+        undo: Box<dyn ShapeAction>,
+        constraints: &mut Constraints,
+        selection: &mut Selection,
+    ) {
+        undo.apply_with_selection(
+            &mut Shape::Noop,
+            constraints,
+            &mut StableIds::default(),
+            selection,
+        );
+    }
+
+    #[test]
+    fn shifts_constraints_and_selection_after_removed_point() {
+        let kept = Constraint::Horizontal((0, 1).into(), (0, 2).into());
+        let dropped = Constraint::Vertical((0, 0).into(), (0, 1).into());
+        let other_shape = Constraint::Horizontal((1, 0).into(), (1, 1).into());
+        let original = constraints(&[kept, dropped, other_shape]);
+        let mut constraints = original.clone();
+        let mut selection = Selection::default();
+        selection.select_control_point((0, 0).into());
+        selection.select_control_point((0, 2).into());
+        selection.select_constraint(kept);
+        let mut remap = RemapPointIndices::default().with_removed_points([(0, 0).into()]);
+        remap.insert((0, 1).into(), (0, 0).into());
+        remap.insert((0, 2).into(), (0, 1).into());
+
+        let undo = apply(remap, &mut constraints, &mut selection);
+        let shifted = Constraint::Horizontal((0, 0).into(), (0, 1).into());
+        assert_eq!(
+            constraint_set(&constraints),
+            HashSet::from_iter([shifted, other_shape])
+        );
+        assert_eq!(selection.control_points(), &BTreeSet::from([(0, 1).into()]));
+        assert_eq!(selection.selected_constraint(), Some(&shifted));
+
+        apply_undo(undo, &mut constraints, &mut selection);
+        assert_eq!(constraint_set(&constraints), constraint_set(&original));
+        assert_eq!(selection.control_points(), &BTreeSet::from([(0, 2).into()]));
+        assert_eq!(selection.selected_constraint(), Some(&kept));
+    }
+
+    #[test]
+    fn removed_shape_drops_its_constraints_and_selection() {
+        let removed = Constraint::Coincident((0, 0).into(), (1, 0).into());
+        let kept = Constraint::Horizontal((0, 0).into(), (0, 1).into());
+        let mut constraints = constraints(&[removed, kept]);
+        let mut selection = Selection::default();
+        selection.select_control_point((1, 0).into());
+        selection.select_control_point((0, 1).into());
+        selection.select_constraint(removed);
+
+        let undo = apply(
+            RemapPointIndices::default().with_removed_shapes([1]),
+            &mut constraints,
+            &mut selection,
+        );
+        assert_eq!(constraint_set(&constraints), HashSet::from_iter([kept]));
+        assert_eq!(selection.control_points(), &BTreeSet::from([(0, 1).into()]));
+        assert_eq!(selection.selected_constraint(), None);
+
+        apply_undo(undo, &mut constraints, &mut selection);
+        assert_eq!(
+            constraint_set(&constraints),
+            HashSet::from_iter([removed, kept])
+        );
+    }
+
+    #[test]
+    fn merged_points_keep_constraints_and_undo_restores_them() {
+        let first = Constraint::Horizontal((0, 0).into(), (0, 2).into());
+        let second = Constraint::Vertical((1, 0).into(), (1, 1).into());
+        let original = constraints(&[first, second]);
+        let mut constraints = original.clone();
+        let mut remap = RemapPointIndices::default();
+        remap.insert((0, 2).into(), (0, 2).into());
+        remap.insert((1, 0).into(), (0, 2).into());
+        remap.insert((1, 1).into(), (0, 3).into());
+
+        let undo = apply(remap, &mut constraints, &mut Selection::default());
+        assert_eq!(
+            constraint_set(&constraints),
+            HashSet::from_iter([first, Constraint::Vertical((0, 2).into(), (0, 3).into())])
+        );
+
+        apply_undo(undo, &mut constraints, &mut Selection::default());
+        assert_eq!(constraint_set(&constraints), constraint_set(&original));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::shape_editor::constraints::Constraints;
 use crate::shape_editor::shape_action::add_shape_points::AddShapePoints;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
 use crate::shape_editor::shape_action::replace_shapes::{ReplaceShapes, ReplaceShapesVisitor};
 use crate::shape_editor::shape_action::{
     Combined, RestoreSelectionActionWrapper, ShapeAction, ShapePoint,
@@ -8,6 +9,7 @@ use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
     IndexedShapesVisitor, IndexedShapesVisitorAdapter,
 };
 use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeVisitor};
+use crate::shape_editor::stable_ids::StableIds;
 use crate::shape_editor::utils::b_tree_map_grouped_by;
 use crate::shape_editor::Selection;
 use egui::Shape;
@@ -22,36 +24,45 @@ impl ShapeAction for RemoveShapePoints {
         self: Box<Self>,
         shape: &mut Shape,
         constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
-        self.apply_with_selection(shape, constraints, &mut Selection::default())
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
     }
 
     fn apply_with_selection(
         self: Box<Self>,
         shape: &mut Shape,
-        _constraints: &mut Constraints,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
         selection: &mut Selection,
     ) -> Box<dyn ShapeAction> {
         let owned = *self;
         let mut points_visitor = RemoveShapePointsVisitor::from_iter(owned.0.iter());
-        let selection_state = std::mem::replace(selection, Selection::default());
+        let selection_state = std::mem::take(selection);
         IndexedShapesVisitorAdapter(&mut points_visitor).visit(shape);
-        let revert_action: Box<dyn ShapeAction> = if points_visitor.shapes_to_remove.is_empty() {
-            Box::new(AddShapePoints(points_visitor.removed_points))
-        } else {
+        let mut revert_actions: Vec<Box<dyn ShapeAction>> = Vec::new();
+        if !points_visitor.shapes_to_remove.is_empty() {
             let mut shapes_visitor =
                 ReplaceShapesVisitor::replace_by_noop(points_visitor.shapes_to_remove.iter());
             IndexedShapesVisitorAdapter(&mut shapes_visitor).visit(shape);
+            revert_actions.push(Box::new(ReplaceShapes::new(shapes_visitor.replaced_shapes)));
+        }
+        revert_actions.push(Box::new(AddShapePoints(points_visitor.removed_points)));
+        let (removed_constraints, _, removed_stable_ids) = points_visitor
+            .remap
+            .with_removed_shapes(points_visitor.shapes_to_remove)
+            .apply_to_constraints(constraints, stable_ids);
+        if !removed_constraints.is_empty() || !removed_stable_ids.is_empty() {
+            revert_actions.push(Box::new(RemapPointIndices::restore(
+                removed_constraints,
+                removed_stable_ids,
+            )));
+        }
+        Box::new(RestoreSelectionActionWrapper::new(
             Box::new(Combined::new(
                 "Add Shapes and Points".into(),
-                vec![
-                    Box::new(ReplaceShapes::new(shapes_visitor.replaced_shapes)),
-                    Box::new(AddShapePoints(points_visitor.removed_points)),
-                ],
-            ))
-        };
-        Box::new(RestoreSelectionActionWrapper::new(
-            revert_action,
+                revert_actions,
+            )),
             selection_state,
         ))
     }
@@ -66,6 +77,7 @@ struct RemoveShapePointsVisitor {
 
     shapes_to_remove: BTreeSet<usize>,
     removed_points: BTreeMap<usize, BTreeMap<usize, ShapePoint>>,
+    remap: RemapPointIndices,
 }
 
 impl RemoveShapePointsVisitor {
@@ -75,7 +87,32 @@ impl RemoveShapePointsVisitor {
             points_to_remove,
             shapes_to_remove: Default::default(),
             removed_points: Default::default(),
+            remap: Default::default(),
+        }
+    }
+
+    fn remap_shape_points(
+        &mut self,
+        shape_index: usize,
+        points_count: usize,
+        removed: &BTreeSet<usize>,
+    ) {
+        let mut removed_before = 0;
+        for i in 0..points_count {
+            if removed.contains(&i) {
+                removed_before += 1;
+            } else {
+                self.remap.insert(
+                    (shape_index, i).into(),
+                    (shape_index, i - removed_before).into(),
+                );
+            }
         }
+        self.remap = std::mem::take(&mut self.remap).with_removed_points(
+            removed
+                .iter()
+                .map(|point_index| (shape_index, *point_index).into()),
+        );
     }
 }
 
@@ -87,24 +124,28 @@ impl IndexedShapesVisitor for RemoveShapePointsVisitor {
                     if shape_points_to_remove.len() > path.points.len() - 2 {
                         self.shapes_to_remove.insert(shape_index);
                     } else {
-                        for i in shape_points_to_remove.into_iter().sorted().rev() {
+                        let points_count = path.points.len();
+                        for &i in shape_points_to_remove.iter().sorted().rev() {
                             self.removed_points
                                 .entry(shape_index)
                                 .or_default()
                                 .insert(i, ShapePoint::Pos(path.points.remove(i)));
                         }
+                        self.remap_shape_points(shape_index, points_count, &shape_points_to_remove);
                     }
                 }
                 Shape::Mesh(mesh) => {
                     if shape_points_to_remove.len() > mesh.vertices.len() - 3 {
                         self.shapes_to_remove.insert(shape_index);
                     } else {
-                        for i in shape_points_to_remove.into_iter().sorted().rev() {
+                        let points_count = mesh.vertices.len();
+                        for &i in shape_points_to_remove.iter().sorted().rev() {
                             self.removed_points.entry(shape_index).or_default().insert(
                                 i,
                                 ShapePoint::Vertex(mesh.vertices.remove(i), mesh.indices.remove(i)),
                             );
                         }
+                        self.remap_shape_points(shape_index, points_count, &shape_points_to_remove);
                     }
                 }
                 _ => {
@@ -1,11 +1,15 @@
 use crate::shape_editor::constraints::Constraints;
-use crate::shape_editor::shape_action::ShapeAction;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
+use crate::shape_editor::shape_action::{Combined, RestoreSelectionActionWrapper, ShapeAction};
 use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
     IndexedShapesVisitor, IndexedShapesVisitorAdapter,
 };
 use crate::shape_editor::shape_visitor::ShapeVisitor;
+use crate::shape_editor::stable_ids::StableIds;
+use crate::shape_editor::Selection;
 use egui::ahash::HashMap;
 use egui::Shape;
+use std::collections::BTreeSet;
 use std::mem;
 
 #[derive(Clone)]
@@ -17,17 +21,53 @@ impl ReplaceShapes {
     pub fn new(shapes_to_replace: HashMap<usize, Shape>) -> Self {
         Self { shapes_to_replace }
     }
+
+    fn replace(
+        self,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let mut visitor = ReplaceShapesVisitor::new(self.shapes_to_replace);
+        IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
+        let revert_action = Box::new(Self::new(visitor.replaced_shapes));
+        let removed = RemapPointIndices::default().with_removed_shapes(visitor.removed_shapes);
+        if removed.is_empty() {
+            revert_action
+        } else {
+            removed.apply_to_selection(selection);
+            let restore_action = Box::new(removed).apply(shape, constraints, stable_ids);
+            Box::new(Combined::new(
+                "Restore Shapes".into(),
+                vec![revert_action, restore_action],
+            ))
+        }
+    }
 }
 
 impl ShapeAction for ReplaceShapes {
     fn apply(
         self: Box<Self>,
         shape: &mut Shape,
-        _constraints: &mut Constraints,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
-        let mut visitor = ReplaceShapesVisitor::new(self.shapes_to_replace);
-        IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
-        Box::new(Self::new(visitor.replaced_shapes))
+        self.replace(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let selection_state = selection.clone();
+        Box::new(RestoreSelectionActionWrapper::new(
+            self.replace(shape, constraints, stable_ids, selection),
+            selection_state,
+        ))
     }
 
     fn short_name(&self) -> String {
@@ -38,6 +78,7 @@ impl ShapeAction for ReplaceShapes {
 pub struct ReplaceShapesVisitor {
     shapes_to_replace: HashMap<usize, Shape>,
     pub(crate) replaced_shapes: HashMap<usize, Shape>,
+    pub(crate) removed_shapes: BTreeSet<usize>,
 }
 
 impl ReplaceShapesVisitor {
@@ -45,21 +86,12 @@ impl ReplaceShapesVisitor {
         Self {
             shapes_to_replace,
             replaced_shapes: Default::default(),
-        }
-    }
-
-    pub(crate) fn single(index: usize, shape: Shape) -> Self {
-        Self {
-            shapes_to_replace: HashMap::from_iter([(index, shape)]),
-            replaced_shapes: Default::default(),
+            removed_shapes: Default::default(),
         }
     }
 
     pub(crate) fn replace_by_noop<'a>(values: impl Iterator<Item = &'a usize>) -> Self {
-        Self {
-            shapes_to_replace: values.map(|index| (*index, Shape::Noop)).collect(),
-            replaced_shapes: Default::default(),
-        }
+        Self::new(values.map(|index| (*index, Shape::Noop)).collect())
     }
 }
 
@@ -67,6 +99,9 @@ impl IndexedShapesVisitor for ReplaceShapesVisitor {
     fn indexed_single_shape(&mut self, index: usize, shape: &mut Shape) -> Option<()> {
         if let Some(shape_replacement) = self.shapes_to_replace.remove(&index) {
             let replaced = mem::replace(shape, shape_replacement);
+            if matches!(shape, Shape::Noop) && !matches!(replaced, Shape::Noop) {
+                self.removed_shapes.insert(index);
+            }
             self.replaced_shapes.insert(index, replaced);
         }
         self.shapes_to_replace.is_empty().then_some(())
@@ -0,0 +1,123 @@
+use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
+use crate::shape_editor::shape_action::replace_shapes::ReplaceShapes;
+use crate::shape_editor::shape_action::{Combined, ShapeAction};
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
+use crate::shape_editor::stable_ids::StableIds;
+use crate::shape_editor::Selection;
+use egui::Shape;
+use std::collections::BTreeSet;
+
+#[derive(Clone)]
+pub struct ReversePath(pub BTreeSet<usize>);
+
+impl ShapeAction for ReversePath {
+    fn apply(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let mut remap = RemapPointIndices::default();
+        let reversed = GetShapes::by_indexes(shape, self.0.iter().copied())
+            .into_iter()
+            .filter_map(|(index, shape)| {
+                if let Shape::Path(mut path) = shape {
+                    let points_count = path.points.len();
+                    for i in 0..points_count {
+                        remap.insert((index, i).into(), (index, points_count - 1 - i).into());
+                    }
+                    path.points.reverse();
+                    Some((index, Shape::Path(path)))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        Box::new(Combined::new(
+            self.short_name(),
+            vec![Box::new(ReplaceShapes::new(reversed)), Box::new(remap)],
+        ))
+        .apply_with_selection(shape, constraints, stable_ids, selection)
+    }
+
+    fn short_name(&self) -> String {
+        "Reverse Path".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape_editor::constraints::Constraint;
+    use crate::shape_editor::shape_visitor::ShapePointIndex;
+    use egui::epaint::PathShape;
+    use egui::{Color32, Pos2, Stroke};
+
+    fn closed_path() -> Shape {
+        Shape::Vec(vec![Shape::Path(PathShape::closed_line(
+            vec![
+                Pos2::new(0.0, 0.0),
+                Pos2::new(1.0, 0.0),
+                Pos2::new(1.0, 1.0),
+                Pos2::new(0.0, 1.0),
+            ],
+            Stroke::new(1.0, Color32::BLACK),
+        ))])
+    }
+
+    #[test]
+    fn reverse_closed_path() {
+        let original = closed_path();
+        let mut shape = original.clone();
+        let mut constraints = Constraints::default();
+        constraints.add_constraint(Constraint::Horizontal((0, 0).into(), (0, 1).into()));
+        let mut stable_ids = StableIds::default();
+        let point_id = stable_ids.point_id((0, 1).into());
+        let mut selection = Selection::default();
+        selection.select_control_point((0, 1).into());
+
+        let undo = Box::new(ReversePath(BTreeSet::from([0]))).apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+        let Shape::Vec(shapes) = &shape else {
+            panic!("expected vec shape");
+        };
+        let Shape::Path(path) = &shapes[0] else {
+            panic!("expected path");
+        };
+        assert!(path.closed);
+        assert_eq!(path.points[0], Pos2::new(0.0, 1.0));
+        assert_eq!(path.points[2], Pos2::new(1.0, 0.0));
+        let reversed: ShapePointIndex = (0, 2).into();
+        assert_eq!(selection.control_points(), &BTreeSet::from([reversed]));
+        assert_eq!(stable_ids.point_index(point_id), Some(reversed));
+        assert_eq!(
+            constraints.constraints().copied().collect::<Vec<_>>(),
+            vec![Constraint::Horizontal((0, 3).into(), reversed)]
+        );
+
+        undo.apply_with_selection(
+            &mut shape,
+            &mut constraints,
+            &mut stable_ids,
+            &mut selection,
+        );
+        assert_eq!(format!("{:?}", shape), format!("{:?}", original));
+        assert_eq!(selection.control_points(), &BTreeSet::from([(0, 1).into()]));
+        assert_eq!(stable_ids.point_index(point_id), Some((0, 1).into()));
+    }
+}
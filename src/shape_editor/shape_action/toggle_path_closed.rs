@@ -0,0 +1,121 @@
+use crate::shape_editor::constraints::Constraints;
+use crate::shape_editor::shape_action::remap_point_indices::RemapPointIndices;
+use crate::shape_editor::shape_action::replace_shapes::ReplaceShapes;
+use crate::shape_editor::shape_action::{Combined, Noop, ShapeAction};
+use crate::shape_editor::shape_visitor::get_shapes::GetShapes;
+use crate::shape_editor::shape_visitor::ShapePointIndex;
+use crate::shape_editor::stable_ids::StableIds;
+use crate::shape_editor::Selection;
+use egui::ahash::HashMap;
+use egui::Shape;
+
+#[derive(Clone)]
+pub struct ClosePath(pub usize);
+
+#[derive(Clone)]
+pub struct OpenPath(pub ShapePointIndex);
+
+impl ShapeAction for ClosePath {
+    fn apply(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let shape_index = self.0;
+        let Some(Shape::Path(mut path)) = GetShapes::single(shape, shape_index) else {
+            return Box::new(Noop);
+        };
+        if path.closed || path.points.len() < 3 {
+            return Box::new(Noop);
+        }
+        let last_index = path.points.len() - 1;
+        let mut remap = RemapPointIndices::default();
+        if path.points.len() > 3 && path.points[0] == path.points[last_index] {
+            path.points.pop();
+            remap = remap.with_removed_points([(shape_index, last_index).into()]);
+        }
+        path.closed = true;
+        Box::new(Combined::new(
+            self.short_name(),
+            vec![
+                Box::new(ReplaceShapes::new(HashMap::from_iter([(
+                    shape_index,
+                    Shape::Path(path),
+                )]))),
+                Box::new(remap),
+            ],
+        ))
+        .apply_with_selection(shape, constraints, stable_ids, selection)
+    }
+
+    fn short_name(&self) -> String {
+        "Close Path".into()
+    }
+}
+
+impl ShapeAction for OpenPath {
+    fn apply(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+    ) -> Box<dyn ShapeAction> {
+        self.apply_with_selection(shape, constraints, stable_ids, &mut Selection::default())
+    }
+
+    fn apply_with_selection(
+        self: Box<Self>,
+        shape: &mut Shape,
+        constraints: &mut Constraints,
+        stable_ids: &mut StableIds,
+        selection: &mut Selection,
+    ) -> Box<dyn ShapeAction> {
+        let ShapePointIndex {
+            shape_index,
+            point_index,
+        } = self.0;
+        let Some(Shape::Path(mut path)) = GetShapes::single(shape, shape_index) else {
+            return Box::new(Noop);
+        };
+        let points_count = path.points.len();
+        if !path.closed || point_index >= points_count {
+            return Box::new(Noop);
+        }
+        path.points.rotate_left(point_index);
+        path.points.push(path.points[0]);
+        path.closed = false;
+        let mut remap = RemapPointIndices::default();
+        for i in 0..points_count {
+            remap.insert(
+                (shape_index, i).into(),
+                (shape_index, (i + points_count - point_index) % points_count).into(),
+            );
+        }
+        Box::new(Combined::new(
+            self.short_name(),
+            vec![
+                Box::new(ReplaceShapes::new(HashMap::from_iter([(
+                    shape_index,
+                    Shape::Path(path),
+                )]))),
+                Box::new(remap),
+            ],
+        ))
+        .apply_with_selection(shape, constraints, stable_ids, selection)
+    }
+
+    fn short_name(&self) -> String {
+        "Open Path".into()
+    }
+}
@@ -4,6 +4,7 @@ use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
     IndexedShapesVisitor, IndexedShapesVisitorAdapter,
 };
 use crate::shape_editor::shape_visitor::ShapeVisitor;
+use crate::shape_editor::stable_ids::StableIds;
 use derivative::Derivative;
 use egui::ahash::HashSet;
 use egui::epaint::{
@@ -52,7 +53,7 @@ impl ShapesParams {
 
     pub fn common(&self) -> BTreeMap<ParamType, Option<ParamValue>> {
         let mut params: BTreeMap<ParamType, HashSet<ParamValue>> = BTreeMap::default();
-        for (_, param) in &self.0 {
+        for param in self.0.values() {
             for (ty, val) in param {
                 if let Some(values) = params.get_mut(ty) {
                     if values.len() < 2 {
@@ -427,18 +428,7 @@ impl IndexedShapesVisitor for ApplyShapeParamsVisitor {
     }
 
     fn indexed_text(&mut self, index: usize, _text: &mut TextShape) -> Option<()> {
-        if let Some(params) = self.shape_params.remove(&index) {
-            let changed = BTreeMap::default();
-            for mut param in params {
-                match &mut param {
-                    _ => continue,
-                }
-                // changed.insert(param.0, param.1);
-            }
-            if !changed.is_empty() {
-                self.changed_params.insert(index, changed);
-            }
-        }
+        self.shape_params.remove(&index);
         self.shape_params.is_empty().then_some(())
     }
 
@@ -542,6 +532,7 @@ impl ShapeAction for ApplyShapeParams {
         self: Box<Self>,
         shape: &mut Shape,
         _constraints: &mut Constraints,
+        _stable_ids: &mut StableIds,
     ) -> Box<dyn ShapeAction> {
         let mut visitor = ApplyShapeParamsVisitor {
             shape_params: (*self).0,
@@ -6,12 +6,14 @@ use egui::epaint::{
 
 use std::ops::{AddAssign, SubAssign};
 
+pub(crate) mod closest_path_segment;
 pub(crate) mod count_shapes;
 pub mod get_points_positions;
-pub(crate) mod get_shape_type_by_point_index;
+pub(crate) mod get_shapes;
 pub(crate) mod indexed_shape_control_points_visitor;
 pub(crate) mod indexed_shapes_visitor;
 pub(crate) mod last_shape_point_index;
+pub(crate) mod shape_geometry;
 
 pub trait ShapeVisitor<R = (), I: Default = usize> {
     fn line_segment(
@@ -100,10 +102,7 @@ pub enum ShapeType {
 impl ShapeType {
     pub const MAX_RADIUS_POINTS: u8 = 2;
     pub fn is_center_and_radius_topography(&self) -> bool {
-        match self {
-            ShapeType::Circle | ShapeType::Ellipse => true,
-            _ => false,
-        }
+        matches!(self, ShapeType::Circle | ShapeType::Ellipse)
     }
 }
 
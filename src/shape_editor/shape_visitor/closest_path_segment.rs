@@ -0,0 +1,58 @@
+use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
+    IndexedShapesVisitor, IndexedShapesVisitorAdapter,
+};
+use crate::shape_editor::shape_visitor::{ShapePointIndex, ShapeVisitor};
+use crate::shape_editor::utils;
+use egui::epaint::PathShape;
+use egui::{Pos2, Shape};
+
+pub struct ClosestPathSegment {
+    pos: Pos2,
+    max_distance: f32,
+    closest: Option<(ShapePointIndex, Pos2, f32)>,
+}
+
+impl ClosestPathSegment {
+    pub fn find(
+        shape: &mut Shape,
+        pos: Pos2,
+        max_distance: f32,
+    ) -> Option<(ShapePointIndex, Pos2)> {
+        let mut visitor = Self {
+            pos,
+            max_distance,
+            closest: None,
+        };
+        IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
+        visitor
+            .closest
+            .map(|(segment_start, point, _)| (segment_start, point))
+    }
+}
+
+impl IndexedShapesVisitor for ClosestPathSegment {
+    fn indexed_path(&mut self, index: usize, path: &mut PathShape) -> Option<()> {
+        let points_count = path.points.len();
+        let segments_count = if path.closed {
+            points_count
+        } else {
+            points_count.saturating_sub(1)
+        };
+        for i in 0..segments_count {
+            let point = utils::closest_point_on_segment(
+                path.points[i],
+                path.points[(i + 1) % points_count],
+                self.pos,
+            );
+            let distance = point.distance(self.pos);
+            if distance <= self.max_distance
+                && self
+                    .closest
+                    .map_or(true, |(_, _, closest_distance)| distance < closest_distance)
+            {
+                self.closest = Some(((index, i).into(), point, distance));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,40 @@
+use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
+    IndexedShapesVisitor, IndexedShapesVisitorAdapter,
+};
+use crate::shape_editor::shape_visitor::ShapeVisitor;
+use egui::ahash::{HashMap, HashSet};
+use egui::Shape;
+
+pub struct GetShapes {
+    indexes: HashSet<usize>,
+    shapes: HashMap<usize, Shape>,
+}
+
+impl GetShapes {
+    pub fn by_indexes(
+        shape: &mut Shape,
+        indexes: impl IntoIterator<Item = usize>,
+    ) -> HashMap<usize, Shape> {
+        let mut visitor = Self {
+            indexes: indexes.into_iter().collect(),
+            shapes: Default::default(),
+        };
+        if !visitor.indexes.is_empty() {
+            IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
+        }
+        visitor.shapes
+    }
+
+    pub fn single(shape: &mut Shape, index: usize) -> Option<Shape> {
+        Self::by_indexes(shape, [index]).remove(&index)
+    }
+}
+
+impl IndexedShapesVisitor for GetShapes {
+    fn indexed_single_shape(&mut self, index: usize, shape: &mut Shape) -> Option<()> {
+        if self.indexes.remove(&index) {
+            self.shapes.insert(index, shape.clone());
+        }
+        self.indexes.is_empty().then_some(())
+    }
+}
@@ -0,0 +1,223 @@
+use crate::shape_editor::index::{not_nan_f32, FloatIndex};
+use crate::shape_editor::shape_visitor::indexed_shapes_visitor::{
+    IndexedShapesVisitor, IndexedShapesVisitorAdapter,
+};
+use crate::shape_editor::shape_visitor::ShapeVisitor;
+use egui::ahash::{HashMap, HashSet};
+use egui::epaint::{
+    CircleShape, CubicBezierShape, EllipseShape, PathShape, QuadraticBezierShape, RectShape,
+};
+use egui::{Pos2, Rect, Shape, Stroke, Vec2};
+use std::f32::consts::TAU;
+
+const ELLIPSE_SEGMENTS: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyPointType {
+    Midpoint,
+    Center,
+    Quadrant,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GeometryEdge {
+    pub shape_index: usize,
+    pub start: Pos2,
+    pub end: Pos2,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct ShapeGeometry {
+    pub edges: Vec<GeometryEdge>,
+    pub key_points: Vec<(Pos2, KeyPointType)>,
+    pub bounds: HashMap<usize, Rect>,
+    edges_index: FloatIndex<f32, usize>,
+    max_edge_width: f32,
+    key_points_index: FloatIndex<f32, usize>,
+    ignored_shapes: HashSet<usize>,
+}
+
+impl ShapeGeometry {
+    pub fn collect(shape: &mut Shape, ignored_shapes: HashSet<usize>) -> Self {
+        let mut visitor = Self {
+            ignored_shapes,
+            ..Default::default()
+        };
+        IndexedShapesVisitorAdapter(&mut visitor).visit(shape);
+        visitor.build_index();
+        visitor
+    }
+
+    pub fn edges_in_distance(
+        &self,
+        pos: Pos2,
+        max_distance: f32,
+    ) -> impl Iterator<Item = &GeometryEdge> {
+        let range = not_nan_f32(pos.x - max_distance - self.max_edge_width)
+            ..=not_nan_f32(pos.x + max_distance);
+        self.edges_index
+            .find_in_range(range)
+            .flat_map(|(_, indexes)| indexes)
+            .map(|index| &self.edges[*index])
+            .filter(move |edge| {
+                Rect::from_two_pos(edge.start, edge.end)
+                    .expand(max_distance)
+                    .contains(pos)
+            })
+    }
+
+    pub fn key_points_in_distance(
+        &self,
+        pos: Pos2,
+        max_distance: f32,
+    ) -> impl Iterator<Item = &(Pos2, KeyPointType)> {
+        self.key_points_index
+            .find_in_distance(not_nan_f32(pos.x), not_nan_f32(max_distance))
+            .flat_map(|(_, indexes)| indexes)
+            .map(|index| &self.key_points[*index])
+            .filter(move |(point, _)| point.distance(pos) <= max_distance)
+    }
+
+    fn build_index(&mut self) {
+        for (index, edge) in self.edges.iter().enumerate() {
+            self.edges_index
+                .insert(not_nan_f32(edge.start.x.min(edge.end.x)), index);
+            self.max_edge_width = self.max_edge_width.max((edge.end.x - edge.start.x).abs());
+        }
+        for (index, (point, _)) in self.key_points.iter().enumerate() {
+            self.key_points_index.insert(not_nan_f32(point.x), index);
+        }
+    }
+
+    fn add_segment(&mut self, shape_index: usize, start: Pos2, end: Pos2) {
+        self.add_polyline(shape_index, &[start, end]);
+        self.key_points
+            .push((start.lerp(end, 0.5), KeyPointType::Midpoint));
+    }
+
+    fn add_bounds(&mut self, shape_index: usize, bounds: Rect) {
+        let shape_bounds = self.bounds.entry(shape_index).or_insert(Rect::NOTHING);
+        *shape_bounds = shape_bounds.union(bounds);
+    }
+
+    fn add_polyline(&mut self, shape_index: usize, points: &[Pos2]) {
+        self.edges
+            .extend(points.windows(2).map(|window| GeometryEdge {
+                shape_index,
+                start: window[0],
+                end: window[1],
+            }));
+    }
+
+    fn add_ellipse(&mut self, shape_index: usize, center: Pos2, radius: Vec2) {
+        let points: Vec<Pos2> = (0..=ELLIPSE_SEGMENTS)
+            .map(|i| {
+                let angle = TAU * i as f32 / ELLIPSE_SEGMENTS as f32;
+                center + Vec2::new(radius.x * angle.cos(), radius.y * angle.sin())
+            })
+            .collect();
+        self.add_polyline(shape_index, &points);
+        self.key_points.push((center, KeyPointType::Center));
+        self.key_points.extend(
+            [
+                Vec2::new(radius.x, 0.0),
+                Vec2::new(0.0, radius.y),
+                Vec2::new(-radius.x, 0.0),
+                Vec2::new(0.0, -radius.y),
+            ]
+            .map(|offset| (center + offset, KeyPointType::Quadrant)),
+        );
+    }
+}
+
+impl IndexedShapesVisitor for ShapeGeometry {
+    fn indexed_line_segment(
+        &mut self,
+        index: usize,
+        points: &mut [Pos2; 2],
+        stroke: &mut Stroke,
+    ) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(
+                index,
+                Rect::from_two_pos(points[0], points[1]).expand(stroke.width / 2.0),
+            );
+            self.add_segment(index, points[0], points[1]);
+        }
+        None
+    }
+
+    fn indexed_path(&mut self, index: usize, path: &mut PathShape) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(index, path.visual_bounding_rect());
+            let points_count = path.points.len();
+            let segments_count = if path.closed {
+                points_count
+            } else {
+                points_count.saturating_sub(1)
+            };
+            for i in 0..segments_count {
+                self.add_segment(index, path.points[i], path.points[(i + 1) % points_count]);
+            }
+        }
+        None
+    }
+
+    fn indexed_circle(&mut self, index: usize, circle: &mut CircleShape) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(index, circle.visual_bounding_rect());
+            self.add_ellipse(index, circle.center, Vec2::splat(circle.radius));
+        }
+        None
+    }
+
+    fn indexed_ellipse(&mut self, index: usize, ellipse: &mut EllipseShape) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(index, ellipse.visual_bounding_rect());
+            self.add_ellipse(index, ellipse.center, ellipse.radius);
+        }
+        None
+    }
+
+    fn indexed_rect(&mut self, index: usize, rect: &mut RectShape) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(index, rect.visual_bounding_rect());
+            let rect = rect.rect;
+            let corners = [
+                rect.left_top(),
+                rect.right_top(),
+                rect.right_bottom(),
+                rect.left_bottom(),
+            ];
+            for i in 0..corners.len() {
+                self.add_segment(index, corners[i], corners[(i + 1) % corners.len()]);
+            }
+            self.key_points.push((rect.center(), KeyPointType::Center));
+        }
+        None
+    }
+
+    fn indexed_quadratic_bezier(
+        &mut self,
+        index: usize,
+        bezier: &mut QuadraticBezierShape,
+    ) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(index, bezier.visual_bounding_rect());
+            self.add_polyline(index, &bezier.flatten(None));
+        }
+        None
+    }
+
+    fn indexed_cubic_bezier(
+        &mut self,
+        index: usize,
+        cubic_bezier: &mut CubicBezierShape,
+    ) -> Option<()> {
+        if !self.ignored_shapes.contains(&index) {
+            self.add_bounds(index, cubic_bezier.visual_bounding_rect());
+            self.add_polyline(index, &cubic_bezier.flatten(None));
+        }
+        None
+    }
+}
@@ -1,17 +1,274 @@
 use crate::shape_editor::canvas::CanvasContext;
-use crate::shape_editor::control_point::ShapeControlPoints;
-use crate::shape_editor::index::{GridIndex, GridLineType, SnapComponent};
-use crate::shape_editor::{style, Selection};
-use egui::{Pos2, Rect, Shape, Vec2};
-use std::cmp::Ordering;
+use crate::shape_editor::control_point::{ShapeControlPoint, ShapeControlPoints};
+use crate::shape_editor::grid::GridMode;
+use crate::shape_editor::guides::{Guide, GuideOrientation};
+use crate::shape_editor::index::{GridIndex, GridLineType};
+use crate::shape_editor::shape_visitor::shape_geometry::{
+    GeometryEdge, KeyPointType, ShapeGeometry,
+};
+use crate::shape_editor::{style, utils, Selection, ShapeEditorOptions};
+use egui::ahash::HashMap;
+use egui::{Align2, Pos2, Rect, Shape, Vec2};
+use itertools::Itertools;
 use std::collections::BTreeSet;
 use std::ops::Sub;
+use strum::EnumIter;
+
+mod smart_guides;
 
 #[derive(Clone, Debug)]
 pub enum SnapTarget {
     ShapeControlPoint(Pos2),
     GridHorizontal(f32),
     GridVertical(f32),
+    GridPoint(Pos2),
+    Edge(Pos2),
+    Midpoint(Pos2),
+    Center(Pos2),
+    Quadrant(Pos2),
+    Intersection(Pos2),
+    Angle {
+        anchor: Pos2,
+        angle_degrees: f32,
+        length: f32,
+    },
+    External(ExternalSnapTarget),
+    SmartGuide {
+        from: Pos2,
+        to: Pos2,
+    },
+    EqualSpacing {
+        gaps: Vec<[Pos2; 2]>,
+    },
+}
+
+impl SnapTarget {
+    fn geometry_point(&self) -> Option<Pos2> {
+        match self {
+            SnapTarget::Edge(pos)
+            | SnapTarget::Midpoint(pos)
+            | SnapTarget::Center(pos)
+            | SnapTarget::Quadrant(pos)
+            | SnapTarget::Intersection(pos) => Some(*pos),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExternalSnapGeometry {
+    Point(Pos2),
+    VerticalLine(f32),
+    HorizontalLine(f32),
+}
+
+#[derive(Clone, Debug)]
+pub struct ExternalSnapTarget {
+    pub geometry: ExternalSnapGeometry,
+    pub highlight: Option<Shape>,
+}
+
+impl ExternalSnapTarget {
+    pub fn point(pos: Pos2) -> Self {
+        Self {
+            geometry: ExternalSnapGeometry::Point(pos),
+            highlight: None,
+        }
+    }
+
+    pub fn vertical_line(x: f32) -> Self {
+        Self {
+            geometry: ExternalSnapGeometry::VerticalLine(x),
+            highlight: None,
+        }
+    }
+
+    pub fn horizontal_line(y: f32) -> Self {
+        Self {
+            geometry: ExternalSnapGeometry::HorizontalLine(y),
+            highlight: None,
+        }
+    }
+
+    pub fn with_highlight(mut self, highlight: Shape) -> Self {
+        self.highlight = Some(highlight);
+        self
+    }
+
+    fn distance(&self, pos: Pos2) -> f32 {
+        match self.geometry {
+            ExternalSnapGeometry::Point(point) => point.distance(pos),
+            ExternalSnapGeometry::VerticalLine(x) => (x - pos.x).abs(),
+            ExternalSnapGeometry::HorizontalLine(y) => (y - pos.y).abs(),
+        }
+    }
+}
+
+pub trait SnapProvider {
+    fn snap_targets(
+        &self,
+        pos: Pos2,
+        max_distance: f32,
+        selection: &Selection,
+    ) -> Vec<ExternalSnapTarget>;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, EnumIter, strum::Display)]
+pub enum SnapTargetKind {
+    GridPrimary,
+    GridSecondary,
+    GridSub,
+    ControlPoints,
+    Guides,
+    SmartGuides,
+    Geometry,
+    Angle,
+    External,
+}
+
+impl SnapTargetKind {
+    fn grid_line_types(&self) -> &'static [GridLineType] {
+        match self {
+            SnapTargetKind::GridPrimary => &[GridLineType::Zero, GridLineType::Primary],
+            SnapTargetKind::GridSecondary => &[GridLineType::Secondary],
+            SnapTargetKind::GridSub => &[GridLineType::Sub],
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SnapTargetOptions {
+    pub enabled: bool,
+    pub priority: i32,
+    pub distance: Option<f32>,
+}
+
+impl SnapTargetOptions {
+    pub fn new(priority: i32) -> Self {
+        Self {
+            enabled: true,
+            priority,
+            distance: None,
+        }
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.enabled = false;
+        self
+    }
+
+    pub fn with_distance(mut self, distance: f32) -> Self {
+        self.distance = Some(distance);
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SnapOptions {
+    pub grid_primary: SnapTargetOptions,
+    pub grid_secondary: SnapTargetOptions,
+    pub grid_sub: SnapTargetOptions,
+    pub control_points: SnapTargetOptions,
+    pub path_points_only: bool,
+    pub guides: SnapTargetOptions,
+    pub smart_guides: SnapTargetOptions,
+    pub geometry: SnapTargetOptions,
+    pub angle: SnapTargetOptions,
+    pub external: SnapTargetOptions,
+    pub angle_step_degrees: f32,
+    pub length_step: Option<f32>,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        Self {
+            grid_primary: SnapTargetOptions::new(0),
+            grid_secondary: SnapTargetOptions::new(0),
+            grid_sub: SnapTargetOptions::new(0).disabled(),
+            control_points: SnapTargetOptions::new(0),
+            path_points_only: false,
+            guides: SnapTargetOptions::new(1),
+            smart_guides: SnapTargetOptions::new(1),
+            geometry: SnapTargetOptions::new(3),
+            angle: SnapTargetOptions::new(2),
+            external: SnapTargetOptions::new(3),
+            angle_step_degrees: 15.0,
+            length_step: None,
+        }
+    }
+}
+
+impl SnapOptions {
+    pub fn target(&self, kind: SnapTargetKind) -> &SnapTargetOptions {
+        match kind {
+            SnapTargetKind::GridPrimary => &self.grid_primary,
+            SnapTargetKind::GridSecondary => &self.grid_secondary,
+            SnapTargetKind::GridSub => &self.grid_sub,
+            SnapTargetKind::ControlPoints => &self.control_points,
+            SnapTargetKind::Guides => &self.guides,
+            SnapTargetKind::SmartGuides => &self.smart_guides,
+            SnapTargetKind::Geometry => &self.geometry,
+            SnapTargetKind::Angle => &self.angle,
+            SnapTargetKind::External => &self.external,
+        }
+    }
+
+    pub fn target_mut(&mut self, kind: SnapTargetKind) -> &mut SnapTargetOptions {
+        match kind {
+            SnapTargetKind::GridPrimary => &mut self.grid_primary,
+            SnapTargetKind::GridSecondary => &mut self.grid_secondary,
+            SnapTargetKind::GridSub => &mut self.grid_sub,
+            SnapTargetKind::ControlPoints => &mut self.control_points,
+            SnapTargetKind::Guides => &mut self.guides,
+            SnapTargetKind::SmartGuides => &mut self.smart_guides,
+            SnapTargetKind::Geometry => &mut self.geometry,
+            SnapTargetKind::Angle => &mut self.angle,
+            SnapTargetKind::External => &mut self.external,
+        }
+    }
+
+    pub fn is_enabled(&self, kind: SnapTargetKind) -> bool {
+        self.target(kind).enabled
+    }
+
+    pub fn set_enabled(&mut self, kind: SnapTargetKind, enabled: bool) {
+        self.target_mut(kind).enabled = enabled;
+    }
+
+    pub fn set_priority(&mut self, kind: SnapTargetKind, priority: i32) {
+        self.target_mut(kind).priority = priority;
+    }
+
+    pub fn set_distance(&mut self, kind: SnapTargetKind, distance: Option<f32>) {
+        self.target_mut(kind).distance = distance;
+    }
+
+    pub(crate) fn apply_overrides(
+        &mut self,
+        overrides: &mut HashMap<SnapTargetKind, SnapTargetOverride>,
+    ) {
+        overrides.retain(|kind, target_override| {
+            self.is_enabled(*kind) == target_override.options_enabled
+        });
+        for (kind, target_override) in overrides.iter() {
+            self.set_enabled(*kind, target_override.enabled);
+        }
+    }
+
+    fn priority(&self, kind: SnapTargetKind) -> i32 {
+        self.target(kind).priority
+    }
+
+    fn distance(&self, kind: SnapTargetKind, default_distance: f32) -> f32 {
+        self.target(kind).distance.unwrap_or(default_distance)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SnapTargetOverride {
+    pub options_enabled: bool,
+    pub enabled: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -22,56 +279,221 @@ pub struct SnapInfo {
     pub snap_point: Option<Pos2>,
 }
 
+pub(crate) struct SnapSources<'a> {
+    pub grid_index: &'a GridIndex,
+    pub shape_control_points: &'a ShapeControlPoints,
+    pub shape_geometry: &'a ShapeGeometry,
+    pub selection: &'a Selection,
+    pub anchor: Option<Pos2>,
+    pub snap_providers: &'a [&'a dyn SnapProvider],
+    pub guides: &'a [Guide],
+    pub moving_bounds: Option<Rect>,
+}
+
+#[derive(Clone)]
+struct ComponentSnap {
+    value: f32,
+    priority: i32,
+    targets: Vec<SnapTarget>,
+}
+
+struct PointSnap {
+    point: Pos2,
+    priority: i32,
+    targets: Vec<SnapTarget>,
+}
+
 impl SnapInfo {
     pub(crate) fn update_snap_info(
         snap: &mut SnapInfo,
         pos: Pos2,
-        max_distance: f32,
-        grid_index: &GridIndex,
-        shape_control_points: &ShapeControlPoints,
-        selection: &Selection,
+        scale: f32,
+        sources: &SnapSources,
+        options: &ShapeEditorOptions,
     ) {
-        let mut ignored_grid_line_types = BTreeSet::new();
-        ignored_grid_line_types.insert(GridLineType::Sub);
-        let max_distance_x = if snap.manual_snap_x.is_some() {
-            0.0
-        } else {
-            max_distance
-        };
-        let max_distance_y = if snap.manual_snap_y.is_some() {
-            0.0
-        } else {
-            max_distance
-        };
+        let snap_options = &options.snap;
+        let max_distance =
+            |kind: SnapTargetKind| scale * snap_options.distance(kind, options.snap_distance);
         snap.targets.clear();
-        let snap_x = calculate_snap_point_x(
-            snap,
+        let has_manual_snap = snap.manual_snap_x.is_some() || snap.manual_snap_y.is_some();
+        let external_targets: Vec<ExternalSnapTarget> =
+            if snap_options.is_enabled(SnapTargetKind::External) {
+                let distance = max_distance(SnapTargetKind::External);
+                sources
+                    .snap_providers
+                    .iter()
+                    .flat_map(|provider| provider.snap_targets(pos, distance, sources.selection))
+                    .filter(|target| target.distance(pos) <= distance)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+        let snap_x = calculate_snap_component(
+            SnapComponentAxis::X,
             pos,
-            max_distance_x,
-            grid_index,
-            shape_control_points,
-            selection,
-            &ignored_grid_line_types,
+            snap.manual_snap_x.is_some(),
+            sources,
+            &external_targets,
+            options,
+            scale,
         );
-        let snap_y = calculate_snap_point_y(
-            snap,
+        let snap_y = calculate_snap_component(
+            SnapComponentAxis::Y,
             pos,
-            max_distance_y,
-            grid_index,
-            shape_control_points,
-            selection,
-            &ignored_grid_line_types,
+            snap.manual_snap_y.is_some(),
+            sources,
+            &external_targets,
+            options,
+            scale,
         );
+        let component_priority = snap_x
+            .iter()
+            .chain(snap_y.iter())
+            .map(|component| component.priority)
+            .max();
+        let component_control_points = |component: &Option<ComponentSnap>| -> Vec<Pos2> {
+            component
+                .iter()
+                .flat_map(|component| &component.targets)
+                .filter_map(|target| match target {
+                    SnapTarget::ShapeControlPoint(point) => Some(*point),
+                    _ => None,
+                })
+                .collect()
+        };
+        let control_points_y = component_control_points(&snap_y);
+        let exact_control_point_snap = component_control_points(&snap_x)
+            .iter()
+            .any(|point| control_points_y.contains(point));
 
-        if snap_x.is_some()
+        let geometry_snap = (!has_manual_snap && snap_options.is_enabled(SnapTargetKind::Geometry))
+            .then(|| {
+                calculate_geometry_snap(
+                    pos,
+                    max_distance(SnapTargetKind::Geometry),
+                    sources.shape_geometry,
+                )
+            })
+            .flatten();
+        let angle_snap = (!has_manual_snap && snap_options.is_enabled(SnapTargetKind::Angle))
+            .then(|| {
+                calculate_angle_snap(
+                    pos,
+                    sources.anchor?,
+                    max_distance(SnapTargetKind::Angle),
+                    snap_x.as_ref().map(|component| component.value),
+                    snap_y.as_ref().map(|component| component.value),
+                    snap_options.angle_step_degrees,
+                    snap_options.length_step,
+                )
+            })
+            .flatten();
+        let geometry_point_snap = geometry_snap
+            .clone()
+            .filter(|target| !matches!(target, SnapTarget::Edge(_)))
+            .and_then(|target| {
+                Some(PointSnap {
+                    point: target.geometry_point()?,
+                    priority: snap_options.priority(SnapTargetKind::Geometry),
+                    targets: vec![target],
+                })
+            });
+        let external_point_snap = external_targets
+            .iter()
+            .filter(|_| !has_manual_snap)
+            .filter_map(|target| match target.geometry {
+                ExternalSnapGeometry::Point(point) => Some((point, target)),
+                _ => None,
+            })
+            .min_by(|(point_a, _), (point_b, _)| {
+                point_a.distance(pos).total_cmp(&point_b.distance(pos))
+            })
+            .map(|(point, target)| PointSnap {
+                point,
+                priority: snap_options.priority(SnapTargetKind::External),
+                targets: vec![SnapTarget::External(target.clone())],
+            });
+        let angle_point_snap = angle_snap.map(|(point, angle_target, component)| {
+            let component = match component {
+                Some(SnapComponentAxis::X) => snap_x.clone(),
+                Some(SnapComponentAxis::Y) => snap_y.clone(),
+                None => None,
+            };
+            let mut targets: Vec<SnapTarget> = component
+                .into_iter()
+                .flat_map(|component| component.targets)
+                .collect();
+            targets.push(angle_target);
+            PointSnap {
+                point,
+                priority: snap_options.priority(SnapTargetKind::Angle),
+                targets,
+            }
+        });
+        let grid_point_snap = (!has_manual_snap
+            && sources.grid_index.mode != GridMode::Rectangular)
+            .then(|| {
+                let kind = [SnapTargetKind::GridSecondary, SnapTargetKind::GridPrimary]
+                    .into_iter()
+                    .find(|kind| snap_options.is_enabled(*kind))?;
+                let point = sources.grid_index.snap_point(
+                    pos,
+                    max_distance(kind),
+                    kind == SnapTargetKind::GridSecondary,
+                )?;
+                Some(PointSnap {
+                    point,
+                    priority: snap_options.priority(kind),
+                    targets: vec![SnapTarget::GridPoint(point)],
+                })
+            })
+            .flatten();
+        let point_snap = [
+            grid_point_snap,
+            geometry_point_snap,
+            external_point_snap,
+            angle_point_snap,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|_| !exact_control_point_snap)
+        .filter(|point_snap| component_priority.map_or(true, |p| point_snap.priority >= p))
+        .min_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.point.distance(pos).total_cmp(&b.point.distance(pos)))
+        });
+        if let Some(point_snap) = point_snap {
+            snap.snap_point = Some(point_snap.point);
+            snap.targets.extend(point_snap.targets);
+        } else if let Some(edge_target) =
+            geometry_snap.filter(|_| snap_x.is_none() && snap_y.is_none())
+        {
+            snap.snap_point = edge_target.geometry_point();
+            snap.targets.push(edge_target);
+        } else if snap_x.is_some()
             || snap_y.is_some()
             || snap.manual_snap_x.is_some()
             || snap.manual_snap_y.is_some()
         {
             snap.snap_point.replace(Pos2::new(
-                snap_x.or(snap.manual_snap_x).unwrap_or(pos.x),
-                snap_y.or(snap.manual_snap_y).unwrap_or(pos.y),
+                snap_x
+                    .as_ref()
+                    .map(|component| component.value)
+                    .or(snap.manual_snap_x)
+                    .unwrap_or(pos.x),
+                snap_y
+                    .as_ref()
+                    .map(|component| component.value)
+                    .or(snap.manual_snap_y)
+                    .unwrap_or(pos.y),
             ));
+            snap.targets.extend(
+                snap_x
+                    .into_iter()
+                    .chain(snap_y)
+                    .flat_map(|component| component.targets),
+            );
         } else {
             snap.snap_point = None;
         }
@@ -84,100 +506,300 @@ impl SnapInfo {
     }
 }
 
-fn calculate_snap_point_x(
-    snap: &mut SnapInfo,
-    pos: Pos2,
-    max_distance: f32,
-    grid_index: &GridIndex,
-    shape_control_points: &ShapeControlPoints,
-    selection: &Selection,
-    ignored_grid_line_types: &BTreeSet<GridLineType>,
-) -> Option<f32> {
-    let control_point_snap =
-        shape_control_points.snap_x(pos, max_distance, selection.control_points());
-    let grid_snap = grid_index.snap_x(pos, max_distance, ignored_grid_line_types);
-    calculate_snap_point_component(
-        snap,
-        pos.x,
-        shape_control_points,
-        control_point_snap,
-        grid_snap,
-        SnapTarget::GridHorizontal,
-    )
-}
-
-fn calculate_snap_point_y(
-    snap: &mut SnapInfo,
+#[derive(Clone, Copy)]
+enum SnapComponentAxis {
+    X,
+    Y,
+}
+
+impl SnapComponentAxis {
+    fn component(&self, pos: Pos2) -> f32 {
+        match self {
+            SnapComponentAxis::X => pos.x,
+            SnapComponentAxis::Y => pos.y,
+        }
+    }
+
+    fn grid_target(&self, value: f32) -> SnapTarget {
+        match self {
+            SnapComponentAxis::X => SnapTarget::GridHorizontal(value),
+            SnapComponentAxis::Y => SnapTarget::GridVertical(value),
+        }
+    }
+}
+
+fn calculate_snap_component(
+    axis: SnapComponentAxis,
     pos: Pos2,
-    max_distance: f32,
-    grid_index: &GridIndex,
-    shape_control_points: &ShapeControlPoints,
-    selection: &Selection,
-    ignored_grid_line_types: &BTreeSet<GridLineType>,
-) -> Option<f32> {
-    let control_point_snap =
-        shape_control_points.snap_y(pos, max_distance, selection.control_points());
-    let grid_snap = grid_index.snap_y(pos, max_distance, ignored_grid_line_types);
-    calculate_snap_point_component(
-        snap,
-        pos.y,
-        shape_control_points,
-        control_point_snap,
-        grid_snap,
-        SnapTarget::GridVertical,
-    )
-}
-
-fn calculate_snap_point_component<F: FnOnce(f32) -> SnapTarget>(
-    snap: &mut SnapInfo,
-    component_pos: f32,
-    shape_control_points: &ShapeControlPoints,
-    control_point_snap: Option<SnapComponent>,
-    grid_snap: Option<f32>,
-    grid_snap_target: F,
-) -> Option<f32> {
-    match (control_point_snap, grid_snap) {
-        (Some((component_value, index_set)), None) => {
-            snap.targets.extend(index_set.iter().filter_map(|index| {
-                shape_control_points
-                    .pos_by_index(index)
-                    .map(SnapTarget::ShapeControlPoint)
+    manual_snap: bool,
+    sources: &SnapSources,
+    external_targets: &[ExternalSnapTarget],
+    options: &ShapeEditorOptions,
+    scale: f32,
+) -> Option<ComponentSnap> {
+    let snap_options = &options.snap;
+    let max_distance = |kind: SnapTargetKind| {
+        if manual_snap {
+            0.0
+        } else {
+            scale * snap_options.distance(kind, options.snap_distance)
+        }
+    };
+    let mut candidates = Vec::new();
+    if snap_options.is_enabled(SnapTargetKind::ControlPoints) {
+        let shape_control_points = sources.shape_control_points;
+        let mut ignored_points = sources.selection.control_points().clone();
+        if snap_options.path_points_only {
+            ignored_points.extend(shape_control_points.iter().filter_map(|(index, point)| {
+                matches!(point, ShapeControlPoint::ControlPoint { .. }).then_some(*index)
             }));
-            Some(component_value)
-        }
-        (None, Some(component_value)) => {
-            snap.targets.push(grid_snap_target(component_value));
-            Some(component_value)
-        }
-        (Some((point_component_value, index_set)), Some(grid_component_value)) => {
-            let point_distance = point_component_value.sub(component_pos).abs();
-            let grid_distance = grid_component_value.sub(component_pos).abs();
-            match point_distance.total_cmp(&grid_distance) {
-                Ordering::Less => {
-                    snap.targets.extend(index_set.iter().filter_map(|index| {
-                        shape_control_points
-                            .pos_by_index(index)
-                            .map(SnapTarget::ShapeControlPoint)
-                    }));
-                    Some(point_component_value)
-                }
-                Ordering::Equal => {
-                    snap.targets.extend(index_set.iter().filter_map(|index| {
+        }
+        let distance = max_distance(SnapTargetKind::ControlPoints);
+        let control_point_snap = match axis {
+            SnapComponentAxis::X => shape_control_points.snap_x(pos, distance, &ignored_points),
+            SnapComponentAxis::Y => shape_control_points.snap_y(pos, distance, &ignored_points),
+        };
+        if let Some((value, index_set)) = control_point_snap {
+            candidates.push(ComponentSnap {
+                value,
+                priority: snap_options.priority(SnapTargetKind::ControlPoints),
+                targets: index_set
+                    .iter()
+                    .filter_map(|index| {
                         shape_control_points
                             .pos_by_index(index)
                             .map(SnapTarget::ShapeControlPoint)
-                    }));
-                    snap.targets.push(grid_snap_target(grid_component_value));
-                    Some(point_component_value)
-                }
-                Ordering::Greater => {
-                    snap.targets.push(grid_snap_target(grid_component_value));
-                    Some(grid_component_value)
-                }
+                    })
+                    .collect(),
+            });
+        }
+    }
+    for kind in [
+        SnapTargetKind::GridPrimary,
+        SnapTargetKind::GridSecondary,
+        SnapTargetKind::GridSub,
+    ] {
+        if !snap_options.is_enabled(kind) || sources.grid_index.mode != GridMode::Rectangular {
+            continue;
+        }
+        let ignored_grid_line_types: BTreeSet<GridLineType> = [
+            GridLineType::Zero,
+            GridLineType::Primary,
+            GridLineType::Secondary,
+            GridLineType::Sub,
+        ]
+        .into_iter()
+        .filter(|line_type| !kind.grid_line_types().contains(line_type))
+        .collect();
+        let distance = max_distance(kind);
+        let grid_snap = match axis {
+            SnapComponentAxis::X => {
+                sources
+                    .grid_index
+                    .snap_x(pos, distance, &ignored_grid_line_types)
             }
+            SnapComponentAxis::Y => {
+                sources
+                    .grid_index
+                    .snap_y(pos, distance, &ignored_grid_line_types)
+            }
+        };
+        if let Some(value) = grid_snap {
+            candidates.push(ComponentSnap {
+                value,
+                priority: snap_options.priority(kind),
+                targets: vec![axis.grid_target(value)],
+            });
         }
-        _ => None,
     }
+    if snap_options.is_enabled(SnapTargetKind::Guides) {
+        let distance = max_distance(SnapTargetKind::Guides);
+        let guide_orientation = match axis {
+            SnapComponentAxis::X => GuideOrientation::Vertical,
+            SnapComponentAxis::Y => GuideOrientation::Horizontal,
+        };
+        let component_pos = axis.component(pos);
+        let guide_snap = sources
+            .guides
+            .iter()
+            .filter(|guide| guide.orientation == guide_orientation)
+            .map(|guide| guide.position)
+            .filter(|value| (value - component_pos).abs() <= distance)
+            .min_by(|a, b| {
+                (a - component_pos)
+                    .abs()
+                    .total_cmp(&(b - component_pos).abs())
+            });
+        if let Some(value) = guide_snap {
+            candidates.push(ComponentSnap {
+                value,
+                priority: snap_options.priority(SnapTargetKind::Guides),
+                targets: vec![axis.grid_target(value)],
+            });
+        }
+    }
+    if let Some(moving_bounds) = sources
+        .moving_bounds
+        .filter(|_| !manual_snap && snap_options.is_enabled(SnapTargetKind::SmartGuides))
+    {
+        let other_bounds: Vec<Rect> = sources.shape_geometry.bounds.values().copied().collect();
+        if let Some((value, target)) = smart_guides::snap_bounds(
+            axis,
+            pos,
+            moving_bounds,
+            &other_bounds,
+            max_distance(SnapTargetKind::SmartGuides),
+        ) {
+            candidates.push(ComponentSnap {
+                value,
+                priority: snap_options.priority(SnapTargetKind::SmartGuides),
+                targets: vec![target],
+            });
+        }
+    }
+    if !manual_snap {
+        candidates.extend(external_targets.iter().filter_map(|target| {
+            let value = match (axis, &target.geometry) {
+                (SnapComponentAxis::X, ExternalSnapGeometry::VerticalLine(x)) => *x,
+                (SnapComponentAxis::Y, ExternalSnapGeometry::HorizontalLine(y)) => *y,
+                _ => return None,
+            };
+            Some(ComponentSnap {
+                value,
+                priority: snap_options.priority(SnapTargetKind::External),
+                targets: vec![SnapTarget::External(target.clone())],
+            })
+        }));
+    }
+    select_component_snap(axis.component(pos), candidates)
+}
+
+fn select_component_snap(
+    component_pos: f32,
+    candidates: Vec<ComponentSnap>,
+) -> Option<ComponentSnap> {
+    let distance = |candidate: &ComponentSnap| candidate.value.sub(component_pos).abs();
+    let best = candidates.iter().min_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then(distance(a).total_cmp(&distance(b)))
+    })?;
+    let (value, priority) = (best.value, best.priority);
+    let targets = candidates
+        .into_iter()
+        .filter(|candidate| candidate.priority == priority && candidate.value == value)
+        .flat_map(|candidate| candidate.targets)
+        .collect();
+    Some(ComponentSnap {
+        value,
+        priority,
+        targets,
+    })
+}
+
+fn calculate_angle_snap(
+    pos: Pos2,
+    anchor: Pos2,
+    max_distance: f32,
+    snap_x: Option<f32>,
+    snap_y: Option<f32>,
+    angle_step_degrees: f32,
+    length_step: Option<f32>,
+) -> Option<(Pos2, SnapTarget, Option<SnapComponentAxis>)> {
+    let offset = pos - anchor;
+    if offset.length() <= max_distance || angle_step_degrees <= 0.0 {
+        return None;
+    }
+    let angle_step = angle_step_degrees.to_radians();
+    let angle = (offset.angle() / angle_step).round() * angle_step;
+    let direction = Vec2::angled(angle);
+    let projected_length = offset.dot(direction);
+    if (anchor + direction * projected_length).distance(pos) > max_distance {
+        return None;
+    }
+    let component_point =
+        |component: Option<f32>, anchor_component: f32, direction_component: f32| {
+            let component = component?;
+            if direction_component.abs() <= f32::EPSILON {
+                return None;
+            }
+            let length = (component - anchor_component) / direction_component;
+            let point = anchor + direction * length;
+            (length > 0.0 && point.distance(pos) <= max_distance).then_some((point, length))
+        };
+    let (point, length, component) =
+        if let Some((point, length)) = component_point(snap_x, anchor.x, direction.x) {
+            (point, length, Some(SnapComponentAxis::X))
+        } else if let Some((point, length)) = component_point(snap_y, anchor.y, direction.y) {
+            (point, length, Some(SnapComponentAxis::Y))
+        } else {
+            let length = length_step
+                .filter(|step| *step > 0.0)
+                .map(|step| (projected_length / step).round() * step)
+                .unwrap_or(projected_length);
+            (anchor + direction * length, length, None)
+        };
+    let angle_degrees = (-angle.to_degrees()).rem_euclid(360.0);
+    Some((
+        point,
+        SnapTarget::Angle {
+            anchor,
+            angle_degrees,
+            length,
+        },
+        component,
+    ))
+}
+
+fn calculate_geometry_snap(
+    pos: Pos2,
+    max_distance: f32,
+    shape_geometry: &ShapeGeometry,
+) -> Option<SnapTarget> {
+    let near_edges: Vec<(&GeometryEdge, Pos2, f32)> = shape_geometry
+        .edges_in_distance(pos, max_distance)
+        .filter_map(|edge| {
+            let point = utils::closest_point_on_segment(edge.start, edge.end, pos);
+            let distance = point.distance(pos);
+            (distance <= max_distance).then_some((edge, point, distance))
+        })
+        .collect();
+    let closest = |targets: Vec<SnapTarget>| {
+        targets.into_iter().min_by(|a, b| {
+            let distance_a = a.geometry_point().map_or(f32::MAX, |p| p.distance(pos));
+            let distance_b = b.geometry_point().map_or(f32::MAX, |p| p.distance(pos));
+            distance_a.total_cmp(&distance_b)
+        })
+    };
+    let intersections = near_edges
+        .iter()
+        .tuple_combinations()
+        .filter(|((edge1, _, _), (edge2, _, _))| edge1.shape_index != edge2.shape_index)
+        .filter_map(|((edge1, _, _), (edge2, _, _))| {
+            utils::segments_intersection(edge1.start, edge1.end, edge2.start, edge2.end)
+        })
+        .filter(|point| point.distance(pos) <= max_distance)
+        .map(SnapTarget::Intersection)
+        .collect();
+    closest(intersections)
+        .or_else(|| {
+            closest(
+                shape_geometry
+                    .key_points_in_distance(pos, max_distance)
+                    .map(|(point, key_point_type)| match key_point_type {
+                        KeyPointType::Midpoint => SnapTarget::Midpoint(*point),
+                        KeyPointType::Center => SnapTarget::Center(*point),
+                        KeyPointType::Quadrant => SnapTarget::Quadrant(*point),
+                    })
+                    .collect(),
+            )
+        })
+        .or_else(|| {
+            near_edges
+                .iter()
+                .min_by(|(_, _, distance1), (_, _, distance2)| distance1.total_cmp(distance2))
+                .map(|(_, point, _)| SnapTarget::Edge(*point))
+        })
 }
 
 pub fn paint_snap_point_highlight(
@@ -188,61 +810,506 @@ pub fn paint_snap_point_highlight(
     puffin_egui::puffin::profile_function!();
     if let Some(snap_point) = snap_info.snap_point {
         let ui_snap_point = ctx.transform.canvas_content_to_ui.transform_pos(snap_point);
-        let canvas_rect = ctx.transform.ui_canvas_rect();
         for snap_target in &snap_info.targets {
-            let shape = match snap_target {
-                SnapTarget::ShapeControlPoint(pos) => {
-                    let pos = ctx.transform.canvas_content_to_ui.transform_pos(*pos);
-                    let pos_rect = Rect::from_center_size(
-                        pos,
-                        Vec2::splat(style.snap_highlight_point_mark_size()),
-                    );
-                    let mut shape = Shape::dashed_line(
-                        &[ui_snap_point, pos],
-                        style.snap_highlight_stroke(),
-                        style.snap_highlight_dash_length(),
-                        style.snap_highlight_gap_length(),
-                    );
-                    shape.extend(Shape::dashed_line(
-                        &[pos_rect.left_top(), pos_rect.right_bottom()],
-                        style.snap_highlight_stroke(),
-                        style.snap_highlight_dash_length(),
-                        style.snap_highlight_gap_length(),
-                    ));
-                    shape.extend(Shape::dashed_line(
-                        &[pos_rect.right_top(), pos_rect.left_bottom()],
-                        style.snap_highlight_stroke(),
-                        style.snap_highlight_dash_length(),
-                        style.snap_highlight_gap_length(),
-                    ));
-                    Shape::Vec(shape)
-                }
-                SnapTarget::GridHorizontal(x) => {
-                    let x = ctx.transform.canvas_content_to_ui.transform_x(*x);
-                    Shape::Vec(Shape::dashed_line(
-                        &[
-                            Pos2::new(x, canvas_rect.top()),
-                            Pos2::new(x, canvas_rect.bottom()),
-                        ],
-                        style.snap_highlight_stroke(),
-                        style.snap_highlight_dash_length(),
-                        style.snap_highlight_gap_length(),
-                    ))
-                }
-                SnapTarget::GridVertical(y) => {
-                    let y = ctx.transform.canvas_content_to_ui.transform_y(*y);
-                    Shape::Vec(Shape::dashed_line(
-                        &[
-                            Pos2::new(canvas_rect.left(), y),
-                            Pos2::new(canvas_rect.right(), y),
-                        ],
-                        style.snap_highlight_stroke(),
-                        style.snap_highlight_dash_length(),
-                        style.snap_highlight_gap_length(),
-                    ))
-                }
-            };
-            ctx.painter.add(shape);
+            ctx.painter.add(snap_target_highlight(
+                ctx,
+                snap_target,
+                ui_snap_point,
+                style,
+            ));
+        }
+    }
+}
+
+fn snap_target_highlight(
+    ctx: &CanvasContext,
+    snap_target: &SnapTarget,
+    ui_snap_point: Pos2,
+    style: &dyn style::Style,
+) -> Shape {
+    let canvas_rect = ctx.transform.ui_canvas_rect();
+    match snap_target {
+        SnapTarget::ShapeControlPoint(pos) => {
+            let pos = ctx.transform.canvas_content_to_ui.transform_pos(*pos);
+            let pos_rect =
+                Rect::from_center_size(pos, Vec2::splat(style.snap_highlight_point_mark_size()));
+            let mut shape = Shape::dashed_line(
+                &[ui_snap_point, pos],
+                style.snap_highlight_stroke(),
+                style.snap_highlight_dash_length(),
+                style.snap_highlight_gap_length(),
+            );
+            shape.extend(Shape::dashed_line(
+                &[pos_rect.left_top(), pos_rect.right_bottom()],
+                style.snap_highlight_stroke(),
+                style.snap_highlight_dash_length(),
+                style.snap_highlight_gap_length(),
+            ));
+            shape.extend(Shape::dashed_line(
+                &[pos_rect.right_top(), pos_rect.left_bottom()],
+                style.snap_highlight_stroke(),
+                style.snap_highlight_dash_length(),
+                style.snap_highlight_gap_length(),
+            ));
+            Shape::Vec(shape)
+        }
+        SnapTarget::GridHorizontal(x) => {
+            let x = ctx.transform.canvas_content_to_ui.transform_x(*x);
+            Shape::Vec(Shape::dashed_line(
+                &[
+                    Pos2::new(x, canvas_rect.top()),
+                    Pos2::new(x, canvas_rect.bottom()),
+                ],
+                style.snap_highlight_stroke(),
+                style.snap_highlight_dash_length(),
+                style.snap_highlight_gap_length(),
+            ))
         }
+        SnapTarget::Edge(pos)
+        | SnapTarget::Midpoint(pos)
+        | SnapTarget::Center(pos)
+        | SnapTarget::Quadrant(pos)
+        | SnapTarget::Intersection(pos)
+        | SnapTarget::GridPoint(pos) => geometry_snap_glyph(
+            ctx.transform.canvas_content_to_ui.transform_pos(*pos),
+            snap_target,
+            style,
+        ),
+        SnapTarget::Angle {
+            anchor,
+            angle_degrees,
+            length,
+        } => {
+            let anchor = ctx.transform.canvas_content_to_ui.transform_pos(*anchor);
+            let guide_length = canvas_rect.size().length();
+            let guide_end = anchor + (ui_snap_point - anchor).normalized() * guide_length;
+            let mut shape = Shape::dashed_line(
+                &[anchor, guide_end],
+                style.snap_highlight_stroke(),
+                style.snap_highlight_dash_length(),
+                style.snap_highlight_gap_length(),
+            );
+            ctx.painter.fonts(|fonts| {
+                shape.push(Shape::text(
+                    fonts,
+                    ui_snap_point + Vec2::splat(style.snap_highlight_point_mark_size() / 2.0),
+                    Align2::LEFT_TOP,
+                    format!(
+                        "{:.0}° {}",
+                        angle_degrees,
+                        ctx.units.format_with_unit(*length)
+                    ),
+                    style.rulers_font().clone(),
+                    style.snap_highlight_stroke().color,
+                ))
+            });
+            Shape::Vec(shape)
+        }
+        SnapTarget::GridVertical(y) => {
+            let y = ctx.transform.canvas_content_to_ui.transform_y(*y);
+            Shape::Vec(Shape::dashed_line(
+                &[
+                    Pos2::new(canvas_rect.left(), y),
+                    Pos2::new(canvas_rect.right(), y),
+                ],
+                style.snap_highlight_stroke(),
+                style.snap_highlight_dash_length(),
+                style.snap_highlight_gap_length(),
+            ))
+        }
+        SnapTarget::SmartGuide { from, to } => {
+            let transform = &ctx.transform.canvas_content_to_ui;
+            Shape::line_segment(
+                [transform.transform_pos(*from), transform.transform_pos(*to)],
+                style.snap_highlight_stroke(),
+            )
+        }
+        SnapTarget::EqualSpacing { gaps } => {
+            let transform = &ctx.transform.canvas_content_to_ui;
+            let tick = style.snap_highlight_point_mark_size() / 4.0;
+            Shape::Vec(
+                gaps.iter()
+                    .flat_map(|[from, to]| {
+                        let from = transform.transform_pos(*from);
+                        let to = transform.transform_pos(*to);
+                        let normal = (to - from).normalized().rot90() * tick;
+                        [
+                            Shape::line_segment([from, to], style.snap_highlight_stroke()),
+                            Shape::line_segment(
+                                [from - normal, from + normal],
+                                style.snap_highlight_stroke(),
+                            ),
+                            Shape::line_segment(
+                                [to - normal, to + normal],
+                                style.snap_highlight_stroke(),
+                            ),
+                        ]
+                    })
+                    .collect(),
+            )
+        }
+        SnapTarget::External(target) => match (&target.highlight, target.geometry) {
+            (Some(highlight), _) => {
+                ctx.transform
+                    .canvas_content_to_ui
+                    .transform_shape(highlight)
+                    .0
+            }
+            (None, ExternalSnapGeometry::Point(pos)) => snap_target_highlight(
+                ctx,
+                &SnapTarget::ShapeControlPoint(pos),
+                ui_snap_point,
+                style,
+            ),
+            (None, ExternalSnapGeometry::VerticalLine(x)) => {
+                snap_target_highlight(ctx, &SnapTarget::GridHorizontal(x), ui_snap_point, style)
+            }
+            (None, ExternalSnapGeometry::HorizontalLine(y)) => {
+                snap_target_highlight(ctx, &SnapTarget::GridVertical(y), ui_snap_point, style)
+            }
+        },
+    }
+}
+
+fn geometry_snap_glyph(pos: Pos2, snap_target: &SnapTarget, style: &dyn style::Style) -> Shape {
+    let half_size = style.snap_highlight_point_mark_size() / 4.0;
+    let stroke = style.snap_highlight_stroke();
+    let rect = Rect::from_center_size(pos, Vec2::splat(half_size * 2.0));
+    match snap_target {
+        SnapTarget::Intersection(_) => Shape::Vec(vec![
+            Shape::line_segment([rect.left_top(), rect.right_bottom()], stroke),
+            Shape::line_segment([rect.right_top(), rect.left_bottom()], stroke),
+        ]),
+        SnapTarget::GridPoint(_) => Shape::Vec(vec![
+            Shape::line_segment([rect.left_center(), rect.right_center()], stroke),
+            Shape::line_segment([rect.center_top(), rect.center_bottom()], stroke),
+        ]),
+        SnapTarget::Midpoint(_) => Shape::closed_line(
+            vec![
+                pos - Vec2::new(0.0, half_size),
+                rect.right_bottom(),
+                rect.left_bottom(),
+            ],
+            stroke,
+        ),
+        SnapTarget::Center(_) => Shape::Vec(vec![
+            Shape::circle_stroke(pos, half_size, stroke),
+            Shape::line_segment([rect.left_center(), rect.right_center()], stroke),
+            Shape::line_segment([rect.center_top(), rect.center_bottom()], stroke),
+        ]),
+        SnapTarget::Quadrant(_) => Shape::closed_line(
+            vec![
+                rect.center_top(),
+                rect.right_center(),
+                rect.center_bottom(),
+                rect.left_center(),
+            ],
+            stroke,
+        ),
+        _ => Shape::Vec(vec![
+            Shape::line_segment([rect.left_top(), rect.right_top()], stroke),
+            Shape::line_segment([rect.right_top(), rect.left_bottom()], stroke),
+            Shape::line_segment([rect.left_bottom(), rect.right_bottom()], stroke),
+        ]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shape_editor::guides::Guide;
+    use crate::shape_editor::shape_visitor::shape_geometry::ShapeGeometry;
+    use crate::shape_editor::snap::{
+        calculate_angle_snap, calculate_geometry_snap, ExternalSnapGeometry, ExternalSnapTarget,
+        SnapComponentAxis, SnapInfo, SnapOptions, SnapProvider, SnapSources, SnapTarget,
+        SnapTargetKind, SnapTargetOverride,
+    };
+    use crate::shape_editor::{Selection, ShapeEditorOptions};
+    use egui::ahash::{HashMap, HashSet};
+    use egui::{Color32, Pos2, Shape, Stroke};
+
+    fn geometry() -> ShapeGeometry {
+        let stroke = Stroke::new(1.0, Color32::BLACK);
+        ShapeGeometry::collect(
+            &mut Shape::Vec(vec![
+                Shape::line_segment([Pos2::new(0.0, 0.0), Pos2::new(10.0, 10.0)], stroke),
+                Shape::line_segment([Pos2::new(0.0, 10.0), Pos2::new(10.0, 0.0)], stroke),
+                Shape::line_segment([Pos2::new(0.0, 20.0), Pos2::new(10.0, 20.0)], stroke),
+                Shape::circle_stroke(Pos2::new(30.0, 0.0), 5.0, stroke),
+            ]),
+            HashSet::default(),
+        )
+    }
+
+    fn geometry_snap(x: f32, y: f32) -> Option<SnapTarget> {
+        calculate_geometry_snap(Pos2::new(x, y), 2.0, &geometry())
+    }
+
+    fn toggled_off(options: &SnapOptions) -> HashMap<SnapTargetKind, SnapTargetOverride> {
+        HashMap::from_iter([(
+            SnapTargetKind::Guides,
+            SnapTargetOverride {
+                options_enabled: options.is_enabled(SnapTargetKind::Guides),
+                enabled: false,
+            },
+        )])
+    }
+
+    #[test]
+    fn runtime_toggle_overrides_unchanged_options() {
+        let mut options = SnapOptions::default();
+        let mut overrides = toggled_off(&options);
+        options.apply_overrides(&mut overrides);
+        assert!(!options.is_enabled(SnapTargetKind::Guides));
+        assert!(options.is_enabled(SnapTargetKind::Geometry));
+        assert_eq!(overrides.len(), 1);
+    }
+
+    #[test]
+    fn override_persists_across_frames() {
+        let mut overrides = toggled_off(&SnapOptions::default());
+        for _ in 0..3 {
+            let mut options = SnapOptions::default();
+            options.apply_overrides(&mut overrides);
+            assert!(!options.is_enabled(SnapTargetKind::Guides));
+        }
+    }
+
+    #[test]
+    fn host_change_after_toggle_wins() {
+        let mut overrides = toggled_off(&SnapOptions::default());
+        let mut options = SnapOptions::default();
+        options.set_enabled(SnapTargetKind::Guides, false);
+        options.apply_overrides(&mut overrides);
+        assert!(overrides.is_empty());
+        options.set_enabled(SnapTargetKind::Guides, true);
+        options.apply_overrides(&mut overrides);
+        assert!(options.is_enabled(SnapTargetKind::Guides));
+    }
+
+    #[test]
+    fn cleared_override_restores_options() {
+        let mut overrides = toggled_off(&SnapOptions::default());
+        overrides.remove(&SnapTargetKind::Guides);
+        let mut options = SnapOptions::default();
+        options.apply_overrides(&mut overrides);
+        assert!(options.is_enabled(SnapTargetKind::Guides));
+    }
+
+    #[test]
+    fn intersection_wins_over_nearby_edges() {
+        assert!(matches!(
+            geometry_snap(5.5, 5.0),
+            Some(SnapTarget::Intersection(pos)) if pos == Pos2::new(5.0, 5.0)
+        ));
+    }
+
+    #[test]
+    fn key_points_win_over_edges() {
+        assert!(matches!(
+            geometry_snap(5.5, 20.5),
+            Some(SnapTarget::Midpoint(pos)) if pos == Pos2::new(5.0, 20.0)
+        ));
+        assert!(matches!(
+            geometry_snap(30.5, 0.5),
+            Some(SnapTarget::Center(pos)) if pos == Pos2::new(30.0, 0.0)
+        ));
+        assert!(matches!(
+            geometry_snap(35.5, 0.5),
+            Some(SnapTarget::Quadrant(pos)) if pos == Pos2::new(35.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn edge_snaps_to_the_closest_point() {
+        assert!(matches!(
+            geometry_snap(2.0, 21.0),
+            Some(SnapTarget::Edge(pos)) if pos == Pos2::new(2.0, 20.0)
+        ));
+        assert!(geometry_snap(20.0, 20.0).is_none());
+    }
+
+    fn angle_snap(
+        x: f32,
+        y: f32,
+        snap_x: Option<f32>,
+        length_step: Option<f32>,
+    ) -> Option<(Pos2, f32, f32, Option<SnapComponentAxis>)> {
+        let (point, target, component) = calculate_angle_snap(
+            Pos2::new(x, y),
+            Pos2::ZERO,
+            2.0,
+            snap_x,
+            None,
+            15.0,
+            length_step,
+        )?;
+        let SnapTarget::Angle {
+            angle_degrees,
+            length,
+            ..
+        } = target
+        else {
+            panic!("expected angle target, got {:?}", target);
+        };
+        Some((point, angle_degrees, length, component))
+    }
+
+    #[test]
+    fn angle_snap_projects_onto_the_nearest_step() {
+        let (point, angle_degrees, length, component) = angle_snap(10.0, 1.0, None, None).unwrap();
+        assert_eq!(point, Pos2::new(10.0, 0.0));
+        assert_eq!(angle_degrees, 0.0);
+        assert_eq!(length, 10.0);
+        assert!(component.is_none());
+
+        let (point, angle_degrees, _, _) = angle_snap(1.0, -10.0, None, None).unwrap();
+        assert!(point.distance(Pos2::new(0.0, -10.0)) < 1e-3);
+        assert!((angle_degrees - 90.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn angle_snap_rounds_length_to_step() {
+        let (point, _, length, _) = angle_snap(10.0, 1.0, None, Some(3.0)).unwrap();
+        assert_eq!(point, Pos2::new(9.0, 0.0));
+        assert_eq!(length, 9.0);
+    }
+
+    #[test]
+    fn angle_snap_prefers_component_snap_on_the_ray() {
+        let (point, _, length, component) = angle_snap(10.0, 1.0, Some(9.5), Some(3.0)).unwrap();
+        assert_eq!(point, Pos2::new(9.5, 0.0));
+        assert_eq!(length, 9.5);
+        assert!(matches!(component, Some(SnapComponentAxis::X)));
+    }
+
+    #[test]
+    fn angle_snap_ignores_points_near_anchor_or_off_the_ray() {
+        assert!(angle_snap(1.0, 1.0, None, None).is_none());
+        assert!(angle_snap(40.0, 5.0, None, None).is_none());
+    }
+
+    struct FixedTargets(Vec<ExternalSnapTarget>);
+
+    impl SnapProvider for FixedTargets {
+        fn snap_targets(
+            &self,
+            _pos: Pos2,
+            _max_distance: f32,
+            _selection: &Selection,
+        ) -> Vec<ExternalSnapTarget> {
+            self.0.clone()
+        }
+    }
+
+    fn update_snap(
+        pos: Pos2,
+        targets: Vec<ExternalSnapTarget>,
+        guides: &[Guide],
+        options: &ShapeEditorOptions,
+    ) -> SnapInfo {
+        let provider = FixedTargets(targets);
+        let providers: [&dyn SnapProvider; 1] = [&provider];
+        let mut snap = SnapInfo::default();
+        SnapInfo::update_snap_info(
+            &mut snap,
+            pos,
+            1.0,
+            &SnapSources {
+                grid_index: &Default::default(),
+                shape_control_points: &Default::default(),
+                shape_geometry: &Default::default(),
+                selection: &Selection::default(),
+                anchor: None,
+                snap_providers: &providers,
+                guides,
+                moving_bounds: None,
+            },
+            options,
+        );
+        snap
+    }
+
+    #[test]
+    fn provider_point_snaps_to_the_closest_target() {
+        let snap = update_snap(
+            Pos2::new(10.0, 10.0),
+            vec![
+                ExternalSnapTarget::point(Pos2::new(13.0, 10.0)),
+                ExternalSnapTarget::point(Pos2::new(11.0, 11.0)),
+                ExternalSnapTarget::point(Pos2::new(30.0, 10.0)),
+            ],
+            &[],
+            &ShapeEditorOptions::default(),
+        );
+        assert_eq!(snap.snap_point, Some(Pos2::new(11.0, 11.0)));
+        assert!(matches!(
+            snap.targets.as_slice(),
+            [SnapTarget::External(ExternalSnapTarget {
+                geometry: ExternalSnapGeometry::Point(pos),
+                ..
+            })] if *pos == Pos2::new(11.0, 11.0)
+        ));
+    }
+
+    #[test]
+    fn provider_lines_snap_single_components() {
+        let snap = update_snap(
+            Pos2::new(10.0, 10.0),
+            vec![
+                ExternalSnapTarget::vertical_line(12.0),
+                ExternalSnapTarget::horizontal_line(40.0),
+            ],
+            &[],
+            &ShapeEditorOptions::default(),
+        );
+        assert_eq!(snap.snap_point, Some(Pos2::new(12.0, 10.0)));
+        assert_eq!(snap.targets.len(), 1);
+    }
+
+    #[test]
+    fn disabled_external_kind_ignores_providers() {
+        let mut options = ShapeEditorOptions::default();
+        options.snap.set_enabled(SnapTargetKind::External, false);
+        let snap = update_snap(
+            Pos2::new(10.0, 10.0),
+            vec![ExternalSnapTarget::point(Pos2::new(11.0, 10.0))],
+            &[],
+            &options,
+        );
+        assert_eq!(snap.snap_point, None);
+        assert!(snap.targets.is_empty());
+    }
+
+    #[test]
+    fn guides_snap_their_own_axis() {
+        let guides = [Guide::vertical(12.0), Guide::horizontal(8.0).locked()];
+        let snap = update_snap(
+            Pos2::new(10.0, 10.0),
+            Vec::new(),
+            &guides,
+            &ShapeEditorOptions::default(),
+        );
+        assert_eq!(snap.snap_point, Some(Pos2::new(12.0, 8.0)));
+
+        let snap = update_snap(
+            Pos2::new(10.0, 20.0),
+            Vec::new(),
+            &guides,
+            &ShapeEditorOptions::default(),
+        );
+        assert_eq!(snap.snap_point, Some(Pos2::new(12.0, 20.0)));
+    }
+
+    #[test]
+    fn disabled_guides_do_not_snap() {
+        let mut options = ShapeEditorOptions::default();
+        options.snap.set_enabled(SnapTargetKind::Guides, false);
+        let snap = update_snap(
+            Pos2::new(10.0, 10.0),
+            Vec::new(),
+            &[Guide::vertical(12.0)],
+            &options,
+        );
+        assert_eq!(snap.snap_point, None);
     }
 }
@@ -0,0 +1,296 @@
+use crate::shape_editor::snap::{SnapComponentAxis, SnapTarget};
+use egui::{Pos2, Rangef, Rect, Vec2};
+use itertools::Itertools;
+
+struct Candidate {
+    delta: f32,
+    target: SnapTarget,
+}
+
+pub(super) fn snap_bounds(
+    axis: SnapComponentAxis,
+    pos: Pos2,
+    moving_bounds: Rect,
+    other_bounds: &[Rect],
+    max_distance: f32,
+) -> Option<(f32, SnapTarget)> {
+    alignment_candidates(axis, moving_bounds, other_bounds, max_distance)
+        .chain(spacing_candidates(
+            axis,
+            moving_bounds,
+            other_bounds,
+            max_distance,
+        ))
+        .min_by(|a, b| a.delta.abs().total_cmp(&b.delta.abs()))
+        .map(|candidate| (axis.component(pos) + candidate.delta, candidate.target))
+}
+
+fn alignment_candidates<'a>(
+    axis: SnapComponentAxis,
+    moving_bounds: Rect,
+    other_bounds: &'a [Rect],
+    max_distance: f32,
+) -> impl Iterator<Item = Candidate> + 'a {
+    let moving_range = axis.range(&moving_bounds);
+    other_bounds.iter().flat_map(move |other| {
+        let other_range = axis.range(other);
+        key_values(moving_range)
+            .into_iter()
+            .cartesian_product(key_values(other_range))
+            .filter(|(moving_value, other_value)| {
+                (other_value - moving_value).abs() <= max_distance
+            })
+            .map(|(moving_value, other_value)| {
+                let delta = other_value - moving_value;
+                let cross_range = axis.cross_range(&moving_bounds);
+                let other_cross_range = axis.cross_range(other);
+                let from = cross_range.min.min(other_cross_range.min);
+                let to = cross_range.max.max(other_cross_range.max);
+                Candidate {
+                    delta,
+                    target: SnapTarget::SmartGuide {
+                        from: axis.pos(other_value, from),
+                        to: axis.pos(other_value, to),
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+fn spacing_candidates(
+    axis: SnapComponentAxis,
+    moving_bounds: Rect,
+    other_bounds: &[Rect],
+    max_distance: f32,
+) -> impl Iterator<Item = Candidate> {
+    let moving_range = axis.range(&moving_bounds);
+    let moving_cross_range = axis.cross_range(&moving_bounds);
+    let neighbors: Vec<&Rect> = other_bounds
+        .iter()
+        .filter(|other| axis.cross_range(other).intersects(moving_cross_range))
+        .collect();
+    let gaps: Vec<(&Rect, &Rect)> = neighbors
+        .iter()
+        .filter_map(|before| {
+            neighbors
+                .iter()
+                .filter(|after| axis.range(after).min > axis.range(before).max)
+                .min_by(|a, b| axis.range(a).min.total_cmp(&axis.range(b).min))
+                .map(|after| (*before, *after))
+        })
+        .filter(|(before, after)| {
+            !Rangef::new(axis.range(before).max, axis.range(after).min).intersects(moving_range)
+        })
+        .collect();
+    let gap_size =
+        |(before, after): &(&Rect, &Rect)| axis.range(after).min - axis.range(before).max;
+    let before = neighbors
+        .iter()
+        .filter(|other| axis.range(other).max <= moving_range.min + max_distance)
+        .max_by(|a, b| axis.range(a).max.total_cmp(&axis.range(b).max))
+        .copied();
+    let after = neighbors
+        .iter()
+        .filter(|other| axis.range(other).min >= moving_range.max - max_distance)
+        .min_by(|a, b| axis.range(a).min.total_cmp(&axis.range(b).min))
+        .copied();
+
+    let mut candidates = Vec::new();
+    let mut push_candidate = |delta: f32,
+                              equal_gaps: Vec<(&Rect, &Rect)>,
+                              before: Option<&Rect>,
+                              after: Option<&Rect>| {
+        if delta.abs() > max_distance {
+            return;
+        }
+        let moved_bounds = moving_bounds.translate(axis.vec(delta));
+        let mut segments: Vec<[Pos2; 2]> = equal_gaps
+            .iter()
+            .map(|(before, after)| gap_segment(axis, before, after))
+            .collect();
+        if let Some(before) = before {
+            segments.push(gap_segment(axis, before, &moved_bounds));
+        }
+        if let Some(after) = after {
+            segments.push(gap_segment(axis, &moved_bounds, after));
+        }
+        candidates.push(Candidate {
+            delta,
+            target: SnapTarget::EqualSpacing { gaps: segments },
+        });
+    };
+    for gap in &gaps {
+        let size = gap_size(gap);
+        if let Some(before) = before {
+            push_candidate(
+                axis.range(before).max + size - moving_range.min,
+                vec![*gap],
+                Some(before),
+                None,
+            );
+        }
+        if let Some(after) = after {
+            push_candidate(
+                axis.range(after).min - size - moving_range.max,
+                vec![*gap],
+                None,
+                Some(after),
+            );
+        }
+    }
+    if let (Some(before), Some(after)) = (before, after) {
+        let center = (axis.range(before).max + axis.range(after).min) / 2.0;
+        if center - moving_range.span() / 2.0 >= axis.range(before).max {
+            push_candidate(
+                center - moving_range.center(),
+                Vec::new(),
+                Some(before),
+                Some(after),
+            );
+        }
+    }
+    candidates.into_iter()
+}
+
+fn gap_segment(axis: SnapComponentAxis, before: &Rect, after: &Rect) -> [Pos2; 2] {
+    let before_cross_range = axis.cross_range(before);
+    let after_cross_range = axis.cross_range(after);
+    let overlap = Rangef::new(
+        before_cross_range.min.max(after_cross_range.min),
+        before_cross_range.max.min(after_cross_range.max),
+    );
+    let cross = overlap.center();
+    [
+        axis.pos(axis.range(before).max, cross),
+        axis.pos(axis.range(after).min, cross),
+    ]
+}
+
+fn key_values(range: Rangef) -> [f32; 3] {
+    [range.min, range.center(), range.max]
+}
+
+impl SnapComponentAxis {
+    fn range(&self, rect: &Rect) -> Rangef {
+        match self {
+            SnapComponentAxis::X => rect.x_range(),
+            SnapComponentAxis::Y => rect.y_range(),
+        }
+    }
+
+    fn cross_range(&self, rect: &Rect) -> Rangef {
+        match self {
+            SnapComponentAxis::X => rect.y_range(),
+            SnapComponentAxis::Y => rect.x_range(),
+        }
+    }
+
+    fn pos(&self, value: f32, cross_value: f32) -> Pos2 {
+        match self {
+            SnapComponentAxis::X => Pos2::new(value, cross_value),
+            SnapComponentAxis::Y => Pos2::new(cross_value, value),
+        }
+    }
+
+    fn vec(&self, value: f32) -> Vec2 {
+        match self {
+            SnapComponentAxis::X => Vec2::new(value, 0.0),
+            SnapComponentAxis::Y => Vec2::new(0.0, value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x_min: f32, x_max: f32, y_min: f32, y_max: f32) -> Rect {
+        Rect::from_x_y_ranges(x_min..=x_max, y_min..=y_max)
+    }
+
+    fn snap_x(moving: Rect, others: &[Rect]) -> Option<(f32, SnapTarget)> {
+        snap_bounds(
+            SnapComponentAxis::X,
+            Pos2::new(100.0, 100.0),
+            moving,
+            others,
+            3.0,
+        )
+    }
+
+    #[test]
+    fn aligns_closest_edge_and_spans_both_shapes() {
+        let (value, target) =
+            snap_x(rect(12.0, 22.0, 40.0, 50.0), &[rect(0.0, 10.0, 0.0, 5.0)]).unwrap();
+        assert_eq!(value, 98.0);
+        assert!(matches!(
+            target,
+            SnapTarget::SmartGuide { from, to }
+                if from == Pos2::new(10.0, 0.0) && to == Pos2::new(10.0, 50.0)
+        ));
+    }
+
+    #[test]
+    fn aligns_vertical_axis() {
+        let (value, target) = snap_bounds(
+            SnapComponentAxis::Y,
+            Pos2::new(100.0, 100.0),
+            rect(40.0, 50.0, 3.0, 9.0),
+            &[rect(0.0, 10.0, 0.0, 10.0)],
+            3.0,
+        )
+        .unwrap();
+        assert_eq!(value, 99.0);
+        assert!(matches!(
+            target,
+            SnapTarget::SmartGuide { from, to }
+                if from == Pos2::new(0.0, 5.0) && to == Pos2::new(50.0, 5.0)
+        ));
+    }
+
+    #[test]
+    fn repeats_existing_gap_after_last_neighbor() {
+        let (value, target) = snap_x(
+            rect(41.0, 51.0, 0.0, 10.0),
+            &[rect(0.0, 10.0, 0.0, 10.0), rect(20.0, 30.0, 0.0, 10.0)],
+        )
+        .unwrap();
+        assert_eq!(value, 99.0);
+        let SnapTarget::EqualSpacing { gaps } = target else {
+            panic!("expected equal spacing, got {:?}", target);
+        };
+        assert_eq!(
+            gaps,
+            vec![
+                [Pos2::new(10.0, 5.0), Pos2::new(20.0, 5.0)],
+                [Pos2::new(30.0, 5.0), Pos2::new(40.0, 5.0)]
+            ]
+        );
+    }
+
+    #[test]
+    fn centers_between_two_neighbors() {
+        let (value, target) = snap_x(
+            rect(19.0, 29.0, 0.0, 10.0),
+            &[rect(0.0, 10.0, 0.0, 10.0), rect(40.0, 50.0, 0.0, 10.0)],
+        )
+        .unwrap();
+        assert_eq!(value, 101.0);
+        let SnapTarget::EqualSpacing { gaps } = target else {
+            panic!("expected equal spacing, got {:?}", target);
+        };
+        assert_eq!(
+            gaps,
+            vec![
+                [Pos2::new(10.0, 5.0), Pos2::new(20.0, 5.0)],
+                [Pos2::new(30.0, 5.0), Pos2::new(40.0, 5.0)]
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_shapes_out_of_range() {
+        assert!(snap_x(rect(30.0, 40.0, 0.0, 10.0), &[rect(0.0, 10.0, 50.0, 60.0)]).is_none());
+    }
+}
@@ -0,0 +1,225 @@
+use crate::shape_editor::shape_visitor::ShapePointIndex;
+use egui::ahash::HashMap;
+
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, PartialOrd, Ord)]
+pub struct ShapeId(u64);
+
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, PartialOrd, Ord)]
+pub struct PointId(u64);
+
+#[derive(Default, Clone)]
+pub struct StableIds {
+    next_id: u64,
+    shape_indices: HashMap<ShapeId, usize>,
+    shape_ids: HashMap<usize, ShapeId>,
+    point_indices: HashMap<PointId, ShapePointIndex>,
+    point_ids: HashMap<ShapePointIndex, PointId>,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct StableIdEntries {
+    shapes: Vec<(ShapeId, usize)>,
+    points: Vec<(PointId, ShapePointIndex)>,
+}
+
+impl StableIds {
+    pub fn shape_id(&mut self, shape_index: usize) -> ShapeId {
+        if let Some(id) = self.shape_ids.get(&shape_index) {
+            return *id;
+        }
+        let id = ShapeId(self.next_id());
+        self.insert_shape(id, shape_index);
+        id
+    }
+
+    pub fn point_id(&mut self, index: ShapePointIndex) -> PointId {
+        if let Some(id) = self.point_ids.get(&index) {
+            return *id;
+        }
+        let id = PointId(self.next_id());
+        self.insert_point(id, index);
+        id
+    }
+
+    pub fn find_shape_id(&self, shape_index: usize) -> Option<ShapeId> {
+        self.shape_ids.get(&shape_index).copied()
+    }
+
+    pub fn find_point_id(&self, index: &ShapePointIndex) -> Option<PointId> {
+        self.point_ids.get(index).copied()
+    }
+
+    pub fn shape_index(&self, id: ShapeId) -> Option<usize> {
+        self.shape_indices.get(&id).copied()
+    }
+
+    pub fn point_index(&self, id: PointId) -> Option<ShapePointIndex> {
+        self.point_indices.get(&id).copied()
+    }
+
+    pub fn remove_shape_id(&mut self, id: ShapeId) -> Option<usize> {
+        let shape_index = self.shape_indices.remove(&id)?;
+        self.shape_ids.remove(&shape_index);
+        Some(shape_index)
+    }
+
+    pub fn remove_point_id(&mut self, id: PointId) -> Option<ShapePointIndex> {
+        let index = self.point_indices.remove(&id)?;
+        self.point_ids.remove(&index);
+        Some(index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shape_indices.is_empty() && self.point_indices.is_empty()
+    }
+
+    pub(crate) fn remap(
+        &mut self,
+        remap: impl Fn(&ShapePointIndex) -> Option<ShapePointIndex>,
+        is_merged: impl Fn(&ShapePointIndex) -> bool,
+        is_shape_removed: impl Fn(usize) -> bool,
+    ) -> StableIdEntries {
+        let mut removed = StableIdEntries::default();
+        if self.is_empty() {
+            return removed;
+        }
+        let mut shapes: Vec<_> = std::mem::take(&mut self.shape_indices)
+            .into_iter()
+            .collect();
+        shapes.sort_unstable();
+        self.shape_ids.clear();
+        for (id, shape_index) in shapes {
+            if is_shape_removed(shape_index) {
+                removed.shapes.push((id, shape_index));
+            } else {
+                self.insert_shape(id, shape_index);
+            }
+        }
+        let mut points: Vec<_> = std::mem::take(&mut self.point_indices)
+            .into_iter()
+            .collect();
+        points.sort_unstable_by_key(|(id, index)| (is_merged(index), *index, *id));
+        self.point_ids.clear();
+        for (id, index) in points {
+            match remap(&index) {
+                Some(remapped) if !self.point_ids.contains_key(&remapped) => {
+                    self.insert_point(id, remapped)
+                }
+                _ => removed.points.push((id, index)),
+            }
+        }
+        removed
+    }
+
+    pub(crate) fn restore(&mut self, entries: &StableIdEntries) {
+        for &(id, shape_index) in &entries.shapes {
+            self.insert_shape(id, shape_index);
+        }
+        for &(id, index) in &entries.points {
+            self.insert_point(id, index);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, entries: &StableIdEntries) {
+        for &(id, _) in &entries.shapes {
+            self.remove_shape_id(id);
+        }
+        for &(id, _) in &entries.points {
+            self.remove_point_id(id);
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn insert_shape(&mut self, id: ShapeId, shape_index: usize) {
+        if let Some(replaced) = self.shape_ids.insert(shape_index, id) {
+            self.shape_indices.remove(&replaced);
+        }
+        self.shape_indices.insert(id, shape_index);
+    }
+
+    fn insert_point(&mut self, id: PointId, index: ShapePointIndex) {
+        if let Some(replaced) = self.point_ids.insert(index, id) {
+            self.point_indices.remove(&replaced);
+        }
+        self.point_indices.insert(id, index);
+    }
+}
+
+impl StableIdEntries {
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty() && self.points.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_reused_per_index_and_unique_across_indices() {
+        let mut stable_ids = StableIds::default();
+        let shape = stable_ids.shape_id(0);
+        let first = stable_ids.point_id((0, 0).into());
+        let second = stable_ids.point_id((0, 1).into());
+        assert_eq!(stable_ids.shape_id(0), shape);
+        assert_eq!(stable_ids.point_id((0, 0).into()), first);
+        assert_ne!(first, second);
+        assert_eq!(stable_ids.find_point_id(&(0, 1).into()), Some(second));
+        assert_eq!(stable_ids.find_point_id(&(0, 2).into()), None);
+
+        assert_eq!(stable_ids.remove_point_id(first), Some((0, 0).into()));
+        assert_eq!(stable_ids.find_point_id(&(0, 0).into()), None);
+        assert_ne!(stable_ids.point_id((0, 0).into()), first);
+        assert_eq!(stable_ids.remove_shape_id(shape), Some(0));
+        assert!(stable_ids.find_shape_id(0).is_none());
+    }
+
+    #[test]
+    fn remap_follows_points_and_restore_brings_back_removed_ids() {
+        let mut stable_ids = StableIds::default();
+        let removed_shape = stable_ids.shape_id(1);
+        let kept_shape = stable_ids.shape_id(0);
+        let removed_point = stable_ids.point_id((0, 0).into());
+        let shifted_point = stable_ids.point_id((0, 1).into());
+
+        let removed = stable_ids.remap(
+            |index| (index.point_index > 0).then(|| (0, index.point_index - 1).into()),
+            |_| false,
+            |shape_index| shape_index == 1,
+        );
+        assert_eq!(stable_ids.shape_index(kept_shape), Some(0));
+        assert_eq!(stable_ids.shape_index(removed_shape), None);
+        assert_eq!(stable_ids.point_index(removed_point), None);
+        assert_eq!(stable_ids.point_index(shifted_point), Some((0, 0).into()));
+
+        stable_ids.remove_point_id(shifted_point);
+        stable_ids.restore(&removed);
+        assert_eq!(stable_ids.shape_index(removed_shape), Some(1));
+        assert_eq!(stable_ids.point_index(removed_point), Some((0, 0).into()));
+        stable_ids.remove(&removed);
+        assert_eq!(stable_ids.shape_index(removed_shape), None);
+        assert_eq!(stable_ids.point_index(removed_point), None);
+        assert_eq!(stable_ids.shape_index(kept_shape), Some(0));
+    }
+
+    #[test]
+    fn merged_point_yields_its_target_index() {
+        let mut stable_ids = StableIds::default();
+        let merged = stable_ids.point_id((0, 0).into());
+        let target = stable_ids.point_id((1, 0).into());
+
+        let removed = stable_ids.remap(
+            |_| Some((1, 0).into()),
+            |index| index.shape_index == 0,
+            |_| false,
+        );
+        assert_eq!(stable_ids.point_index(target), Some((1, 0).into()));
+        assert_eq!(stable_ids.point_index(merged), None);
+        assert_eq!(removed.points, vec![(merged, (0, 0).into())]);
+    }
+}
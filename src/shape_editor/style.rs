@@ -1,4 +1,4 @@
-use egui::{Color32, Margin, Pos2, Shape, Stroke};
+use egui::{Color32, Margin, Pos2, Rect, Shape, Stroke, Vec2};
 
 #[derive(Clone)]
 pub struct Light {
@@ -25,17 +25,45 @@ pub struct Light {
     pub rulers_half_div_height: f32,
     pub rulers_sub_div_height: f32,
     pub rulers_text_position: Pos2,
+    pub rulers_label_spacing: f32,
+    pub rulers_cursor_stroke: Stroke,
+    pub rulers_selection_fill: Color32,
+    pub rulers_origin_stroke: Stroke,
+    pub minimap_size: Vec2,
+    pub minimap_margin: f32,
+    pub minimap_bg_color: Color32,
+    pub minimap_border_stroke: Stroke,
+    pub minimap_viewport_fill: Color32,
+    pub minimap_viewport_stroke: Stroke,
 
     pub grid_line_zero_stroke: Stroke,
     pub grid_line_primary_stroke: Stroke,
     pub grid_line_secondary_stroke: Stroke,
     pub grid_line_secondary_gap: f32,
     pub grid_line_secondary_length: f32,
+    pub grid_isometric_stroke: Stroke,
+    pub grid_polar_ring_stroke: Stroke,
+    pub grid_polar_spoke_stroke: Stroke,
 
     pub snap_highlight_stroke: Stroke,
     pub snap_highlight_dash_length: f32,
     pub snap_highlight_gap_length: f32,
     pub snap_highlight_point_mark_size: f32,
+
+    pub constraint_link_stroke: Stroke,
+    pub constraint_range_stroke: Stroke,
+    pub constraint_glyph_radius: f32,
+    pub constraint_glyph_fill: Color32,
+    pub constraint_glyph_stroke: Stroke,
+    pub constraint_selected_glyph_stroke: Stroke,
+
+    pub guide_stroke: Stroke,
+    pub guide_hovered_stroke: Stroke,
+    pub guide_locked_stroke: Stroke,
+    pub guide_locked_dash_length: f32,
+    pub guide_locked_gap_length: f32,
+    pub guide_hover_distance: f32,
+    pub touch_hit_scale: f32,
 }
 
 impl Default for Light {
@@ -63,17 +91,45 @@ impl Default for Light {
             rulers_half_div_height: 2.0,
             rulers_sub_div_height: 1.0,
             rulers_text_position: Pos2::new(0.0, 2.0),
+            rulers_label_spacing: 4.0,
+            rulers_cursor_stroke: Stroke::new(1.0, Color32::RED),
+            rulers_selection_fill: Color32::from_rgba_unmultiplied(0, 170, 255, 48),
+            rulers_origin_stroke: Stroke::new(1.0, Color32::DARK_GRAY),
+            minimap_size: Vec2::new(160.0, 120.0),
+            minimap_margin: 8.0,
+            minimap_bg_color: Color32::from_white_alpha(220),
+            minimap_border_stroke: Stroke::new(1.0, Color32::GRAY),
+            minimap_viewport_fill: Color32::from_rgba_unmultiplied(0, 170, 255, 32),
+            minimap_viewport_stroke: Stroke::new(1.0, Color32::from_rgb(0, 170, 255)),
 
             grid_line_zero_stroke: Stroke::new(2.0, Color32::LIGHT_GRAY),
             grid_line_primary_stroke: Stroke::new(0.5, Color32::LIGHT_GRAY),
             grid_line_secondary_stroke: Stroke::new(0.5, Color32::LIGHT_GRAY),
             grid_line_secondary_gap: 3.0,
             grid_line_secondary_length: 3.0,
+            grid_isometric_stroke: Stroke::new(0.5, Color32::LIGHT_GRAY),
+            grid_polar_ring_stroke: Stroke::new(0.5, Color32::LIGHT_GRAY),
+            grid_polar_spoke_stroke: Stroke::new(0.5, Color32::LIGHT_GRAY),
 
             snap_highlight_stroke: Stroke::new(1.0, Color32::GRAY),
             snap_highlight_dash_length: 5.0,
             snap_highlight_gap_length: 5.0,
             snap_highlight_point_mark_size: 20.0,
+
+            constraint_link_stroke: Stroke::new(1.0, Color32::LIGHT_BLUE),
+            constraint_range_stroke: Stroke::new(1.0, Color32::LIGHT_BLUE),
+            constraint_glyph_radius: 4.0,
+            constraint_glyph_fill: Color32::WHITE,
+            constraint_glyph_stroke: Stroke::new(1.0, Color32::BLUE),
+            constraint_selected_glyph_stroke: Stroke::new(2.0, Color32::DARK_BLUE),
+
+            guide_stroke: Stroke::new(1.0, Color32::from_rgb(0, 170, 255)),
+            guide_hovered_stroke: Stroke::new(2.0, Color32::from_rgb(0, 170, 255)),
+            guide_locked_stroke: Stroke::new(1.0, Color32::from_rgb(0, 170, 255)),
+            guide_locked_dash_length: 6.0,
+            guide_locked_gap_length: 3.0,
+            guide_hover_distance: 3.0,
+            touch_hit_scale: 2.5,
         }
     }
 }
@@ -171,6 +227,36 @@ impl Style for Light {
     fn rulers_text_position(&self) -> Pos2 {
         self.rulers_text_position
     }
+    fn rulers_label_spacing(&self) -> f32 {
+        self.rulers_label_spacing
+    }
+    fn rulers_cursor_stroke(&self) -> Stroke {
+        self.rulers_cursor_stroke
+    }
+    fn rulers_selection_fill(&self) -> Color32 {
+        self.rulers_selection_fill
+    }
+    fn rulers_origin_stroke(&self) -> Stroke {
+        self.rulers_origin_stroke
+    }
+    fn minimap_size(&self) -> Vec2 {
+        self.minimap_size
+    }
+    fn minimap_margin(&self) -> f32 {
+        self.minimap_margin
+    }
+    fn minimap_bg_color(&self) -> Color32 {
+        self.minimap_bg_color
+    }
+    fn minimap_border_stroke(&self) -> Stroke {
+        self.minimap_border_stroke
+    }
+    fn minimap_viewport_fill(&self) -> Color32 {
+        self.minimap_viewport_fill
+    }
+    fn minimap_viewport_stroke(&self) -> Stroke {
+        self.minimap_viewport_stroke
+    }
     fn grid_line_zero_stroke(&self) -> Stroke {
         self.grid_line_zero_stroke
     }
@@ -186,6 +272,15 @@ impl Style for Light {
     fn grid_line_secondary_length(&self) -> f32 {
         self.grid_line_secondary_length
     }
+    fn grid_isometric_stroke(&self) -> Stroke {
+        self.grid_isometric_stroke
+    }
+    fn grid_polar_ring_stroke(&self) -> Stroke {
+        self.grid_polar_ring_stroke
+    }
+    fn grid_polar_spoke_stroke(&self) -> Stroke {
+        self.grid_polar_spoke_stroke
+    }
     fn snap_highlight_stroke(&self) -> Stroke {
         self.snap_highlight_stroke
     }
@@ -198,6 +293,65 @@ impl Style for Light {
     fn snap_highlight_point_mark_size(&self) -> f32 {
         self.snap_highlight_point_mark_size
     }
+    fn constraint_link_stroke(&self) -> Stroke {
+        self.constraint_link_stroke
+    }
+    fn constraint_range_stroke(&self) -> Stroke {
+        self.constraint_range_stroke
+    }
+    fn constraint_glyph_radius(&self) -> f32 {
+        self.constraint_glyph_radius
+    }
+    fn constraint_glyph_shape(&self, pos: Pos2, selected: bool) -> Shape {
+        let stroke = if selected {
+            self.constraint_selected_glyph_stroke
+        } else {
+            self.constraint_glyph_stroke
+        };
+        Shape::Vec(vec![
+            Shape::circle_filled(
+                pos,
+                self.constraint_glyph_radius,
+                self.constraint_glyph_fill,
+            ),
+            Shape::circle_stroke(pos, self.constraint_glyph_radius, stroke),
+        ])
+    }
+    fn constraint_lock_shape(&self, pos: Pos2, selected: bool) -> Shape {
+        let stroke = if selected {
+            self.constraint_selected_glyph_stroke
+        } else {
+            self.constraint_glyph_stroke
+        };
+        let radius = self.constraint_glyph_radius;
+        let body = Rect::from_min_max(pos - Vec2::new(radius, 0.0), pos + Vec2::splat(radius));
+        let shackle = Rect::from_center_size(pos, Vec2::new(radius, radius * 1.5));
+        Shape::Vec(vec![
+            Shape::rect_stroke(shackle, radius / 2.0, stroke),
+            Shape::rect_filled(body, 0.0, self.constraint_glyph_fill),
+            Shape::rect_stroke(body, 0.0, stroke),
+        ])
+    }
+    fn guide_hover_distance(&self) -> f32 {
+        self.guide_hover_distance
+    }
+    fn touch_hit_scale(&self) -> f32 {
+        self.touch_hit_scale
+    }
+    fn guide_shape(&self, points: [Pos2; 2], hovered: bool, locked: bool) -> Shape {
+        if locked {
+            Shape::Vec(Shape::dashed_line(
+                &points,
+                self.guide_locked_stroke,
+                self.guide_locked_dash_length,
+                self.guide_locked_gap_length,
+            ))
+        } else if hovered {
+            Shape::line_segment(points, self.guide_hovered_stroke)
+        } else {
+            Shape::line_segment(points, self.guide_stroke)
+        }
+    }
 }
 
 pub trait Style {
@@ -222,13 +376,34 @@ pub trait Style {
     fn rulers_half_div_height(&self) -> f32;
     fn rulers_sub_div_height(&self) -> f32;
     fn rulers_text_position(&self) -> Pos2;
+    fn rulers_label_spacing(&self) -> f32;
+    fn rulers_cursor_stroke(&self) -> Stroke;
+    fn rulers_selection_fill(&self) -> Color32;
+    fn rulers_origin_stroke(&self) -> Stroke;
+    fn minimap_size(&self) -> Vec2;
+    fn minimap_margin(&self) -> f32;
+    fn minimap_bg_color(&self) -> Color32;
+    fn minimap_border_stroke(&self) -> Stroke;
+    fn minimap_viewport_fill(&self) -> Color32;
+    fn minimap_viewport_stroke(&self) -> Stroke;
     fn grid_line_zero_stroke(&self) -> Stroke;
     fn grid_line_primary_stroke(&self) -> Stroke;
     fn grid_line_secondary_stroke(&self) -> Stroke;
     fn grid_line_secondary_gap(&self) -> f32;
     fn grid_line_secondary_length(&self) -> f32;
+    fn grid_isometric_stroke(&self) -> Stroke;
+    fn grid_polar_ring_stroke(&self) -> Stroke;
+    fn grid_polar_spoke_stroke(&self) -> Stroke;
     fn snap_highlight_stroke(&self) -> Stroke;
     fn snap_highlight_dash_length(&self) -> f32;
     fn snap_highlight_gap_length(&self) -> f32;
     fn snap_highlight_point_mark_size(&self) -> f32;
+    fn constraint_link_stroke(&self) -> Stroke;
+    fn constraint_range_stroke(&self) -> Stroke;
+    fn constraint_glyph_radius(&self) -> f32;
+    fn constraint_glyph_shape(&self, pos: Pos2, selected: bool) -> Shape;
+    fn constraint_lock_shape(&self, pos: Pos2, selected: bool) -> Shape;
+    fn guide_hover_distance(&self) -> f32;
+    fn touch_hit_scale(&self) -> f32;
+    fn guide_shape(&self, points: [Pos2; 2], hovered: bool, locked: bool) -> Shape;
 }
@@ -1,60 +1,130 @@
-use crate::shape_editor::shape_visitor::{visit_shape, ShapeVisitor};
-use egui::emath::{Pos2, Rect, RectTransform, Vec2};
+use crate::shape_editor::shape_visitor::{visit_shape, ShapeType, ShapeVisitor};
+use egui::emath::{Pos2, Rect, Vec2};
+use egui::epaint::tessellator::path::rounded_rectangle;
+use egui::epaint::text::{Fonts, LayoutJob};
 use egui::epaint::{
-    CircleShape, CubicBezierShape, EllipseShape, Mesh, PathShape, QuadraticBezierShape, RectShape,
-    Shape, Stroke, TextShape,
+    CircleShape, CubicBezierShape, EllipseShape, Mesh, PaintCallback, PathShape,
+    QuadraticBezierShape, RectShape, Rounding, Shape, Stroke, TextShape, TextureId,
 };
+use egui::Galley;
 use egui::Rangef;
-
-#[derive(Clone, Debug)]
-pub struct Transform(pub(crate) RectTransform);
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub translation: Vec2,
+}
 
 impl Default for Transform {
     fn default() -> Self {
-        Self(RectTransform::identity(Rect::from_min_size(
-            Pos2::ZERO,
-            VEC_ONE,
-        )))
+        Self::IDENTITY
     }
 }
 
-const VEC_ONE: Vec2 = Vec2::splat(1.0);
+const ELLIPSE_SEGMENTS: usize = 64;
 
 impl Transform {
+    pub const IDENTITY: Self = Self {
+        x_axis: Vec2::X,
+        y_axis: Vec2::Y,
+        translation: Vec2::ZERO,
+    };
+
+    pub fn new(x_axis: Vec2, y_axis: Vec2, translation: Vec2) -> Self {
+        Self {
+            x_axis,
+            y_axis,
+            translation,
+        }
+    }
+
     pub fn from_to(from: Rect, to: Rect) -> Self {
-        Self(RectTransform::from_to(from, to))
+        let scale = to.size() / from.size();
+        Self::new(
+            Vec2::new(scale.x, 0.0),
+            Vec2::new(0.0, scale.y),
+            to.min.to_vec2() - from.min.to_vec2() * scale,
+        )
     }
 
     pub fn from_translate(translate: Vec2) -> Self {
-        Self::from_min(translate.to_pos2())
+        Self::new(Vec2::X, Vec2::Y, translate)
     }
 
     pub fn from_min(min: Pos2) -> Self {
-        Self(RectTransform::from_to(
-            Rect::from_min_size(Pos2::ZERO, VEC_ONE),
-            Rect::from_min_size(min, VEC_ONE),
-        ))
+        Self::from_translate(min.to_vec2())
+    }
+
+    pub fn from_scale(scale: Vec2) -> Self {
+        Self::new(Vec2::new(scale.x, 0.0), Vec2::new(0.0, scale.y), Vec2::ZERO)
+    }
+
+    pub fn from_rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(Vec2::new(cos, sin), Vec2::new(-sin, cos), Vec2::ZERO)
+    }
+
+    pub fn from_skew(skew: Vec2) -> Self {
+        Self::new(
+            Vec2::new(1.0, skew.y.tan()),
+            Vec2::new(skew.x.tan(), 1.0),
+            Vec2::ZERO,
+        )
     }
 
     pub fn combine(outer: &Self, inner: &Self) -> Self {
         puffin_egui::puffin::profile_function!();
-        let min = outer.0.to().min + (inner.0.to().min - inner.0.from().min) * outer.scale();
-
-        Self(RectTransform::from_to(
-            *outer.0.from(),
-            Rect::from_min_size(min, outer.0.to().size() * inner.scale()),
-        ))
+        Self::new(
+            outer.transform_vec(inner.x_axis),
+            outer.transform_vec(inner.y_axis),
+            outer.transform_pos(inner.translation.to_pos2()).to_vec2(),
+        )
     }
 
     pub fn scale(&self) -> Vec2 {
-        self.0.scale()
+        Vec2::new(self.x_axis.length(), self.y_axis.length())
     }
 
-    pub fn transform_shape(&self, shape: &Shape) -> Shape {
+    pub fn rotation(&self) -> f32 {
+        self.x_axis.angle()
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.x_axis.x * self.y_axis.y - self.y_axis.x * self.x_axis.y
+    }
+
+    pub fn is_axis_aligned(&self) -> bool {
+        self.x_axis.y == 0.0 && self.y_axis.x == 0.0
+    }
+
+    pub fn is_similarity(&self) -> bool {
+        let scale = self.scale();
+        let tolerance = scale.max_elem() * 1e-5;
+        (scale.x - scale.y).abs() <= tolerance
+            && self.x_axis.dot(self.y_axis).abs() <= tolerance * scale.max_elem()
+    }
+
+    pub fn transform_shape(&self, shape: &Shape) -> (Shape, Vec<ShapeType>) {
         puffin_egui::puffin::profile_function!();
         let mut shape = shape.clone();
-        TransformShape::transform(self.clone(), &mut shape);
-        shape
+        let approximated = TransformShape::transform(self.clone(), &mut shape);
+        (shape, approximated)
+    }
+
+    pub fn transform_shape_with_fonts(
+        &self,
+        shape: &Shape,
+        fonts: &Fonts,
+    ) -> (Shape, Vec<ShapeType>) {
+        puffin_egui::puffin::profile_function!();
+        let mut shape = shape.clone();
+        let approximated = TransformShape::new(self.clone())
+            .with_fonts(fonts)
+            .apply(&mut shape);
+        (shape, approximated)
     }
 
     pub fn translate_shape(&self, shape: &Shape) -> Shape {
@@ -66,7 +136,15 @@ impl Transform {
 
     pub fn transform_pos(&self, pos: Pos2) -> Pos2 {
         puffin_egui::puffin::profile_function!();
-        self.0.transform_pos(pos)
+        if self.is_axis_aligned() {
+            Pos2::new(self.x_axis.x * pos.x, self.y_axis.y * pos.y) + self.translation
+        } else {
+            (self.transform_vec(pos.to_vec2()) + self.translation).to_pos2()
+        }
+    }
+
+    pub fn transform_vec(&self, vec: Vec2) -> Vec2 {
+        self.x_axis * vec.x + self.y_axis * vec.y
     }
 
     pub fn transform_x(&self, x: f32) -> f32 {
@@ -85,65 +163,218 @@ impl Transform {
 
     pub fn transform_rect(&self, rect: &Rect) -> Rect {
         puffin_egui::puffin::profile_function!();
-        self.0.transform_rect(*rect)
+        if self.is_axis_aligned() {
+            Rect::from_two_pos(self.transform_pos(rect.min), self.transform_pos(rect.max))
+        } else {
+            Rect::from_points(&[
+                self.transform_pos(rect.left_top()),
+                self.transform_pos(rect.right_top()),
+                self.transform_pos(rect.left_bottom()),
+                self.transform_pos(rect.right_bottom()),
+            ])
+        }
     }
 
     pub fn transform_x_rangef(&self, range: &Rangef) -> Rangef {
         puffin_egui::puffin::profile_function!();
-        self.0
-            .transform_rect(Rect::from_x_y_ranges(*range, Rangef::NOTHING))
-            .x_range()
+        let (min, max) = (self.transform_x(range.min), self.transform_x(range.max));
+        Rangef::new(min.min(max), min.max(max))
     }
 
     pub fn transform_y_rangef(&self, range: &Rangef) -> Rangef {
         puffin_egui::puffin::profile_function!();
-        self.0
-            .transform_rect(Rect::from_x_y_ranges(Rangef::NOTHING, *range))
-            .y_range()
+        let (min, max) = (self.transform_y(range.min), self.transform_y(range.max));
+        Rangef::new(min.min(max), min.max(max))
     }
 
     pub fn resize_at(&self, delta: f32, point: Pos2) -> Self {
         puffin_egui::puffin::profile_function!();
-        let from = *self.0.from();
-        let to = Rect::from_min_size(self.0.to().min, self.0.from().size() * self.scale() * delta);
-        let transformed_point = self.inverse().transform_pos(point);
-        let resized = Self(RectTransform::from_to(from, to));
-        let resized_point = resized.transform_pos(transformed_point);
-        resized.translate(point - resized_point)
+        Self::new(
+            self.x_axis * delta,
+            self.y_axis * delta,
+            point.to_vec2() + (self.translation - point.to_vec2()) * delta,
+        )
+    }
+
+    pub fn zoom_to(&self, zoom: f32, anchor: Pos2) -> Self {
+        self.resize_at(zoom / self.scale().x, self.transform_pos(anchor))
+    }
+
+    pub fn center_on(&self, point: Pos2, canvas_size: Vec2) -> Self {
+        self.translate(canvas_size / 2.0 - self.transform_pos(point).to_vec2())
     }
 
     pub fn translate(&self, translate: Vec2) -> Self {
         puffin_egui::puffin::profile_function!();
-        Self(RectTransform::from_to(
-            *self.0.from(),
-            self.0.to().translate(translate),
-        ))
+        Self::new(self.x_axis, self.y_axis, self.translation + translate)
     }
 
     pub fn to_translate_only(&self) -> Self {
         puffin_egui::puffin::profile_function!();
-        Self(RectTransform::from_to(
-            *self.0.from(),
-            Rect::from_min_size(self.0.to().min, self.0.from().size()),
-        ))
+        Self::from_translate(self.translation)
     }
 
     pub fn inverse(&self) -> Self {
         puffin_egui::puffin::profile_function!();
-        Self(self.0.inverse())
+        let determinant = self.determinant();
+        let x_axis = Vec2::new(self.y_axis.y, -self.x_axis.y) / determinant;
+        let y_axis = Vec2::new(-self.y_axis.x, self.x_axis.x) / determinant;
+        let inverse = Self::new(x_axis, y_axis, Vec2::ZERO);
+        let translation = -inverse.transform_vec(self.translation);
+        Self::new(x_axis, y_axis, translation)
     }
 }
 
-pub struct TransformShape(Transform);
+pub struct TransformShape<'a> {
+    transform: Transform,
+    fonts: Option<&'a Fonts>,
+    approximated: Vec<ShapeType>,
+}
 
-impl TransformShape {
-    pub fn transform(transform: Transform, shape: &mut Shape) {
-        let mut slf = TransformShape(transform);
-        visit_shape(&mut slf, shape);
+impl<'a> TransformShape<'a> {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            transform,
+            fonts: None,
+            approximated: vec![],
+        }
+    }
+
+    pub fn with_fonts(mut self, fonts: &'a Fonts) -> Self {
+        self.fonts = Some(fonts);
+        self
+    }
+
+    pub fn transform(transform: Transform, shape: &mut Shape) -> Vec<ShapeType> {
+        TransformShape::new(transform).apply(shape)
+    }
+
+    pub fn apply(mut self, shape: &mut Shape) -> Vec<ShapeType> {
+        self.convert_unrepresentable(shape);
+        visit_shape(&mut self, shape);
+        self.approximated
+    }
+
+    fn convert_unrepresentable(&mut self, shape: &mut Shape) {
+        let axis_aligned = self.transform.is_axis_aligned();
+        match shape {
+            Shape::Vec(shapes) => shapes
+                .iter_mut()
+                .for_each(|shape| self.convert_unrepresentable(shape)),
+            Shape::Circle(circle) if !self.transform.is_similarity() => {
+                let ellipse = EllipseShape {
+                    center: circle.center,
+                    radius: Vec2::splat(circle.radius),
+                    fill: circle.fill,
+                    stroke: circle.stroke,
+                };
+                *shape = if axis_aligned {
+                    Shape::Ellipse(ellipse)
+                } else {
+                    ellipse_path(&ellipse)
+                };
+            }
+            Shape::Ellipse(ellipse) if !axis_aligned => {
+                *shape = ellipse_path(ellipse);
+            }
+            Shape::Rect(rect)
+                if !axis_aligned
+                    || (rect.rounding != Rounding::ZERO && !self.transform.is_similarity()) =>
+            {
+                if rect.fill_texture_id != TextureId::default() {
+                    self.approximated.push(ShapeType::Rect);
+                }
+                let mut points = vec![];
+                rounded_rectangle(&mut points, rect.rect, rect.rounding);
+                *shape = Shape::Path(PathShape {
+                    points,
+                    closed: true,
+                    fill: rect.fill,
+                    stroke: rect.stroke,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn scale_rounding(&self, rounding: Rounding) -> Rounding {
+        let scale = self.transform.scale().x;
+        let Rounding { nw, ne, sw, se } = rounding * scale;
+        match (self.transform.x_axis.x < 0.0, self.transform.y_axis.y < 0.0) {
+            (false, false) => Rounding { nw, ne, sw, se },
+            (true, false) => Rounding {
+                nw: ne,
+                ne: nw,
+                sw: se,
+                se: sw,
+            },
+            (false, true) => Rounding {
+                nw: sw,
+                ne: se,
+                sw: nw,
+                se: ne,
+            },
+            (true, true) => Rounding {
+                nw: se,
+                ne: sw,
+                sw: ne,
+                se: nw,
+            },
+        }
     }
 }
 
-impl ShapeVisitor for TransformShape {
+fn ellipse_path(ellipse: &EllipseShape) -> Shape {
+    let points = (0..ELLIPSE_SEGMENTS)
+        .map(|i| {
+            let angle = TAU * i as f32 / ELLIPSE_SEGMENTS as f32;
+            ellipse.center + Vec2::angled(angle) * ellipse.radius
+        })
+        .collect();
+    Shape::Path(PathShape {
+        points,
+        closed: true,
+        fill: ellipse.fill,
+        stroke: ellipse.stroke,
+    })
+}
+
+fn scale_layout_job(job: &mut LayoutJob, scale: f32) {
+    job.wrap.max_width *= scale;
+    job.first_row_min_height *= scale;
+    job.sections.iter_mut().for_each(|section| {
+        section.leading_space *= scale;
+        section.format.font_id.size *= scale;
+        section.format.extra_letter_spacing *= scale;
+        section.format.line_height = section.format.line_height.map(|h| h * scale);
+    });
+}
+
+fn scale_galley(galley: &Galley, job: LayoutJob, scale: f32) -> Galley {
+    let scale_pos = |pos: Pos2| (pos.to_vec2() * scale).to_pos2();
+    let scale_rect = |rect: Rect| Rect::from_min_max(scale_pos(rect.min), scale_pos(rect.max));
+    let mut galley = galley.clone();
+    galley.job = Arc::new(job);
+    galley.rect = scale_rect(galley.rect);
+    galley.mesh_bounds = scale_rect(galley.mesh_bounds);
+    for row in &mut galley.rows {
+        row.rect = scale_rect(row.rect);
+        row.visuals.mesh_bounds = scale_rect(row.visuals.mesh_bounds);
+        row.visuals
+            .mesh
+            .vertices
+            .iter_mut()
+            .for_each(|vertex| vertex.pos = scale_pos(vertex.pos));
+        for glyph in &mut row.glyphs {
+            glyph.pos = scale_pos(glyph.pos);
+            glyph.ascent *= scale;
+            glyph.size *= scale;
+        }
+    }
+    galley
+}
+
+impl<'a> ShapeVisitor for TransformShape<'a> {
     fn line_segment(
         &mut self,
         _index: &mut usize,
@@ -152,49 +383,57 @@ impl ShapeVisitor for TransformShape {
     ) -> Option<()> {
         points
             .iter_mut()
-            .for_each(|p| *p = self.0.transform_pos(*p));
+            .for_each(|p| *p = self.transform.transform_pos(*p));
         None
     }
 
     fn path(&mut self, _index: &mut usize, path: &mut PathShape) -> Option<()> {
         path.points
             .iter_mut()
-            .for_each(|p| *p = self.0.transform_pos(*p));
+            .for_each(|p| *p = self.transform.transform_pos(*p));
         None
     }
 
     fn circle(&mut self, _index: &mut usize, c: &mut CircleShape) -> Option<()> {
-        c.center = self.0.transform_pos(c.center);
-        c.radius *= self.0.scale().x;
+        c.center = self.transform.transform_pos(c.center);
+        c.radius *= self.transform.scale().x;
         None
     }
 
     fn ellipse(&mut self, _index: &mut usize, ellipse: &mut EllipseShape) -> Option<()> {
-        ellipse.center = self.0.transform_pos(ellipse.center);
-        ellipse.radius *= self.0.scale().x;
+        ellipse.center = self.transform.transform_pos(ellipse.center);
+        ellipse.radius = self.transform.scale_vec(ellipse.radius);
         None
     }
 
     fn rect(&mut self, _index: &mut usize, rect: &mut RectShape) -> Option<()> {
-        let scale_factor = self.0.scale().length_sq();
-        rect.rect.min = self.0.transform_pos(rect.rect.min);
-        rect.rect.max = self.0.transform_pos(rect.rect.max);
-        rect.rounding.ne *= scale_factor;
-        rect.rounding.nw *= scale_factor;
-        rect.rounding.se *= scale_factor;
-        rect.rounding.sw *= scale_factor;
+        rect.rect = self.transform.transform_rect(&rect.rect);
+        rect.rounding = self.scale_rounding(rect.rounding);
         None
     }
 
     fn text(&mut self, _index: &mut usize, text: &mut TextShape) -> Option<()> {
-        text.pos = self.0.transform_pos(text.pos);
+        text.pos = self.transform.transform_pos(text.pos);
+        text.angle += self.transform.rotation();
+        let scale = self.transform.scale().x;
+        if !self.transform.is_similarity() || self.transform.determinant() < 0.0 {
+            self.approximated.push(ShapeType::Text);
+        }
+        if scale != 1.0 {
+            let mut job = (*text.galley.job).clone();
+            scale_layout_job(&mut job, scale);
+            text.galley = match self.fonts {
+                Some(fonts) => fonts.layout_job(job),
+                None => Arc::new(scale_galley(&text.galley, job, scale)),
+            };
+        }
         None
     }
 
     fn mesh(&mut self, _index: &mut usize, mesh: &mut Mesh) -> Option<()> {
         mesh.vertices
             .iter_mut()
-            .for_each(|v| v.pos = self.0.transform_pos(v.pos));
+            .for_each(|v| v.pos = self.transform.transform_pos(v.pos));
         None
     }
 
@@ -206,7 +445,7 @@ impl ShapeVisitor for TransformShape {
         quad_bezier
             .points
             .iter_mut()
-            .for_each(|p| *p = self.0.transform_pos(*p));
+            .for_each(|p| *p = self.transform.transform_pos(*p));
         None
     }
 
@@ -218,7 +457,206 @@ impl ShapeVisitor for TransformShape {
         cubic_bezier
             .points
             .iter_mut()
-            .for_each(|p| *p = self.0.transform_pos(*p));
+            .for_each(|p| *p = self.transform.transform_pos(*p));
         None
     }
+
+    fn paint_callback(&mut self, _index: &mut usize, callback: &mut PaintCallback) -> Option<()> {
+        if !self.transform.is_axis_aligned() {
+            self.approximated.push(ShapeType::Callback);
+        }
+        callback.rect = self.transform.transform_rect(&callback.rect);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::epaint::text::FontDefinitions;
+    use egui::{Color32, FontId};
+
+    fn stroke() -> Stroke {
+        Stroke::new(1.0, Color32::BLACK)
+    }
+
+    fn assert_near(a: Pos2, b: Pos2) {
+        assert!(a.distance(b) < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn non_uniform_scale_turns_circle_into_ellipse() {
+        let circle = Shape::circle_stroke(Pos2::new(1.0, 1.0), 1.0, stroke());
+        let (shape, approximated) =
+            Transform::from_scale(Vec2::new(2.0, 3.0)).transform_shape(&circle);
+        let Shape::Ellipse(ellipse) = shape else {
+            panic!("expected ellipse, got {:?}", shape);
+        };
+        assert_eq!(ellipse.center, Pos2::new(2.0, 3.0));
+        assert_eq!(ellipse.radius, Vec2::new(2.0, 3.0));
+        assert!(approximated.is_empty());
+    }
+
+    #[test]
+    fn uniform_scale_keeps_circle() {
+        let circle = Shape::circle_stroke(Pos2::new(1.0, 1.0), 1.0, stroke());
+        let (shape, _) = Transform::from_scale(Vec2::splat(2.0)).transform_shape(&circle);
+        let Shape::Circle(circle) = shape else {
+            panic!("expected circle, got {:?}", shape);
+        };
+        assert_eq!(circle.center, Pos2::new(2.0, 2.0));
+        assert_eq!(circle.radius, 2.0);
+    }
+
+    #[test]
+    fn non_uniform_scale_scales_both_ellipse_radii() {
+        let ellipse = Shape::ellipse_stroke(Pos2::ZERO, Vec2::new(1.0, 2.0), stroke());
+        let (shape, _) = Transform::from_scale(Vec2::new(3.0, 0.5)).transform_shape(&ellipse);
+        let Shape::Ellipse(ellipse) = shape else {
+            panic!("expected ellipse, got {:?}", shape);
+        };
+        assert_eq!(ellipse.radius, Vec2::new(3.0, 1.0));
+    }
+
+    #[test]
+    fn rotation_converts_ellipse_to_rotated_path() {
+        let ellipse = Shape::ellipse_stroke(Pos2::ZERO, Vec2::new(2.0, 1.0), stroke());
+        let (shape, approximated) =
+            Transform::from_rotation(std::f32::consts::FRAC_PI_2).transform_shape(&ellipse);
+        let Shape::Path(path) = shape else {
+            panic!("expected path, got {:?}", shape);
+        };
+        assert!(path.closed);
+        assert_eq!(path.points.len(), ELLIPSE_SEGMENTS);
+        for point in path.points {
+            assert!(((point.x / 1.0).powi(2) + (point.y / 2.0).powi(2) - 1.0).abs() < 1e-4);
+        }
+        assert!(approximated.is_empty());
+    }
+
+    #[test]
+    fn rotation_preserves_orientation() {
+        let transform = Transform::from_rotation(0.3);
+        assert!((transform.determinant() - 1.0).abs() < 1e-5);
+        assert!((transform.rotation() - 0.3).abs() < 1e-5);
+        assert!(transform.is_similarity());
+    }
+
+    #[test]
+    fn rotation_keeps_circle_and_moves_center() {
+        let circle = Shape::circle_stroke(Pos2::new(1.0, 0.0), 1.0, stroke());
+        let (shape, _) =
+            Transform::from_rotation(std::f32::consts::FRAC_PI_2).transform_shape(&circle);
+        let Shape::Circle(circle) = shape else {
+            panic!("expected circle, got {:?}", shape);
+        };
+        assert_near(circle.center, Pos2::new(0.0, 1.0));
+        assert!((circle.radius - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotation_converts_rect_to_path_and_flags_texture() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(2.0, 1.0));
+        let transform = Transform::from_rotation(std::f32::consts::FRAC_PI_2);
+        let (shape, approximated) =
+            transform.transform_shape(&Shape::rect_stroke(rect, 0.0, stroke()));
+        let Shape::Path(path) = shape else {
+            panic!("expected path, got {:?}", shape);
+        };
+        assert!(path.closed);
+        assert!(path
+            .points
+            .iter()
+            .any(|point| point.distance(Pos2::new(-1.0, 2.0)) < 1e-4));
+        assert!(approximated.is_empty());
+
+        let mut textured = RectShape::filled(rect, 0.0, Color32::WHITE);
+        textured.fill_texture_id = TextureId::User(1);
+        let (_, approximated) = transform.transform_shape(&Shape::Rect(textured));
+        assert_eq!(approximated, vec![ShapeType::Rect]);
+    }
+
+    #[test]
+    fn skew_converts_circle_to_skewed_path() {
+        let circle = Shape::circle_stroke(Pos2::ZERO, 1.0, stroke());
+        let transform = Transform::from_skew(Vec2::new(std::f32::consts::FRAC_PI_4, 0.0));
+        let (shape, _) = transform.transform_shape(&circle);
+        let Shape::Path(path) = shape else {
+            panic!("expected path, got {:?}", shape);
+        };
+        assert_eq!(path.points.len(), ELLIPSE_SEGMENTS);
+        assert_near(path.points[0], Pos2::new(1.0, 0.0));
+        assert_near(path.points[ELLIPSE_SEGMENTS / 4], Pos2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn uniform_scale_scales_rounding_linearly() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0));
+        let rounded = Shape::rect_stroke(rect, Rounding::same(1.0), stroke());
+        let (shape, _) = Transform::from_scale(Vec2::splat(3.0)).transform_shape(&rounded);
+        let Shape::Rect(rect) = shape else {
+            panic!("expected rect, got {:?}", shape);
+        };
+        assert_eq!(rect.rounding, Rounding::same(3.0));
+    }
+
+    #[test]
+    fn mirroring_swaps_rounding_corners() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0));
+        let rounding = Rounding {
+            nw: 1.0,
+            ne: 2.0,
+            sw: 3.0,
+            se: 4.0,
+        };
+        let (shape, _) = Transform::from_scale(Vec2::new(-1.0, 1.0))
+            .transform_shape(&Shape::rect_stroke(rect, rounding, stroke()));
+        let Shape::Rect(rect) = shape else {
+            panic!("expected rect, got {:?}", shape);
+        };
+        assert_eq!(
+            rect.rounding,
+            Rounding {
+                nw: 2.0,
+                ne: 1.0,
+                sw: 4.0,
+                se: 3.0,
+            }
+        );
+    }
+
+    #[test]
+    fn non_uniform_scale_converts_rounded_rect_to_path() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::splat(10.0));
+        let transform = Transform::from_scale(Vec2::new(2.0, 1.0));
+        let (shape, _) =
+            transform.transform_shape(&Shape::rect_stroke(rect, Rounding::same(2.0), stroke()));
+        let Shape::Path(path) = shape else {
+            panic!("expected path, got {:?}", shape);
+        };
+        let bounds = Rect::from_points(&path.points);
+        assert_near(bounds.min, Pos2::ZERO);
+        assert_near(bounds.max, Pos2::new(20.0, 10.0));
+        assert!(!path.points.contains(&Pos2::new(20.0, 10.0)));
+
+        let (shape, _) = transform.transform_shape(&Shape::rect_stroke(rect, 0.0, stroke()));
+        assert!(matches!(shape, Shape::Rect(rect) if rect.rect.max == Pos2::new(20.0, 10.0)));
+    }
+
+    #[test]
+    fn scale_scales_text_font_size_without_fonts() {
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        let galley =
+            fonts.layout_no_wrap("Text".into(), FontId::proportional(10.0), Color32::BLACK);
+        let width = galley.rect.width();
+        let text = Shape::galley(Pos2::new(1.0, 1.0), galley, Color32::BLACK);
+        let (shape, approximated) = Transform::from_scale(Vec2::splat(2.0)).transform_shape(&text);
+        let Shape::Text(text) = shape else {
+            panic!("expected text, got {:?}", shape);
+        };
+        assert_eq!(text.pos, Pos2::new(2.0, 2.0));
+        assert_eq!(text.galley.job.sections[0].format.font_id.size, 20.0);
+        assert!((text.galley.rect.width() - width * 2.0).abs() < 1e-4);
+        assert!(approximated.is_empty());
+    }
 }
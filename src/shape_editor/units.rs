@@ -0,0 +1,145 @@
+use egui::DragValue;
+use strum::EnumIter;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, EnumIter, strum::Display)]
+pub enum Unit {
+    #[default]
+    #[strum(serialize = "px")]
+    Px,
+    #[strum(serialize = "mm")]
+    Mm,
+    #[strum(serialize = "cm")]
+    Cm,
+    #[strum(serialize = "in")]
+    In,
+    #[strum(serialize = "pt")]
+    Pt,
+}
+
+impl Unit {
+    fn per_inch(&self, dpi: f32) -> f32 {
+        match self {
+            Unit::Px => dpi,
+            Unit::Mm => 25.4,
+            Unit::Cm => 2.54,
+            Unit::In => 1.0,
+            Unit::Pt => 72.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UnitOptions {
+    pub unit: Unit,
+    pub dpi: f32,
+    pub precision: usize,
+}
+
+impl Default for UnitOptions {
+    fn default() -> Self {
+        Self {
+            unit: Unit::Px,
+            dpi: 96.0,
+            precision: 2,
+        }
+    }
+}
+
+impl UnitOptions {
+    pub fn pixels_per_unit(&self) -> f32 {
+        self.dpi / self.unit.per_inch(self.dpi)
+    }
+
+    pub fn to_unit(&self, pixels: f32) -> f32 {
+        pixels / self.pixels_per_unit()
+    }
+
+    pub fn from_unit(&self, value: f32) -> f32 {
+        value * self.pixels_per_unit()
+    }
+
+    pub fn format(&self, pixels: f32) -> String {
+        let formatted = format!("{:.*}", self.precision, self.to_unit(pixels));
+        let formatted = if formatted.contains('.') {
+            formatted.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &formatted
+        };
+        if formatted == "-0" {
+            "0".to_string()
+        } else {
+            formatted.to_string()
+        }
+    }
+
+    pub fn format_with_unit(&self, pixels: f32) -> String {
+        format!("{} {}", self.format(pixels), self.unit)
+    }
+
+    pub fn drag_value<'a>(&self, pixels: &'a mut f32) -> DragValue<'a> {
+        let pixels_per_unit = self.pixels_per_unit();
+        DragValue::from_get_set(move |value| {
+            if let Some(value) = value {
+                *pixels = value as f32 * pixels_per_unit;
+            }
+            (*pixels / pixels_per_unit) as f64
+        })
+        .speed(0.1)
+        .max_decimals(self.precision)
+        .suffix(format!(" {}", self.unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(unit: Unit) -> UnitOptions {
+        UnitOptions {
+            unit,
+            ..Default::default()
+        }
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn converts_pixels_at_dpi() {
+        assert_near(options(Unit::Px).to_unit(96.0), 96.0);
+        assert_near(options(Unit::In).to_unit(96.0), 1.0);
+        assert_near(options(Unit::Mm).to_unit(96.0), 25.4);
+        assert_near(options(Unit::Cm).to_unit(96.0), 2.54);
+        assert_near(options(Unit::Pt).to_unit(96.0), 72.0);
+        let high_dpi = UnitOptions {
+            unit: Unit::In,
+            dpi: 192.0,
+            ..Default::default()
+        };
+        assert_near(high_dpi.to_unit(96.0), 0.5);
+        assert_near(high_dpi.pixels_per_unit(), 192.0);
+    }
+
+    #[test]
+    fn conversion_round_trips() {
+        for unit in [Unit::Px, Unit::Mm, Unit::Cm, Unit::In, Unit::Pt] {
+            let options = options(unit);
+            assert_near(options.from_unit(options.to_unit(123.0)), 123.0);
+        }
+    }
+
+    #[test]
+    fn format_trims_trailing_zeros_and_negative_zero() {
+        let options = options(Unit::In);
+        assert_eq!(options.format(96.0), "1");
+        assert_eq!(options.format(144.0), "1.5");
+        assert_eq!(options.format(100.0), "1.04");
+        assert_eq!(options.format(-0.1), "0");
+        assert_eq!(options.format_with_unit(48.0), "0.5 in");
+        assert_eq!(UnitOptions::default().format_with_unit(10.0), "10 px");
+    }
+}
@@ -1,16 +1,24 @@
-use egui::ahash::{HashMap, HashSet};
-use egui::{Rangef, Rect};
+use egui::{Pos2, Rect};
 use std::collections::{BTreeMap, BTreeSet};
 use std::hash::Hash;
 
-pub fn grid_step(scale: f32) -> f32 {
-    50f32 * 5f32.powi(-scale.log(5.0).round() as i32)
+pub fn grid_step(base_step: f32, scale: f32, progression: f32) -> f32 {
+    base_step * progression.powi(-scale.log(progression).round() as i32)
 }
 
-pub fn step_by(range: Rangef, step: f32) -> impl Iterator<Item = f32> {
-    let min = (range.min / step).floor() as i32;
-    let max = (range.max / step).ceil() as i32;
-    (min..max).map(move |i| i as f32 * step)
+pub fn nice_grid_step(base_step: f32, scale: f32) -> f32 {
+    let step = base_step / scale;
+    let magnitude = 10.0f32.powf(step.log10().floor());
+    let normalized = step / magnitude;
+    let nice = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .min_by(|a: &f32, b: &f32| {
+            (normalized.ln() - a.ln())
+                .abs()
+                .total_cmp(&(normalized.ln() - b.ln()).abs())
+        })
+        .unwrap_or(1.0);
+    nice * magnitude
 }
 
 pub fn normalize_rect(rect: &Rect) -> Rect {
@@ -28,20 +36,27 @@ pub fn normalize_rect(rect: &Rect) -> Rect {
     rect
 }
 
-pub fn map_grouped_by<'a, T, K, V, F, I>(iter: I, mut key_value_fn: F) -> HashMap<K, HashSet<V>>
-where
-    T: 'a,
-    I: Iterator<Item = &'a T>,
-    F: FnMut(&T) -> (K, V),
-    K: PartialEq + Eq + Hash,
-    V: PartialEq + Eq + Hash,
-{
-    let mut grouped: HashMap<K, HashSet<V>> = Default::default();
-    for item in iter {
-        let (key, value) = key_value_fn(item);
-        grouped.entry(key).or_default().insert(value);
+pub fn closest_point_on_segment(start: Pos2, end: Pos2, pos: Pos2) -> Pos2 {
+    let segment = end - start;
+    let length_sq = segment.length_sq();
+    if length_sq == 0.0 {
+        return start;
     }
-    grouped
+    let t = ((pos - start).dot(segment) / length_sq).clamp(0.0, 1.0);
+    start + segment * t
+}
+
+pub fn segments_intersection(start1: Pos2, end1: Pos2, start2: Pos2, end2: Pos2) -> Option<Pos2> {
+    let segment1 = end1 - start1;
+    let segment2 = end2 - start2;
+    let denominator = segment1.x * segment2.y - segment1.y * segment2.x;
+    if denominator.abs() <= f32::EPSILON * segment1.length() * segment2.length() {
+        return None;
+    }
+    let offset = start2 - start1;
+    let t = (offset.x * segment2.y - offset.y * segment2.x) / denominator;
+    let u = (offset.x * segment1.y - offset.y * segment1.x) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| start1 + segment1 * t)
 }
 
 pub fn b_tree_map_grouped_by<'a, T, K, V, F, I>(
@@ -62,3 +77,99 @@ where
     }
     grouped
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nice_grid_step_picks_one_two_five() {
+        assert_eq!(nice_grid_step(50.0, 1.0), 50.0);
+        assert_eq!(nice_grid_step(50.0, 3.0), 20.0);
+        assert_eq!(nice_grid_step(13.0, 1.0), 10.0);
+        assert_eq!(nice_grid_step(1.0, 8.0), 0.1);
+        assert_eq!(nice_grid_step(50.0, 0.08), 500.0);
+    }
+
+    #[test]
+    fn grid_step_snaps_scale_to_progression() {
+        assert_eq!(grid_step(50.0, 1.0, 2.0), 50.0);
+        assert_eq!(grid_step(50.0, 3.0, 2.0), 12.5);
+        assert_eq!(grid_step(50.0, 0.3, 2.0), 200.0);
+    }
+
+    #[test]
+    fn closest_point_is_clamped_to_segment() {
+        let start = Pos2::new(0.0, 0.0);
+        let end = Pos2::new(10.0, 0.0);
+        assert_eq!(
+            closest_point_on_segment(start, end, Pos2::new(4.0, 3.0)),
+            Pos2::new(4.0, 0.0)
+        );
+        assert_eq!(
+            closest_point_on_segment(start, end, Pos2::new(-5.0, 1.0)),
+            start
+        );
+        assert_eq!(
+            closest_point_on_segment(start, end, Pos2::new(15.0, -1.0)),
+            end
+        );
+        assert_eq!(
+            closest_point_on_segment(start, start, Pos2::new(1.0, 1.0)),
+            start
+        );
+    }
+
+    #[test]
+    fn crossing_segments_intersect() {
+        assert_eq!(
+            segments_intersection(
+                Pos2::new(0.0, 0.0),
+                Pos2::new(10.0, 10.0),
+                Pos2::new(0.0, 10.0),
+                Pos2::new(10.0, 0.0)
+            ),
+            Some(Pos2::new(5.0, 5.0))
+        );
+        assert_eq!(
+            segments_intersection(
+                Pos2::new(0.0, 0.0),
+                Pos2::new(10.0, 0.0),
+                Pos2::new(10.0, 0.0),
+                Pos2::new(10.0, 5.0)
+            ),
+            Some(Pos2::new(10.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn disjoint_and_parallel_segments_do_not_intersect() {
+        assert_eq!(
+            segments_intersection(
+                Pos2::new(0.0, 0.0),
+                Pos2::new(4.0, 4.0),
+                Pos2::new(0.0, 10.0),
+                Pos2::new(10.0, 0.0)
+            ),
+            None
+        );
+        assert_eq!(
+            segments_intersection(
+                Pos2::new(0.0, 0.0),
+                Pos2::new(10.0, 0.0),
+                Pos2::new(0.0, 1.0),
+                Pos2::new(10.0, 1.0)
+            ),
+            None
+        );
+        assert_eq!(
+            segments_intersection(
+                Pos2::new(0.0, 0.0),
+                Pos2::new(10.0, 0.0),
+                Pos2::new(5.0, 0.0),
+                Pos2::new(15.0, 0.0)
+            ),
+            None
+        );
+    }
+}